@@ -0,0 +1,64 @@
+// Spawns 100k entities, runs a movement system over them through a SceneLayer and despawns
+// them again. Run with `cargo bench --bench ecs`.
+use std::time::{Duration, Instant};
+
+use hexgem_engine::{
+    HexgemEcs::{SceneLayer, World},
+    HexgemMath::{Transform2D, Vec2},
+    Timestep,
+};
+
+const ENTITY_COUNT: usize = 100_000;
+const FRAMES: u32 = 100;
+
+struct Velocity(Vec2);
+
+fn report(name: &str, elapsed: Duration, iterations: u32) {
+    let per_iteration = elapsed / iterations;
+    println!(
+        "{:<24} {:>10.3} ms total {:>10.3} ms/iter",
+        name,
+        elapsed.as_secs_f64() * 1000.0,
+        per_iteration.as_secs_f64() * 1000.0
+    );
+}
+
+fn main() {
+    let mut layer = SceneLayer::create("Benchmark");
+    layer.add_system("movement", |world: &mut World, timestep: Timestep| {
+        for (transform, velocity) in world.query::<(&mut Transform2D, &Velocity)>().iter() {
+            transform.translation += velocity.0 * timestep.get_seconds();
+        }
+    });
+
+    let start = Instant::now();
+    let entities: Vec<_> = (0..ENTITY_COUNT)
+        .map(|index| {
+            layer.get_world_mut().spawn_with((
+                Transform2D::default(),
+                Velocity(Vec2::new(index as f32, 1.0)),
+            ))
+        })
+        .collect();
+    report("spawn 100k", start.elapsed(), 1);
+
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        layer.update(Timestep::create(1.0 / 60.0));
+    }
+    report("update 100k", start.elapsed(), FRAMES);
+
+    let start = Instant::now();
+    let mut count = 0;
+    for _ in 0..FRAMES {
+        count += layer.get_world().query::<&Transform2D>().count();
+    }
+    report("read query 100k", start.elapsed(), FRAMES);
+    assert_eq!(count, ENTITY_COUNT * FRAMES as usize);
+
+    let start = Instant::now();
+    for entity in entities {
+        layer.get_world_mut().despawn(entity);
+    }
+    report("despawn 100k", start.elapsed(), 1);
+}
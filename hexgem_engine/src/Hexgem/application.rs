@@ -1,52 +1,85 @@
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    rc::Rc,
+    time::Instant,
+};
+
 use log::{error, info, warn};
 
-use crate::HexgemEvent::{
-    Event, EventDispatcher, EventType, MouseButtonEvent, MouseMoveEvent, WindowCloseEvent,
+use crate::{
+    HexgemEvent::{
+        Event, EventDispatcher, EventType, MouseButtonEvent, MouseMoveEvent, WindowCloseEvent,
+        WindowResizeEvent,
+    },
+    HexgemMath::Vec4,
+    HexgemRenderer::{OpenGlRendererApi, RenderCommand, Renderer2D, SoftwareRendererApi},
 };
 
 use super::{
+    asset::HexgemAsset::{AssetManager, SharedAssets},
+    error::HexgemResult,
+    frame_capture::FrameCapture,
     layer::Layer,
-    layer_stack::LayerStack,
+    layer_stack::{LayerStack, LayerStates},
+    log::CrashReporter,
     platform::HexgemWindow::*,
-    window::{Window, WindowProps},
+    timestep::Timestep,
+    window::{Window, WindowBackend, WindowProps},
 };
 
 pub trait HexgemApp: Sized {
-    fn application() -> Application;
-    fn run<T>(&self, mut callback: T)
+    fn application() -> HexgemResult<Application>;
+    fn run<T>(&self, mut callback: T) -> HexgemResult<()>
     where
         T: FnMut(&mut Application),
     {
-        let mut application = Self::application();
+        let mut application = Self::application()?;
         callback(&mut application);
         application.run(self);
+        Ok(())
     }
 }
 pub struct Application {
     pub window: Option<Box<dyn Window>>,
     layer_stack: LayerStack,
+    assets: SharedAssets,
     running: bool,
 }
 
 impl Application {
-    pub fn create() -> Self {
-        let window = {
-            let props = WindowProps::default();
-            #[cfg(not(target_os = "macos"))]
-            {
-                Some(GlfwWindow::create(props))
+    pub fn create() -> HexgemResult<Self> {
+        Self::with_backend(WindowBackend::default(), WindowProps::default())
+    }
+
+    pub fn with_backend(backend: WindowBackend, props: WindowProps) -> HexgemResult<Self> {
+        let window: Box<dyn Window> = match backend {
+            WindowBackend::Glfw => {
+                let window = GlfwWindow::create(props)?;
+                RenderCommand::init(OpenGlRendererApi::create());
+                window
             }
-            #[cfg(target_os = "macos")]
-            {
-                Some(SdlWindow::create(props))
+            WindowBackend::Sdl => {
+                let window = SdlWindow::create(props)?;
+                RenderCommand::init(OpenGlRendererApi::create());
+                window
+            }
+            WindowBackend::Headless { frame_limit } => {
+                let mut window = HeadlessWindow::create_headless(props)?;
+                window.set_frame_limit(frame_limit);
+                RenderCommand::init(SoftwareRendererApi::create(window.get_surface().clone()));
+                Box::new(window)
             }
         };
+        RenderCommand::set_clear_color(Vec4::new(0.13, 0.15, 0.18, 1.0));
+        RenderCommand::set_viewport(0, 0, window.get_width() as u32, window.get_height() as u32);
+        Renderer2D::init()?;
         let layer_stack = LayerStack::create();
-        Self {
+        Ok(Self {
             layer_stack,
+            assets: Rc::new(RefCell::new(AssetManager::create())),
             running: true,
-            window,
-        }
+            window: Some(window),
+        })
     }
 
     pub fn push_layer<T>(&mut self, mut layer: T)
@@ -67,6 +100,7 @@ impl Application {
 
     fn on_event(&mut self) -> impl FnMut(Box<dyn Event>, Box<&mut dyn Window>) + '_ {
         return |mut event: Box<dyn Event>, window: Box<&mut dyn Window>| {
+            CrashReporter::record_event(&event);
             let mut handle_vector: Vec<bool> = vec![];
 
             {
@@ -94,16 +128,21 @@ impl Application {
                         None
                     },
                 ));
+                event_dispatcher.dispatch::<WindowResizeEvent, _>(EventType::WindowResize, |e| {
+                    let (width, height) = (e.size.width.max(0), e.size.height.max(0));
+                    RenderCommand::set_viewport(0, 0, width as u32, height as u32);
+                    Some(false)
+                });
             }
             *event.handled() = handle_vector.contains(&true);
-            let mut layers = self.layer_stack.layers();
+            FrameCapture::on_event(event.as_ref());
+            let mut layers = self.layer_stack.layers_rev();
 
-            for layer in layers.iter_mut().rev() {
+            for layer in layers.iter_mut() {
                 if event.is_handled() {
-                    info!("HANDLED ${}", event.is_handled());
-
                     break;
                 }
+                crate::profile_scope!(format!("{} on_event", layer.get_name()));
                 layer.on_event(&mut event, *window);
             }
         };
@@ -112,17 +151,51 @@ impl Application {
     fn run(&mut self, _app: &impl HexgemApp) {
         info!("Running app");
         self.window.take().map(|mut window| {
+            let mut last_frame = Instant::now();
             while self.running {
-                for layer in self.layer_stack.layers() {
-                    layer.on_update(&mut window);
+                crate::profile_scope!("Application frame");
+                let now = Instant::now();
+                let timestep = Timestep::from(now - last_frame);
+                last_frame = now;
+                Renderer2D::reset_stats();
+                RenderCommand::clear();
+                {
+                    crate::profile_scope!("Layers on_update");
+                    for layer in self.layer_stack.layers() {
+                        crate::profile_scope!(format!("{} on_update", layer.get_name()));
+                        layer.on_update(&mut window, timestep);
+                    }
+                }
+                if let Err(err) = FrameCapture::on_frame_end(window.as_ref()) {
+                    error!("{}", err);
+                }
+                {
+                    crate::profile_scope!("Window on_update");
+                    let mut callback = self.on_event();
+                    window.on_update(&mut callback);
                 }
-                let mut callback = self.on_event();
-                window.on_update(&mut callback);
             }
             self.window = Some(window);
         });
     }
 
+    pub fn layer_states(&self) -> LayerStates {
+        self.layer_stack.states()
+    }
+
+    // Hand this to layers that load assets, they share the manager with the application.
+    pub fn assets(&self) -> SharedAssets {
+        Rc::clone(&self.assets)
+    }
+
+    pub fn get_assets(&self) -> Ref<'_, AssetManager> {
+        self.assets.borrow()
+    }
+
+    pub fn get_assets_mut(&mut self) -> RefMut<'_, AssetManager> {
+        self.assets.borrow_mut()
+    }
+
     pub fn get_window(&self) -> &Option<Box<dyn Window>> {
         &self.window
     }
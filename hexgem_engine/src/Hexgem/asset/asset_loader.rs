@@ -0,0 +1,97 @@
+use std::{borrow::Cow, cell::RefCell, path::Path, rc::Rc};
+
+use crate::Hexgem::error::HexgemResult;
+
+use super::{
+    asset_manager::AssetManager,
+    asset_source::{normalize_path, AssetSource},
+};
+
+// Turns the bytes behind a path into an asset. Loaders are picked by extension, the last one
+// registered for an extension wins, so the built in loaders can be replaced.
+pub trait AssetLoader<T: ?Sized> {
+    // Lowercase and without the leading dot.
+    fn get_extensions(&self) -> &[&str];
+    fn load(&self, context: &LoadContext) -> HexgemResult<Box<T>>;
+}
+
+// Releases the reference an asset holds on one of its dependencies.
+pub(crate) type Dependency = Box<dyn FnOnce(&mut AssetManager)>;
+
+pub struct LoadContext<'a> {
+    path: &'a str,
+    source: Rc<AssetSource>,
+    manager: RefCell<&'a mut AssetManager>,
+    dependencies: RefCell<Vec<Dependency>>,
+}
+
+impl<'a> LoadContext<'a> {
+    pub(crate) fn create(path: &'a str, manager: &'a mut AssetManager) -> Self {
+        Self {
+            path,
+            source: manager.get_shared_source(),
+            manager: RefCell::new(manager),
+            dependencies: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn get_path(&self) -> &'a str {
+        self.path
+    }
+
+    // File name without the extension.
+    pub fn get_name(&self) -> String {
+        Path::new(self.path)
+            .file_stem()
+            .map_or(String::new(), |stem| stem.to_string_lossy().to_string())
+    }
+
+    pub fn get_source(&self) -> &AssetSource {
+        &self.source
+    }
+
+    pub fn read(&self) -> HexgemResult<Cow<'_, [u8]>> {
+        self.source.read(self.path)
+    }
+
+    pub fn read_to_string(&self) -> HexgemResult<String> {
+        self.source.read_to_string(self.path)
+    }
+
+    // For files an asset refers to, like a font page or a shader include.
+    pub fn resolve(&self, relative: &str) -> String {
+        resolve_path(self.path, relative)
+    }
+
+    pub fn read_relative(&self, relative: &str) -> HexgemResult<Cow<'_, [u8]>> {
+        self.source.read(&self.resolve(relative))
+    }
+
+    // Loads an asset this one is built from through the manager, so it is shared with anything
+    // else loading the same path. The reference taken is released when this asset is unloaded.
+    pub fn load<T: ?Sized + 'static>(&self, relative: &str) -> HexgemResult<Rc<T>> {
+        let mut manager = self.manager.borrow_mut();
+        let handle = manager.load::<T>(&self.resolve(relative))?;
+        self.dependencies
+            .borrow_mut()
+            .push(Box::new(move |manager: &mut AssetManager| {
+                let _ = manager.release(handle);
+            }));
+        Ok(manager
+            .get_shared(handle)
+            .expect("Dependency was just loaded"))
+    }
+
+    pub(crate) fn into_dependencies(self) -> Vec<Dependency> {
+        self.dependencies.into_inner()
+    }
+}
+
+pub(crate) fn resolve_path(from: &str, relative: &str) -> String {
+    match from.rfind('/') {
+        Some(end) if !relative.starts_with('/') => {
+            normalize_path(&format!("{}/{}", &from[..end], relative))
+        }
+        _ => normalize_path(relative),
+    }
+}
@@ -0,0 +1,483 @@
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    path::Path,
+    rc::Rc,
+};
+
+use log::debug;
+
+use crate::Hexgem::error::{HexgemError, HexgemResult};
+
+use super::{
+    asset_loader::{AssetLoader, Dependency, LoadContext},
+    asset_source::{asset_error, normalize_path, AssetSource},
+    asset_storage::{AnyAssetStorage, AssetStorage},
+    handle::{short_type_name, Handle},
+    loaders::{AudioLoader, FontLoader, SceneLoader, ShaderLoader, TextureLoader},
+};
+
+type LoaderList<T> = Vec<Rc<dyn AssetLoader<T>>>;
+
+// How the application shares its manager with layers.
+pub type SharedAssets = Rc<RefCell<AssetManager>>;
+
+// Loads assets by path and hands out typed handles. Loading a path that is already loaded
+// returns the same handle and bumps its reference count, release drops one reference and
+// unloads the asset once none are left.
+pub struct AssetManager {
+    source: Rc<AssetSource>,
+    storages: HashMap<TypeId, Box<dyn AnyAssetStorage>>,
+    loaders: HashMap<TypeId, Box<dyn Any>>,
+    // Assets being loaded, to catch assets that end up depending on themselves.
+    loading: Vec<(TypeId, String)>,
+}
+
+impl Default for AssetManager {
+    fn default() -> Self {
+        Self::with_source(AssetSource::default())
+    }
+}
+
+impl AssetManager {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn with_source(source: AssetSource) -> Self {
+        let mut manager = Self {
+            source: Rc::new(source),
+            storages: HashMap::new(),
+            loaders: HashMap::new(),
+            loading: vec![],
+        };
+        manager.register_loader(TextureLoader::default());
+        manager.register_loader(ShaderLoader);
+        manager.register_loader(FontLoader::default());
+        manager.register_loader(AudioLoader);
+        manager.register_loader(SceneLoader::default());
+        manager
+    }
+
+    pub fn get_source(&self) -> &AssetSource {
+        &self.source
+    }
+
+    // Assets that are already loaded stay as they are, only later loads use the new source.
+    pub fn set_source(&mut self, source: AssetSource) {
+        self.source = Rc::new(source);
+    }
+
+    pub(crate) fn get_shared_source(&self) -> Rc<AssetSource> {
+        Rc::clone(&self.source)
+    }
+
+    // Takes precedence over loaders registered earlier for the same extensions.
+    pub fn register_loader<T, L>(&mut self, loader: L)
+    where
+        T: ?Sized + 'static,
+        L: AssetLoader<T> + 'static,
+    {
+        self.loaders
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(LoaderList::<T>::new()))
+            .downcast_mut::<LoaderList<T>>()
+            .expect("Loader list type mismatch")
+            .push(Rc::new(loader));
+    }
+
+    pub fn load<T: ?Sized + 'static>(&mut self, path: &str) -> HexgemResult<Handle<T>> {
+        let path = normalize_path(path);
+        if let Some(handle) = self.find::<T>(&path) {
+            if let Some(slot) = self.get_storage_mut::<T>().get_slot_mut(handle) {
+                slot.ref_count += 1;
+            }
+            return Ok(handle);
+        }
+        let (asset, dependencies) = self.load_asset::<T>(&path)?;
+        debug!("Loaded {} {}", short_type_name::<T>(), path);
+        Ok(self
+            .get_storage_mut::<T>()
+            .insert(Some(path), asset, dependencies))
+    }
+
+    // Adds an asset built in code. Later loads of the path return it instead of reading the
+    // source.
+    pub fn add<T: ?Sized + 'static>(
+        &mut self,
+        path: &str,
+        asset: Box<T>,
+    ) -> HexgemResult<Handle<T>> {
+        let path = normalize_path(path);
+        if self.find::<T>(&path).is_some() {
+            return Err(asset_error(&path, "is already loaded"));
+        }
+        Ok(self
+            .get_storage_mut::<T>()
+            .insert(Some(path), Rc::from(asset), vec![]))
+    }
+
+    // Looks up a loaded asset without taking a reference.
+    pub fn find<T: ?Sized + 'static>(&self, path: &str) -> Option<Handle<T>> {
+        self.get_storage::<T>()?.find(&normalize_path(path))
+    }
+
+    // Takes another reference, for when a handle is shared by several owners.
+    pub fn acquire<T: ?Sized + 'static>(&mut self, handle: Handle<T>) -> HexgemResult<u32> {
+        let slot = self
+            .get_storage_mut::<T>()
+            .get_slot_mut(handle)
+            .ok_or_else(|| not_loaded(handle))?;
+        slot.ref_count += 1;
+        Ok(slot.ref_count)
+    }
+
+    // Returns whether this was the last reference and the asset got unloaded, releasing the
+    // assets it was loaded from in turn. Anyone holding the asset through get_shared keeps it
+    // alive until they drop it.
+    pub fn release<T: ?Sized + 'static>(&mut self, handle: Handle<T>) -> HexgemResult<bool> {
+        let storage = self.get_storage_mut::<T>();
+        let slot = storage
+            .get_slot_mut(handle)
+            .ok_or_else(|| not_loaded(handle))?;
+        slot.ref_count -= 1;
+        if slot.ref_count > 0 {
+            return Ok(false);
+        }
+        if let Some(path) = slot.path.as_ref() {
+            debug!("Unloaded {} {}", short_type_name::<T>(), path);
+        }
+        let dependencies = std::mem::take(&mut slot.dependencies);
+        storage.remove(handle);
+        self.release_dependencies(dependencies);
+        Ok(true)
+    }
+
+    // Reads the asset again from the source, keeping its handle and reference count.
+    pub fn reload<T: ?Sized + 'static>(&mut self, handle: Handle<T>) -> HexgemResult<()> {
+        let path = self
+            .get_path(handle)
+            .ok_or_else(|| not_loaded(handle))?
+            .to_string();
+        let (asset, dependencies) = self.load_asset::<T>(&path)?;
+        // The old dependencies go after the new ones are loaded, so shared ones stay loaded.
+        let old_dependencies = match self.get_storage_mut::<T>().get_slot_mut(handle) {
+            Some(slot) => {
+                slot.asset = Some(asset);
+                std::mem::replace(&mut slot.dependencies, dependencies)
+            }
+            None => dependencies,
+        };
+        self.release_dependencies(old_dependencies);
+        Ok(())
+    }
+
+    pub fn get<T: ?Sized + 'static>(&self, handle: Handle<T>) -> Option<&T> {
+        self.get_storage::<T>()?.get_slot(handle)?.asset.as_deref()
+    }
+
+    // None while the asset is shared through get_shared.
+    pub fn get_mut<T: ?Sized + 'static>(&mut self, handle: Handle<T>) -> Option<&mut T> {
+        Rc::get_mut(
+            self.get_storage_mut::<T>()
+                .get_slot_mut(handle)?
+                .asset
+                .as_mut()?,
+        )
+    }
+
+    // For things that need to own their asset, like a SubTexture2D.
+    pub fn get_shared<T: ?Sized + 'static>(&self, handle: Handle<T>) -> Option<Rc<T>> {
+        self.get_storage::<T>()?.get_slot(handle)?.asset.clone()
+    }
+
+    pub fn get_path<T: ?Sized + 'static>(&self, handle: Handle<T>) -> Option<&str> {
+        self.get_storage::<T>()?.get_slot(handle)?.path.as_deref()
+    }
+
+    // Zero once the asset is unloaded.
+    pub fn get_ref_count<T: ?Sized + 'static>(&self, handle: Handle<T>) -> u32 {
+        self.get_storage::<T>()
+            .and_then(|storage| storage.get_slot(handle))
+            .map_or(0, |slot| slot.ref_count)
+    }
+
+    pub fn is_loaded<T: ?Sized + 'static>(&self, handle: Handle<T>) -> bool {
+        self.get_storage::<T>()
+            .is_some_and(|storage| storage.get_slot(handle).is_some())
+    }
+
+    pub fn get_loaded_count<T: ?Sized + 'static>(&self) -> usize {
+        self.storages
+            .get(&TypeId::of::<T>())
+            .map_or(0, |storage| storage.get_loaded_count())
+    }
+
+    // Unloads every asset regardless of reference counts, all handles become invalid.
+    pub fn clear(&mut self) {
+        for storage in self.storages.values_mut() {
+            storage.clear();
+        }
+    }
+
+    fn load_asset<T: ?Sized + 'static>(
+        &mut self,
+        path: &str,
+    ) -> HexgemResult<(Rc<T>, Vec<Dependency>)> {
+        crate::profile_scope!(format!("Load asset {}", path));
+        let extension = Path::new(path)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let loader = self
+            .loaders
+            .get(&TypeId::of::<T>())
+            .and_then(|loaders| loaders.downcast_ref::<LoaderList<T>>())
+            .and_then(|loaders| {
+                loaders
+                    .iter()
+                    .rev()
+                    .find(|loader| loader.get_extensions().contains(&extension.as_str()))
+                    .cloned()
+            })
+            .ok_or_else(|| {
+                asset_error(
+                    path,
+                    format!(
+                        "no loader for .{} files as {}",
+                        extension,
+                        short_type_name::<T>()
+                    ),
+                )
+            })?;
+        let key = (TypeId::of::<T>(), path.to_string());
+        if self.loading.contains(&key) {
+            return Err(asset_error(path, "depends on itself"));
+        }
+        self.loading.push(key);
+        let context = LoadContext::create(path, self);
+        let asset = loader.load(&context);
+        let dependencies = context.into_dependencies();
+        self.loading.pop();
+        match asset {
+            Ok(asset) => Ok((Rc::from(asset), dependencies)),
+            Err(err) => {
+                self.release_dependencies(dependencies);
+                Err(match err {
+                    HexgemError::Asset { .. } => err,
+                    err => asset_error(path, err),
+                })
+            }
+        }
+    }
+
+    fn release_dependencies(&mut self, dependencies: Vec<Dependency>) {
+        for release in dependencies {
+            release(self);
+        }
+    }
+
+    fn get_storage<T: ?Sized + 'static>(&self) -> Option<&AssetStorage<T>> {
+        self.storages
+            .get(&TypeId::of::<T>())
+            .and_then(|storage| storage.as_any().downcast_ref::<AssetStorage<T>>())
+    }
+
+    fn get_storage_mut<T: ?Sized + 'static>(&mut self) -> &mut AssetStorage<T> {
+        self.storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(AssetStorage::<T>::default()))
+            .as_any_mut()
+            .downcast_mut::<AssetStorage<T>>()
+            .expect("Asset storage type mismatch")
+    }
+}
+
+fn not_loaded<T: ?Sized>(handle: Handle<T>) -> HexgemError {
+    asset_error(&format!("{:?}", handle), "is not loaded")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::Hexgem::{
+        ecs::HexgemEcs::Entity,
+        pack::HexgemPack::{Pack, PackBuilder},
+        renderer::HexgemRenderer::{ImageData, NullRendererApi, RenderCommand, Texture2D},
+        scene::HexgemScene::{Scene, SceneSerializer, SpriteRenderer},
+    };
+
+    struct Text(String);
+
+    struct TextLoader;
+
+    impl AssetLoader<Text> for TextLoader {
+        fn get_extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+
+        fn load(&self, context: &LoadContext) -> HexgemResult<Box<Text>> {
+            Ok(Box::new(Text(context.read_to_string()?)))
+        }
+    }
+
+    // One path per line, each loaded as a Text dependency. Nested bundles are flattened.
+    struct Bundle(Vec<Rc<Text>>);
+
+    struct BundleLoader;
+
+    impl AssetLoader<Bundle> for BundleLoader {
+        fn get_extensions(&self) -> &[&str] {
+            &["bundle"]
+        }
+
+        fn load(&self, context: &LoadContext) -> HexgemResult<Box<Bundle>> {
+            let mut parts = vec![];
+            for line in context.read_to_string()?.lines() {
+                if line.ends_with(".bundle") {
+                    parts.extend(context.load::<Bundle>(line)?.0.iter().cloned());
+                } else {
+                    parts.push(context.load::<Text>(line)?);
+                }
+            }
+            Ok(Box::new(Bundle(parts)))
+        }
+    }
+
+    fn manager(files: &[(&str, &[u8])]) -> AssetManager {
+        let mut builder = PackBuilder::create();
+        for (name, data) in files {
+            builder.add(name, data.to_vec()).unwrap();
+        }
+        let mut cursor = Cursor::new(vec![]);
+        builder.write(&mut cursor).unwrap();
+        let pack = Pack::from_bytes("assets.hxpk", cursor.into_inner()).unwrap();
+        let mut manager = AssetManager::with_source(AssetSource::Pack(pack));
+        manager.register_loader(TextLoader);
+        manager.register_loader(BundleLoader);
+        manager
+    }
+
+    fn text_ref_count(manager: &AssetManager, path: &str) -> u32 {
+        manager
+            .find::<Text>(path)
+            .map_or(0, |handle| manager.get_ref_count(handle))
+    }
+
+    #[test]
+    fn loading_a_path_twice_shares_the_asset() {
+        let mut manager = manager(&[("a.txt", b"a")]);
+        let handle = manager.load::<Text>("a.txt").unwrap();
+        assert_eq!(manager.load::<Text>("./b/../a.txt").unwrap(), handle);
+        assert_eq!(manager.get_ref_count(handle), 2);
+        assert_eq!(manager.get_loaded_count::<Text>(), 1);
+        assert_eq!(manager.get(handle).unwrap().0, "a");
+
+        assert!(!manager.release(handle).unwrap());
+        assert!(manager.release(handle).unwrap());
+        assert!(!manager.is_loaded(handle));
+        assert_eq!(manager.get_ref_count(handle), 0);
+        assert!(manager.release(handle).is_err());
+
+        // The freed slot is reused, the stale handle stays invalid.
+        let reloaded = manager.load::<Text>("a.txt").unwrap();
+        assert_eq!(reloaded.get_index(), handle.get_index());
+        assert_ne!(reloaded, handle);
+        assert!(manager.get(handle).is_none());
+    }
+
+    #[test]
+    fn dependencies_are_shared_and_released_with_the_asset() {
+        let mut manager = manager(&[
+            ("a.txt", b"a"),
+            ("bundles/b.txt", b"b"),
+            ("bundles/level.bundle", b"../a.txt\nb.txt\n../a.txt"),
+        ]);
+        let a = manager.load::<Text>("a.txt").unwrap();
+        let bundle = manager.load::<Bundle>("bundles/level.bundle").unwrap();
+        assert_eq!(manager.get_ref_count(a), 3);
+        assert_eq!(text_ref_count(&manager, "bundles/b.txt"), 1);
+        let parts = &manager.get(bundle).unwrap().0;
+        assert!(Rc::ptr_eq(&parts[0], &manager.get_shared(a).unwrap()));
+        assert!(Rc::ptr_eq(&parts[0], &parts[2]));
+        assert_eq!(parts[1].0, "b");
+
+        manager.reload(bundle).unwrap();
+        assert_eq!(manager.get_ref_count(a), 3);
+        assert_eq!(text_ref_count(&manager, "bundles/b.txt"), 1);
+
+        assert!(manager.release(bundle).unwrap());
+        assert_eq!(manager.get_ref_count(a), 1);
+        assert_eq!(text_ref_count(&manager, "bundles/b.txt"), 0);
+        assert_eq!(manager.get_loaded_count::<Text>(), 1);
+    }
+
+    #[test]
+    fn failed_loads_release_their_dependencies() {
+        let mut manager = manager(&[
+            ("a.txt", b"a"),
+            ("broken.bundle", b"a.txt\nmissing.txt"),
+            ("loop.bundle", b"a.txt\nnested/inner.bundle"),
+            ("nested/inner.bundle", b"../loop.bundle"),
+        ]);
+        let err = manager.load::<Bundle>("broken.bundle").unwrap_err();
+        assert!(err.to_string().contains("missing.txt"), "{}", err);
+        assert_eq!(manager.get_loaded_count::<Text>(), 0);
+        assert!(manager.find::<Bundle>("broken.bundle").is_none());
+
+        let err = manager.load::<Bundle>("loop.bundle").unwrap_err();
+        assert!(err.to_string().contains("depends on itself"), "{}", err);
+        assert_eq!(manager.get_loaded_count::<Bundle>(), 0);
+        assert_eq!(manager.get_loaded_count::<Text>(), 0);
+    }
+
+    #[test]
+    fn scene_textures_load_relative_to_the_scene_through_the_manager() {
+        RenderCommand::init(NullRendererApi);
+        let mut scene = Scene::create();
+        let sprites: Vec<Entity> = (0..2)
+            .map(|index| scene.create_entity(&format!("sprite {}", index)))
+            .collect();
+        for entity in sprites.iter() {
+            scene
+                .get_world_mut()
+                .insert(
+                    *entity,
+                    SpriteRenderer::default().texture_path("../textures/crate.png"),
+                )
+                .unwrap();
+        }
+        let source = SceneSerializer::create().serialize(&scene).unwrap();
+        let png = ImageData::filled(2, 2, [255, 0, 0, 255])
+            .unwrap()
+            .encode_png()
+            .unwrap();
+        let mut manager = manager(&[
+            ("scenes/level.toml", source.as_bytes()),
+            ("textures/crate.png", &png),
+        ]);
+
+        let texture = manager.load::<Texture2D>("textures/crate.png").unwrap();
+        let level = manager.load::<Scene>("scenes/level.toml").unwrap();
+        assert_eq!(manager.get_ref_count(texture), 3);
+        let shared = manager.get_shared(texture).unwrap();
+        let world = manager.get(level).unwrap().get_world();
+        let mut query = world.query::<&SpriteRenderer>();
+        assert_eq!(query.count(), 2);
+        for sprite in query.iter() {
+            let sprite_texture = sprite.texture.as_ref().unwrap().get_texture();
+            assert!(Rc::ptr_eq(sprite_texture, &shared));
+            assert_eq!(
+                sprite.texture_path.as_deref(),
+                Some("../textures/crate.png")
+            );
+        }
+        drop(query);
+
+        assert!(manager.release(level).unwrap());
+        assert_eq!(manager.get_ref_count(texture), 1);
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    borrow::Cow,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::Hexgem::{
+    error::{HexgemError, HexgemResult},
+    pack::{is_valid_entry_name, HexgemPack::Pack},
+};
+
+// Where asset paths are read from. Paths always use '/' separators, so the same path works
+// against a directory during development and against the pack built from it.
+pub enum AssetSource {
+    Directory(PathBuf),
+    Pack(Pack),
+}
+
+impl Default for AssetSource {
+    fn default() -> Self {
+        AssetSource::Directory(PathBuf::from("."))
+    }
+}
+
+impl AssetSource {
+    pub fn directory<P: AsRef<Path>>(path: P) -> Self {
+        AssetSource::Directory(path.as_ref().to_path_buf())
+    }
+
+    pub fn pack<P: AsRef<Path>>(path: P) -> HexgemResult<Self> {
+        Ok(AssetSource::Pack(Pack::open(path)?))
+    }
+
+    pub fn exists(&self, path: &str) -> bool {
+        match self {
+            AssetSource::Directory(root) => is_valid_entry_name(path) && root.join(path).is_file(),
+            AssetSource::Pack(pack) => pack.contains(path),
+        }
+    }
+
+    pub fn read(&self, path: &str) -> HexgemResult<Cow<'_, [u8]>> {
+        match self {
+            // Same rule as pack entries, so nothing outside of the root can be read.
+            AssetSource::Directory(_) if !is_valid_entry_name(path) => {
+                Err(asset_error(path, "path escapes the asset directory"))
+            }
+            AssetSource::Directory(root) => fs::read(root.join(path))
+                .map(Cow::Owned)
+                .map_err(|err| asset_error(path, err)),
+            AssetSource::Pack(pack) => pack.read(path).map_err(|err| asset_error(path, err)),
+        }
+    }
+
+    pub fn read_to_string(&self, path: &str) -> HexgemResult<String> {
+        let bytes = self.read(path)?;
+        String::from_utf8(bytes.into_owned()).map_err(|err| asset_error(path, err))
+    }
+}
+
+// Collapses "." and ".." and turns '\' into '/', so different spellings of a path share the
+// same cache entry.
+pub fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut components: Vec<&str> = vec![];
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." if components.last().is_some_and(|last| *last != "..") => {
+                components.pop();
+            }
+            _ => components.push(component),
+        }
+    }
+    let normalized = components.join("/");
+    if path.starts_with('/') {
+        format!("/{}", normalized)
+    } else {
+        normalized
+    }
+}
+
+pub(crate) fn asset_error<E: ToString>(path: &str, err: E) -> HexgemError {
+    HexgemError::Asset {
+        path: path.to_string(),
+        message: err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directories_refuse_paths_outside_of_the_root() {
+        let dir = std::env::temp_dir().join(format!("hexgem_assets_{}", std::process::id()));
+        let root = dir.join("assets");
+        fs::create_dir_all(root.join("textures")).unwrap();
+        fs::write(root.join("textures/a.txt"), "a").unwrap();
+        let secret = dir.join("secret.txt");
+        fs::write(&secret, "secret").unwrap();
+
+        let source = AssetSource::directory(&root);
+        assert!(source.exists("textures/a.txt"));
+        assert_eq!(source.read_to_string("textures/a.txt").unwrap(), "a");
+        let absolute = secret.display().to_string();
+        for path in [
+            "../secret.txt",
+            "textures/../../secret.txt",
+            absolute.as_str(),
+        ] {
+            assert!(!source.exists(path), "{}", path);
+            assert!(source.read(path).is_err(), "{}", path);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
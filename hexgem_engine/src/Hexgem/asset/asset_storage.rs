@@ -0,0 +1,125 @@
+use std::{any::Any, collections::HashMap, rc::Rc};
+
+use super::{asset_loader::Dependency, handle::Handle};
+
+pub(crate) trait AnyAssetStorage {
+    fn get_loaded_count(&self) -> usize;
+    fn clear(&mut self);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+pub(crate) struct AssetSlot<T: ?Sized> {
+    pub(crate) asset: Option<Rc<T>>,
+    pub(crate) path: Option<String>,
+    pub(crate) ref_count: u32,
+    // Assets loaded while loading this one, released when it is unloaded.
+    pub(crate) dependencies: Vec<Dependency>,
+    generation: u32,
+}
+
+// Slots are reused once freed, bumping the generation so old handles stop resolving.
+pub(crate) struct AssetStorage<T: ?Sized> {
+    slots: Vec<AssetSlot<T>>,
+    free: Vec<u32>,
+    paths: HashMap<String, u32>,
+}
+
+impl<T: ?Sized> Default for AssetStorage<T> {
+    fn default() -> Self {
+        Self {
+            slots: vec![],
+            free: vec![],
+            paths: HashMap::new(),
+        }
+    }
+}
+
+impl<T: ?Sized> AssetStorage<T> {
+    pub(crate) fn insert(
+        &mut self,
+        path: Option<String>,
+        asset: Rc<T>,
+        dependencies: Vec<Dependency>,
+    ) -> Handle<T> {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.slots.push(AssetSlot {
+                    asset: None,
+                    path: None,
+                    ref_count: 0,
+                    dependencies: vec![],
+                    generation: 0,
+                });
+                self.slots.len() as u32 - 1
+            }
+        };
+        if let Some(path) = path.as_ref() {
+            self.paths.insert(path.clone(), index);
+        }
+        let slot = &mut self.slots[index as usize];
+        slot.asset = Some(asset);
+        slot.path = path;
+        slot.ref_count = 1;
+        slot.dependencies = dependencies;
+        Handle::create(index, slot.generation)
+    }
+
+    pub(crate) fn find(&self, path: &str) -> Option<Handle<T>> {
+        let index = *self.paths.get(path)?;
+        Some(Handle::create(index, self.slots[index as usize].generation))
+    }
+
+    pub(crate) fn get_slot(&self, handle: Handle<T>) -> Option<&AssetSlot<T>> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation && slot.asset.is_some())
+    }
+
+    pub(crate) fn get_slot_mut(&mut self, handle: Handle<T>) -> Option<&mut AssetSlot<T>> {
+        self.slots
+            .get_mut(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation && slot.asset.is_some())
+    }
+
+    pub(crate) fn remove(&mut self, handle: Handle<T>) -> Option<Rc<T>> {
+        let slot = self.get_slot_mut(handle)?;
+        let asset = slot.asset.take();
+        let path = slot.path.take();
+        slot.ref_count = 0;
+        slot.generation = slot.generation.wrapping_add(1);
+        if let Some(path) = path {
+            self.paths.remove(&path);
+        }
+        self.free.push(handle.index);
+        asset
+    }
+}
+
+impl<T: ?Sized + 'static> AnyAssetStorage for AssetStorage<T> {
+    fn get_loaded_count(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    fn clear(&mut self) {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if slot.asset.take().is_some() {
+                slot.path = None;
+                slot.ref_count = 0;
+                slot.dependencies.clear();
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(index as u32);
+            }
+        }
+        self.paths.clear();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -0,0 +1,59 @@
+use crate::Hexgem::error::{HexgemError, HexgemResult};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AudioFormat {
+    Wav,
+    Ogg,
+    Mp3,
+    Flac,
+}
+
+impl AudioFormat {
+    // Sniffs the format from the leading bytes rather than trusting the extension.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => {
+                Some(AudioFormat::Wav)
+            }
+            [b'O', b'g', b'g', b'S', ..] => Some(AudioFormat::Ogg),
+            [b'f', b'L', b'a', b'C', ..] => Some(AudioFormat::Flac),
+            [b'I', b'D', b'3', ..] => Some(AudioFormat::Mp3),
+            [0xFF, second, ..] if second & 0xE0 == 0xE0 => Some(AudioFormat::Mp3),
+            _ => None,
+        }
+    }
+}
+
+// Encoded audio kept as loaded, decoding is left to whatever plays it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioClip {
+    name: String,
+    format: AudioFormat,
+    data: Vec<u8>,
+}
+
+impl AudioClip {
+    pub fn from_bytes(name: &str, data: Vec<u8>) -> HexgemResult<Self> {
+        let format = AudioFormat::detect(&data).ok_or_else(|| HexgemError::Asset {
+            path: name.to_string(),
+            message: "unrecognized audio format, expected wav, ogg, mp3 or flac".to_string(),
+        })?;
+        Ok(Self {
+            name: name.to_string(),
+            format,
+            data,
+        })
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_format(&self) -> AudioFormat {
+        self.format
+    }
+
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+}
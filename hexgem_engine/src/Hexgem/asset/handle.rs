@@ -0,0 +1,73 @@
+use std::{
+    any::type_name,
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+// Typed reference to an asset owned by the AssetManager. Handles are plain ids, the generation
+// makes a handle to an unloaded asset stay invalid even after its slot is reused.
+pub struct Handle<T: ?Sized> {
+    pub(crate) index: u32,
+    pub(crate) generation: u32,
+    marker: PhantomData<fn() -> Box<T>>,
+}
+
+impl<T: ?Sized> Handle<T> {
+    pub(crate) fn create(index: u32, generation: u32) -> Self {
+        Self {
+            index,
+            generation,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn get_index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn get_generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+// Implemented by hand, deriving would require T itself to implement them.
+impl<T: ?Sized> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for Handle<T> {}
+
+impl<T: ?Sized> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T: ?Sized> Eq for Handle<T> {}
+
+impl<T: ?Sized> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T: ?Sized> Debug for Handle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Handle<{}>({}v{})",
+            short_type_name::<T>(),
+            self.index,
+            self.generation
+        )
+    }
+}
+
+pub(crate) fn short_type_name<T: ?Sized>() -> &'static str {
+    let name = type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
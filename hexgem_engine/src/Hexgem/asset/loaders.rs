@@ -0,0 +1,139 @@
+use crate::Hexgem::{
+    error::{HexgemError, HexgemResult},
+    renderer::HexgemRenderer::{
+        Font, FontOptions, ImageData, RenderCommand, Shader, ShaderSource, Texture2D,
+        TextureOptions,
+    },
+    scene::HexgemScene::{Scene, SceneSerializer},
+};
+
+use super::{
+    asset_loader::{resolve_path, AssetLoader, LoadContext},
+    audio_clip::AudioClip,
+};
+
+#[derive(Default)]
+pub struct TextureLoader {
+    options: TextureOptions,
+}
+
+impl TextureLoader {
+    pub fn create(options: TextureOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl AssetLoader<Texture2D> for TextureLoader {
+    fn get_extensions(&self) -> &[&str] {
+        &["png", "jpg", "jpeg", "gif"]
+    }
+
+    fn load(&self, context: &LoadContext) -> HexgemResult<Box<Texture2D>> {
+        let bytes = context.read()?;
+        Ok(Box::new(Texture2D::from_memory(&bytes, self.options)?))
+    }
+}
+
+// Includes are resolved relative to the including file, through the same source.
+#[derive(Default)]
+pub struct ShaderLoader;
+
+impl AssetLoader<dyn Shader> for ShaderLoader {
+    fn get_extensions(&self) -> &[&str] {
+        &["glsl"]
+    }
+
+    fn load(&self, context: &LoadContext) -> HexgemResult<Box<dyn Shader>> {
+        let text = context.read_to_string()?;
+        let asset_source = context.get_source();
+        let source = ShaderSource::parse_file_with_includes(
+            &context.get_name(),
+            context.get_path(),
+            &text,
+            |from, include| {
+                let file = resolve_path(from, include);
+                let source = asset_source.read_to_string(&file).ok()?;
+                Some((file, source))
+            },
+        )?;
+        RenderCommand::create_shader(&source)
+    }
+}
+
+#[derive(Default)]
+pub struct FontLoader {
+    options: FontOptions,
+}
+
+impl FontLoader {
+    pub fn create(options: FontOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl AssetLoader<Font> for FontLoader {
+    fn get_extensions(&self) -> &[&str] {
+        &["ttf", "otf", "fnt"]
+    }
+
+    fn load(&self, context: &LoadContext) -> HexgemResult<Box<Font>> {
+        if !context.get_path().to_lowercase().ends_with(".fnt") {
+            let mut font = Font::from_ttf(&context.read()?, &self.options)?;
+            font.set_name(&context.get_name());
+            return Ok(Box::new(font));
+        }
+        let descriptor = context.read_to_string()?;
+        let page = Font::get_bmfont_page(&descriptor)
+            .ok_or_else(|| HexgemError::Font("BMFont descriptor has no page file".to_string()))?;
+        let page = ImageData::decode(&context.read_relative(&page)?)?;
+        let mut font = Font::from_bmfont(&descriptor, &page)?;
+        if font.get_name().is_empty() {
+            font.set_name(&context.get_name());
+        }
+        Ok(Box::new(font))
+    }
+}
+
+#[derive(Default)]
+pub struct AudioLoader;
+
+impl AssetLoader<AudioClip> for AudioLoader {
+    fn get_extensions(&self) -> &[&str] {
+        &["wav", "ogg", "mp3", "flac"]
+    }
+
+    fn load(&self, context: &LoadContext) -> HexgemResult<Box<AudioClip>> {
+        let data = context.read()?.into_owned();
+        Ok(Box::new(AudioClip::from_bytes(context.get_path(), data)?))
+    }
+}
+
+// Sprite texture paths are relative to the scene file, like font pages and shader includes, and
+// load through the manager so sprites share textures with everything else. Register a loader
+// with your own serializer to load scenes with custom components.
+#[derive(Default)]
+pub struct SceneLoader {
+    serializer: SceneSerializer,
+}
+
+impl SceneLoader {
+    pub fn create(serializer: SceneSerializer) -> Self {
+        Self { serializer }
+    }
+}
+
+impl AssetLoader<Scene> for SceneLoader {
+    fn get_extensions(&self) -> &[&str] {
+        &["toml", "scene"]
+    }
+
+    fn load(&self, context: &LoadContext) -> HexgemResult<Box<Scene>> {
+        let text = context.read_to_string()?;
+        let scene = self
+            .serializer
+            .deserialize_with(&text, context.get_path(), |path| {
+                context.load::<Texture2D>(path)
+            })?;
+        Ok(Box::new(scene))
+    }
+}
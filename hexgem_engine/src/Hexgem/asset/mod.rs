@@ -0,0 +1,16 @@
+mod asset_loader;
+mod asset_manager;
+mod asset_source;
+mod asset_storage;
+mod audio_clip;
+mod handle;
+mod loaders;
+
+pub mod HexgemAsset {
+    pub use super::asset_loader::{AssetLoader, LoadContext};
+    pub use super::asset_manager::{AssetManager, SharedAssets};
+    pub use super::asset_source::{normalize_path, AssetSource};
+    pub use super::audio_clip::{AudioClip, AudioFormat};
+    pub use super::handle::Handle;
+    pub use super::loaders::{AudioLoader, FontLoader, SceneLoader, ShaderLoader, TextureLoader};
+}
@@ -2,16 +2,26 @@ pub trait ToAny: 'static {
     fn as_any(&self) -> &dyn std::any::Any;
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Position<T> {
     pub x: T,
     pub y: T,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Size<T> {
     pub width: T,
     pub height: T,
 }
+
+impl Size<i32> {
+    pub fn aspect_ratio(&self) -> f32 {
+        if self.height == 0 {
+            return 1.0;
+        }
+        self.width as f32 / self.height as f32
+    }
+}
 #[macro_export]
 macro_rules! toAnyImpl {
     ($struct:ident) => {
@@ -0,0 +1,96 @@
+// Index into the component storages plus the generation of that slot, so a handle to a
+// despawned entity never matches the entity that reuses its index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    pub fn get_index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn get_generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn to_bits(&self) -> u64 {
+        (self.generation as u64) << 32 | self.index as u64
+    }
+
+    pub fn from_bits(bits: u64) -> Self {
+        Self {
+            index: bits as u32,
+            generation: (bits >> 32) as u32,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct EntityAllocator {
+    generations: Vec<u32>,
+    alive: Vec<bool>,
+    free: Vec<u32>,
+    count: usize,
+}
+
+impl EntityAllocator {
+    pub fn allocate(&mut self) -> Entity {
+        self.count += 1;
+        if let Some(index) = self.free.pop() {
+            self.alive[index as usize] = true;
+            return Entity {
+                index,
+                generation: self.generations[index as usize],
+            };
+        }
+        self.generations.push(0);
+        self.alive.push(true);
+        Entity {
+            index: self.generations.len() as u32 - 1,
+            generation: 0,
+        }
+    }
+
+    pub fn free(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+        let index = entity.index as usize;
+        self.alive[index] = false;
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.free.push(entity.index);
+        self.count -= 1;
+        true
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        let index = entity.index as usize;
+        index < self.alive.len()
+            && self.alive[index]
+            && self.generations[index] == entity.generation
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.alive
+            .iter()
+            .zip(self.generations.iter())
+            .enumerate()
+            .filter(|(_, (alive, _))| **alive)
+            .map(|(index, (_, generation))| Entity {
+                index: index as u32,
+                generation: *generation,
+            })
+    }
+
+    pub fn clear(&mut self) {
+        for entity in self.iter().collect::<Vec<_>>() {
+            self.free(entity);
+        }
+    }
+}
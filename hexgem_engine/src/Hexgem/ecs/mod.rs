@@ -0,0 +1,14 @@
+mod entity;
+mod query;
+mod scene_layer;
+mod storage;
+mod system;
+mod world;
+
+pub mod HexgemEcs {
+    pub use super::entity::Entity;
+    pub use super::query::{Query, QueryBorrow, QueryIter};
+    pub use super::scene_layer::SceneLayer;
+    pub use super::system::{Scheduler, System, SystemStage};
+    pub use super::world::{Bundle, World};
+}
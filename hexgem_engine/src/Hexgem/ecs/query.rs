@@ -0,0 +1,348 @@
+use std::{
+    borrow::Cow,
+    cell::{Ref, RefMut},
+};
+
+use super::{entity::Entity, storage::ComponentStorage, world::World};
+
+// Something that can be fetched per entity: &T, &mut T, Option<&T>, Option<&mut T>, Entity
+// and tuples of those. Storages stay borrowed for as long as the query is alive, so asking for
+// the same component mutably twice panics like any other RefCell double borrow.
+pub trait Query {
+    type State<'w>;
+    type Item<'q>;
+    // None when a required component was never inserted, the query then matches nothing.
+    fn borrow(world: &World) -> Option<Self::State<'_>>;
+    // Entities of the smallest required storage, they drive the iteration.
+    fn candidates<'s>(state: &'s Self::State<'_>) -> Option<&'s [Entity]>;
+    fn matches(state: &Self::State<'_>, entity: Entity) -> bool;
+    /// # Safety
+    /// The entity has to match, and an entity must not be fetched again while an item fetched
+    /// for it is still alive.
+    unsafe fn fetch<'q>(state: &Self::State<'_>, entity: Entity) -> Self::Item<'q>;
+}
+
+pub struct WriteState<'w, T> {
+    storage: RefMut<'w, ComponentStorage<T>>,
+    components: *mut T,
+}
+
+impl<'w, T: 'static> WriteState<'w, T> {
+    fn create(mut storage: RefMut<'w, ComponentStorage<T>>) -> Self {
+        let components = storage.components_ptr();
+        Self {
+            storage,
+            components,
+        }
+    }
+
+    unsafe fn fetch<'q>(&self, entity: Entity) -> Option<&'q mut T> {
+        let index = self.storage.dense_index(entity)?;
+        Some(&mut *self.components.add(index))
+    }
+}
+
+impl<T: 'static> Query for &T {
+    type State<'w> = Ref<'w, ComponentStorage<T>>;
+    type Item<'q> = &'q T;
+
+    fn borrow(world: &World) -> Option<Self::State<'_>> {
+        world.borrow_storage::<T>()
+    }
+
+    fn candidates<'s>(state: &'s Self::State<'_>) -> Option<&'s [Entity]> {
+        Some(state.get_entities())
+    }
+
+    fn matches(state: &Self::State<'_>, entity: Entity) -> bool {
+        state.dense_index(entity).is_some()
+    }
+
+    unsafe fn fetch<'q>(state: &Self::State<'_>, entity: Entity) -> Self::Item<'q> {
+        &*(state.get(entity).expect("fetched entity has the component") as *const T)
+    }
+}
+
+impl<T: 'static> Query for &mut T {
+    type State<'w> = WriteState<'w, T>;
+    type Item<'q> = &'q mut T;
+
+    fn borrow(world: &World) -> Option<Self::State<'_>> {
+        world.borrow_storage_mut::<T>().map(WriteState::create)
+    }
+
+    fn candidates<'s>(state: &'s Self::State<'_>) -> Option<&'s [Entity]> {
+        Some(state.storage.get_entities())
+    }
+
+    fn matches(state: &Self::State<'_>, entity: Entity) -> bool {
+        state.storage.dense_index(entity).is_some()
+    }
+
+    unsafe fn fetch<'q>(state: &Self::State<'_>, entity: Entity) -> Self::Item<'q> {
+        state
+            .fetch(entity)
+            .expect("fetched entity has the component")
+    }
+}
+
+impl<T: 'static> Query for Option<&T> {
+    type State<'w> = Option<Ref<'w, ComponentStorage<T>>>;
+    type Item<'q> = Option<&'q T>;
+
+    fn borrow(world: &World) -> Option<Self::State<'_>> {
+        Some(world.borrow_storage::<T>())
+    }
+
+    fn candidates<'s>(_state: &'s Self::State<'_>) -> Option<&'s [Entity]> {
+        None
+    }
+
+    fn matches(_state: &Self::State<'_>, _entity: Entity) -> bool {
+        true
+    }
+
+    unsafe fn fetch<'q>(state: &Self::State<'_>, entity: Entity) -> Self::Item<'q> {
+        let storage = state.as_ref()?;
+        storage
+            .get(entity)
+            .map(|component| &*(component as *const T))
+    }
+}
+
+impl<T: 'static> Query for Option<&mut T> {
+    type State<'w> = Option<WriteState<'w, T>>;
+    type Item<'q> = Option<&'q mut T>;
+
+    fn borrow(world: &World) -> Option<Self::State<'_>> {
+        Some(world.borrow_storage_mut::<T>().map(WriteState::create))
+    }
+
+    fn candidates<'s>(_state: &'s Self::State<'_>) -> Option<&'s [Entity]> {
+        None
+    }
+
+    fn matches(_state: &Self::State<'_>, _entity: Entity) -> bool {
+        true
+    }
+
+    unsafe fn fetch<'q>(state: &Self::State<'_>, entity: Entity) -> Self::Item<'q> {
+        state.as_ref()?.fetch(entity)
+    }
+}
+
+impl Query for Entity {
+    type State<'w> = ();
+    type Item<'q> = Entity;
+
+    fn borrow(_world: &World) -> Option<Self::State<'_>> {
+        Some(())
+    }
+
+    fn candidates<'s>(_state: &'s Self::State<'_>) -> Option<&'s [Entity]> {
+        None
+    }
+
+    fn matches(_state: &Self::State<'_>, _entity: Entity) -> bool {
+        true
+    }
+
+    unsafe fn fetch<'q>(_state: &Self::State<'_>, entity: Entity) -> Self::Item<'q> {
+        entity
+    }
+}
+
+macro_rules! queryTupleImpl {
+    ($($name:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<$($name: Query),+> Query for ($($name,)+) {
+            type State<'w> = ($($name::State<'w>,)+);
+            type Item<'q> = ($($name::Item<'q>,)+);
+
+            fn borrow(world: &World) -> Option<Self::State<'_>> {
+                Some(($($name::borrow(world)?,)+))
+            }
+
+            fn candidates<'s>(state: &'s Self::State<'_>) -> Option<&'s [Entity]> {
+                let ($($name,)+) = state;
+                let mut smallest: Option<&[Entity]> = None;
+                $(
+                    if let Some(entities) = $name::candidates($name) {
+                        if smallest.is_none_or(|smallest| entities.len() < smallest.len()) {
+                            smallest = Some(entities);
+                        }
+                    }
+                )+
+                smallest
+            }
+
+            fn matches(state: &Self::State<'_>, entity: Entity) -> bool {
+                let ($($name,)+) = state;
+                $($name::matches($name, entity))&&+
+            }
+
+            unsafe fn fetch<'q>(state: &Self::State<'_>, entity: Entity) -> Self::Item<'q> {
+                let ($($name,)+) = state;
+                ($($name::fetch($name, entity),)+)
+            }
+        }
+    };
+}
+
+queryTupleImpl!(A);
+queryTupleImpl!(A, B);
+queryTupleImpl!(A, B, C);
+queryTupleImpl!(A, B, C, D);
+queryTupleImpl!(A, B, C, D, E);
+queryTupleImpl!(A, B, C, D, E, F);
+queryTupleImpl!(A, B, C, D, E, F, G);
+queryTupleImpl!(A, B, C, D, E, F, G, H);
+
+// Keeps the storages a query reads and writes borrowed. Iterate it with iter(), items borrow
+// the query so they cannot outlive the storage borrows.
+pub struct QueryBorrow<'w, Q: Query> {
+    world: &'w World,
+    state: Option<Q::State<'w>>,
+}
+
+impl<'w, Q: Query> QueryBorrow<'w, Q> {
+    pub(crate) fn create(world: &'w World) -> Self {
+        Self {
+            world,
+            state: Q::borrow(world),
+        }
+    }
+
+    pub fn iter(&mut self) -> QueryIter<'_, 'w, Q> {
+        let entities = match self.state.as_ref() {
+            Some(state) => match Q::candidates(state) {
+                Some(entities) => Cow::Borrowed(entities),
+                None => Cow::Owned(self.world.get_entities()),
+            },
+            None => Cow::Owned(vec![]),
+        };
+        QueryIter {
+            state: self.state.as_ref(),
+            entities,
+            position: 0,
+        }
+    }
+
+    pub fn get(&mut self, entity: Entity) -> Option<Q::Item<'_>> {
+        let state = self.state.as_ref()?;
+        if !self.world.is_alive(entity) || !Q::matches(state, entity) {
+            return None;
+        }
+        // The exclusive borrow of self keeps this the only live item.
+        Some(unsafe { Q::fetch(state, entity) })
+    }
+
+    pub fn count(&mut self) -> usize {
+        self.iter().count()
+    }
+}
+
+pub struct QueryIter<'q, 'w, Q: Query> {
+    state: Option<&'q Q::State<'w>>,
+    entities: Cow<'q, [Entity]>,
+    position: usize,
+}
+
+impl<'q, 'w, Q: Query> Iterator for QueryIter<'q, 'w, Q> {
+    type Item = Q::Item<'q>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let state = self.state?;
+        while let Some(entity) = self.entities.get(self.position).copied() {
+            self.position += 1;
+            if Q::matches(state, entity) {
+                // Entities are unique in the list, so every item points at different data.
+                return Some(unsafe { Q::fetch(state, entity) });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Velocity(i32);
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Frozen;
+
+    #[test]
+    fn mutable_iteration_writes_every_match() {
+        let mut world = World::create();
+        let moving = [1, 2, 3].map(|i| world.spawn_with((Position(i * 10), Velocity(i))));
+        let still = world.spawn_with((Position(5),));
+
+        for (position, velocity) in world.query::<(&mut Position, &Velocity)>().iter() {
+            position.0 += velocity.0;
+        }
+        for (entity, expected) in moving.iter().zip([11, 22, 33]) {
+            assert_eq!(*world.get::<Position>(*entity).unwrap(), Position(expected));
+        }
+        assert_eq!(*world.get::<Position>(still).unwrap(), Position(5));
+        assert_eq!(world.query::<(&Position, &Velocity)>().count(), 3);
+    }
+
+    #[test]
+    fn get_misses_despawned_and_reused_entities() {
+        let mut world = World::create();
+        let old = world.spawn_with((Position(1),));
+        assert!(world.despawn(old));
+        assert!(world.query::<&Position>().get(old).is_none());
+
+        let new = world.spawn_with((Position(2),));
+        assert_eq!(new.get_index(), old.get_index());
+        assert_ne!(new.get_generation(), old.get_generation());
+        let mut query = world.query::<(Entity, &Position)>();
+        assert!(query.get(old).is_none());
+        assert_eq!(query.get(new), Some((new, &Position(2))));
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed")]
+    fn aliasing_mutable_access_panics() {
+        let mut world = World::create();
+        world.spawn_with((Position(1),));
+        let _ = world.query::<(&mut Position, &mut Position)>();
+    }
+
+    #[test]
+    fn optional_components_over_missing_storages() {
+        let mut world = World::create();
+        let a = world.spawn_with((Position(1),));
+        let b = world.spawn_with((Position(2),));
+
+        let mut query = world.query::<(Entity, Option<&Velocity>)>();
+        let items: Vec<_> = query.iter().collect();
+        assert_eq!(items, vec![(a, None), (b, None)]);
+        drop(query);
+
+        let mut query = world.query::<(&Position, Option<&mut Velocity>)>();
+        assert!(query.iter().all(|(_, velocity)| velocity.is_none()));
+        drop(query);
+
+        // A required component that was never inserted matches nothing.
+        assert_eq!(world.query::<(Option<&Position>, &Frozen)>().count(), 0);
+
+        world.insert(b, Velocity(3)).unwrap();
+        let mut query = world.query::<(&Position, Option<&mut Velocity>)>();
+        for (_, velocity) in query.iter() {
+            if let Some(velocity) = velocity {
+                velocity.0 *= 2;
+            }
+        }
+        drop(query);
+        assert_eq!(*world.get::<Velocity>(b).unwrap(), Velocity(6));
+        assert_eq!(world.query::<Option<&Velocity>>().count(), 2);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::HexgemEvent::Event;
+
+use crate::Hexgem::{layer::Layer, timestep::Timestep, window::Window};
+
+use super::{
+    system::{Scheduler, System, SystemStage},
+    world::World,
+};
+
+// Layer owning a world and running its systems every frame. update() does the same without a
+// window, for headless simulations and tests.
+pub struct SceneLayer {
+    name: &'static str,
+    world: World,
+    scheduler: Scheduler,
+}
+
+impl SceneLayer {
+    pub fn create(name: &'static str) -> Self {
+        Self {
+            name,
+            world: World::create(),
+            scheduler: Scheduler::create(),
+        }
+    }
+
+    pub fn get_world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn get_world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    pub fn get_scheduler(&self) -> &Scheduler {
+        &self.scheduler
+    }
+
+    pub fn get_scheduler_mut(&mut self) -> &mut Scheduler {
+        &mut self.scheduler
+    }
+
+    pub fn add_system<S: System + 'static>(&mut self, name: &'static str, system: S) {
+        self.scheduler.add_system(name, system);
+    }
+
+    pub fn add_system_to_stage<S: System + 'static>(
+        &mut self,
+        stage: SystemStage,
+        name: &'static str,
+        system: S,
+    ) {
+        self.scheduler.add_system_to_stage(stage, name, system);
+    }
+
+    pub fn update(&mut self, timestep: Timestep) {
+        self.scheduler.run(&mut self.world, timestep);
+    }
+}
+
+impl Layer for SceneLayer {
+    fn get_name(&self) -> &'static str {
+        self.name
+    }
+
+    fn on_update(&mut self, window: &mut Box<dyn Window>, timestep: Timestep) {
+        self.update(timestep);
+    }
+
+    fn on_event(&mut self, event: &mut Box<dyn Event>, window: &mut dyn Window) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Velocity(f32);
+    struct Position(f32);
+
+    #[test]
+    fn update_runs_the_systems_on_the_world() {
+        let mut layer = SceneLayer::create("Scene");
+        let entity = layer
+            .get_world_mut()
+            .spawn_with((Position(0.0), Velocity(2.0)));
+        layer.add_system("movement", |world: &mut World, timestep: Timestep| {
+            for (position, velocity) in world.query::<(&mut Position, &Velocity)>().iter() {
+                position.0 += velocity.0 * timestep.get_seconds();
+            }
+        });
+        layer.add_system_to_stage(SystemStage::PreUpdate, "boost", |world: &mut World, _| {
+            for velocity in world.query::<&mut Velocity>().iter() {
+                velocity.0 += 1.0;
+            }
+        });
+        layer.update(Timestep::create(0.5));
+        layer.update(Timestep::create(0.5));
+        let position = layer.get_world().get::<Position>(entity).unwrap().0;
+        assert_eq!(position, 3.0 * 0.5 + 4.0 * 0.5);
+        assert_eq!(
+            layer.get_scheduler().get_system_names(),
+            ["boost", "movement"]
+        );
+        assert_eq!(layer.get_name(), "Scene");
+    }
+}
@@ -0,0 +1,142 @@
+use std::any::Any;
+
+use super::entity::Entity;
+
+const EMPTY: u32 = u32::MAX;
+
+pub(crate) trait AnyStorage {
+    fn remove_entity(&mut self, entity: Entity) -> bool;
+    fn clear(&mut self);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+// Sparse set: components are packed in a dense array, the sparse array maps entity indices
+// to their position in it. Removal swaps the last component into the hole.
+pub struct ComponentStorage<T> {
+    sparse: Vec<u32>,
+    entities: Vec<Entity>,
+    components: Vec<T>,
+}
+
+impl<T: 'static> ComponentStorage<T> {
+    pub fn create() -> Self {
+        Self {
+            sparse: vec![],
+            entities: vec![],
+            components: vec![],
+        }
+    }
+
+    pub fn dense_index(&self, entity: Entity) -> Option<usize> {
+        let index = *self.sparse.get(entity.get_index() as usize)?;
+        if index == EMPTY || self.entities[index as usize] != entity {
+            return None;
+        }
+        Some(index as usize)
+    }
+
+    pub fn insert(&mut self, entity: Entity, component: T) -> Option<T> {
+        if let Some(index) = self.dense_index(entity) {
+            return Some(std::mem::replace(&mut self.components[index], component));
+        }
+        let sparse_index = entity.get_index() as usize;
+        if sparse_index >= self.sparse.len() {
+            self.sparse.resize(sparse_index + 1, EMPTY);
+        }
+        self.sparse[sparse_index] = self.entities.len() as u32;
+        self.entities.push(entity);
+        self.components.push(component);
+        None
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        let index = self.dense_index(entity)?;
+        self.sparse[entity.get_index() as usize] = EMPTY;
+        self.entities.swap_remove(index);
+        let component = self.components.swap_remove(index);
+        if let Some(moved) = self.entities.get(index) {
+            self.sparse[moved.get_index() as usize] = index as u32;
+        }
+        Some(component)
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        self.dense_index(entity)
+            .map(|index| &self.components[index])
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        self.dense_index(entity)
+            .map(|index| &mut self.components[index])
+    }
+
+    pub fn get_entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    pub fn components_ptr(&mut self) -> *mut T {
+        self.components.as_mut_ptr()
+    }
+}
+
+impl<T: 'static> AnyStorage for ComponentStorage<T> {
+    fn remove_entity(&mut self, entity: Entity) -> bool {
+        self.remove(entity).is_some()
+    }
+
+    fn clear(&mut self) {
+        self.sparse.clear();
+        self.entities.clear();
+        self.components.clear();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(index: u32, generation: u32) -> Entity {
+        Entity::from_bits((generation as u64) << 32 | index as u64)
+    }
+
+    #[test]
+    fn remove_swaps_the_last_component_into_the_hole() {
+        let mut storage = ComponentStorage::create();
+        let (a, b, c) = (entity(0, 0), entity(1, 0), entity(2, 0));
+        storage.insert(a, "a");
+        storage.insert(b, "b");
+        storage.insert(c, "c");
+
+        assert_eq!(storage.remove(a), Some("a"));
+        assert_eq!(storage.get_entities(), &[c, b]);
+        assert_eq!(storage.dense_index(c), Some(0));
+        assert_eq!(storage.dense_index(b), Some(1));
+        assert_eq!(storage.get(c), Some(&"c"));
+        assert_eq!(storage.get(a), None);
+        assert_eq!(storage.remove(a), None);
+
+        // Removing the last component has nothing to move.
+        assert_eq!(storage.remove(b), Some("b"));
+        assert_eq!(storage.get_entities(), &[c]);
+        assert_eq!(storage.get(c), Some(&"c"));
+    }
+
+    #[test]
+    fn stale_generations_do_not_match() {
+        let mut storage = ComponentStorage::create();
+        storage.insert(entity(3, 1), 10);
+        assert_eq!(storage.get(entity(3, 0)), None);
+        assert_eq!(storage.remove(entity(3, 2)), None);
+        assert_eq!(storage.insert(entity(3, 1), 20), Some(10));
+        assert_eq!(storage.get(entity(3, 1)), Some(&20));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::Hexgem::timestep::Timestep;
+
+use super::world::World;
+
+pub trait System {
+    fn run(&mut self, world: &mut World, timestep: Timestep);
+}
+
+impl<F: FnMut(&mut World, Timestep)> System for F {
+    fn run(&mut self, world: &mut World, timestep: Timestep) {
+        self(world, timestep)
+    }
+}
+
+// Stages run in declaration order, systems within a stage in the order they were added.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum SystemStage {
+    PreUpdate,
+    #[default]
+    Update,
+    PostUpdate,
+}
+
+struct ScheduledSystem {
+    name: &'static str,
+    stage: SystemStage,
+    enabled: bool,
+    system: Box<dyn System>,
+}
+
+#[derive(Default)]
+pub struct Scheduler {
+    systems: Vec<ScheduledSystem>,
+}
+
+impl Scheduler {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn add_system<S: System + 'static>(&mut self, name: &'static str, system: S) {
+        self.add_system_to_stage(SystemStage::Update, name, system);
+    }
+
+    pub fn add_system_to_stage<S: System + 'static>(
+        &mut self,
+        stage: SystemStage,
+        name: &'static str,
+        system: S,
+    ) {
+        // Keeps the list sorted by stage, after the systems already in the same stage.
+        let index = self.systems.partition_point(|system| system.stage <= stage);
+        self.systems.insert(
+            index,
+            ScheduledSystem {
+                name,
+                stage,
+                enabled: true,
+                system: Box::new(system),
+            },
+        );
+    }
+
+    pub fn remove_system(&mut self, name: &str) -> bool {
+        let count = self.systems.len();
+        self.systems.retain(|system| system.name != name);
+        self.systems.len() != count
+    }
+
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) {
+        for system in self.systems.iter_mut().filter(|system| system.name == name) {
+            system.enabled = enabled;
+        }
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.systems
+            .iter()
+            .any(|system| system.name == name && system.enabled)
+    }
+
+    pub fn get_system_names(&self) -> Vec<&'static str> {
+        self.systems.iter().map(|system| system.name).collect()
+    }
+
+    pub fn run(&mut self, world: &mut World, timestep: Timestep) {
+        for scheduled in self.systems.iter_mut().filter(|system| system.enabled) {
+            crate::profile_scope!(scheduled.name);
+            scheduled.system.run(world, timestep);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Log(Vec<&'static str>);
+
+    fn logger(name: &'static str) -> impl FnMut(&mut World, Timestep) {
+        move |world, _| world.get_resource_mut::<Log>().unwrap().0.push(name)
+    }
+
+    fn run(scheduler: &mut Scheduler) -> Vec<&'static str> {
+        let mut world = World::create();
+        world.insert_resource(Log::default());
+        scheduler.run(&mut world, Timestep::create(0.016));
+        world.remove_resource::<Log>().unwrap().0
+    }
+
+    #[test]
+    fn stages_run_in_order() {
+        let mut scheduler = Scheduler::create();
+        scheduler.add_system_to_stage(SystemStage::PostUpdate, "render", logger("render"));
+        scheduler.add_system("move", logger("move"));
+        scheduler.add_system_to_stage(SystemStage::PreUpdate, "input", logger("input"));
+        scheduler.add_system("collide", logger("collide"));
+        assert_eq!(
+            scheduler.get_system_names(),
+            ["input", "move", "collide", "render"]
+        );
+        assert_eq!(run(&mut scheduler), ["input", "move", "collide", "render"]);
+    }
+
+    #[test]
+    fn disabled_systems_are_skipped() {
+        let mut scheduler = Scheduler::create();
+        scheduler.add_system("a", logger("a"));
+        scheduler.add_system("b", logger("b"));
+        scheduler.set_enabled("a", false);
+        assert!(!scheduler.is_enabled("a"));
+        assert!(scheduler.is_enabled("b"));
+        assert!(!scheduler.is_enabled("missing"));
+        assert_eq!(run(&mut scheduler), ["b"]);
+        scheduler.set_enabled("a", true);
+        assert_eq!(run(&mut scheduler), ["a", "b"]);
+    }
+
+    #[test]
+    fn removed_systems_stop_running() {
+        let mut scheduler = Scheduler::create();
+        scheduler.add_system("a", logger("a"));
+        scheduler.add_system("b", logger("b"));
+        assert!(scheduler.remove_system("a"));
+        assert!(!scheduler.remove_system("a"));
+        assert_eq!(scheduler.get_system_names(), ["b"]);
+        assert_eq!(run(&mut scheduler), ["b"]);
+    }
+}
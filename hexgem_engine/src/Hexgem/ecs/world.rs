@@ -0,0 +1,316 @@
+use std::{
+    any::{type_name, Any, TypeId},
+    cell::{Ref, RefCell, RefMut},
+    collections::HashMap,
+};
+
+use crate::Hexgem::error::{HexgemError, HexgemResult};
+
+use super::{
+    entity::{Entity, EntityAllocator},
+    query::{Query, QueryBorrow},
+    storage::{AnyStorage, ComponentStorage},
+};
+
+// Components added together, implemented for tuples so spawn_with((a, b)) works.
+pub trait Bundle {
+    fn insert_into(self, world: &mut World, entity: Entity) -> HexgemResult<()>;
+}
+
+macro_rules! bundleTupleImpl {
+    ($($name:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<$($name: 'static),+> Bundle for ($($name,)+) {
+            fn insert_into(self, world: &mut World, entity: Entity) -> HexgemResult<()> {
+                let ($($name,)+) = self;
+                $(world.insert(entity, $name)?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+bundleTupleImpl!(A);
+bundleTupleImpl!(A, B);
+bundleTupleImpl!(A, B, C);
+bundleTupleImpl!(A, B, C, D);
+bundleTupleImpl!(A, B, C, D, E);
+bundleTupleImpl!(A, B, C, D, E, F);
+bundleTupleImpl!(A, B, C, D, E, F, G);
+bundleTupleImpl!(A, B, C, D, E, F, G, H);
+
+// Entities with any 'static type as component, plus resources: singletons shared by systems.
+// Storages are borrowed through RefCells, so queries only need a shared reference.
+#[derive(Default)]
+pub struct World {
+    entities: EntityAllocator,
+    storages: HashMap<TypeId, RefCell<Box<dyn AnyStorage>>>,
+    resources: HashMap<TypeId, RefCell<Box<dyn Any>>>,
+}
+
+impl World {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn spawn(&mut self) -> Entity {
+        self.entities.allocate()
+    }
+
+    pub fn spawn_with<B: Bundle>(&mut self, bundle: B) -> Entity {
+        let entity = self.spawn();
+        // The entity was just created, inserting into it cannot fail.
+        let _ = bundle.insert_into(self, entity);
+        entity
+    }
+
+    // Removes the entity with all of its components, false if it was already gone.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.entities.free(entity) {
+            return false;
+        }
+        for storage in self.storages.values_mut() {
+            storage.get_mut().remove_entity(entity);
+        }
+        true
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.entities.is_alive(entity)
+    }
+
+    pub fn get_entity_count(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn get_entities(&self) -> Vec<Entity> {
+        self.entities.iter().collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.entities.clear();
+        for storage in self.storages.values_mut() {
+            storage.get_mut().clear();
+        }
+    }
+
+    // Copy of the entities without any components or resources, handles stay valid in it.
+    pub fn clone_entities(&self) -> World {
+        World {
+            entities: self.entities.clone(),
+            ..Default::default()
+        }
+    }
+
+    // Copies every T component into the target world, for entities alive in both.
+    pub fn clone_components<T: Clone + 'static>(&self, target: &mut World) {
+        let Some(storage) = self.borrow_storage::<T>() else {
+            return;
+        };
+        for &entity in storage.get_entities() {
+            if let Some(component) = storage.get(entity) {
+                // Entities dead in the target are skipped by the failing insert.
+                let _ = target.insert(entity, component.clone());
+            }
+        }
+    }
+
+    // Returns the component the entity had before, if any.
+    pub fn insert<T: 'static>(&mut self, entity: Entity, component: T) -> HexgemResult<Option<T>> {
+        if !self.is_alive(entity) {
+            return Err(HexgemError::Ecs(format!(
+                "Cannot add {} to despawned entity {:?}",
+                type_name::<T>(),
+                entity
+            )));
+        }
+        let storage = self
+            .storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| RefCell::new(Box::new(ComponentStorage::<T>::create())))
+            .get_mut();
+        Ok(downcast_mut::<T>(storage).insert(entity, component))
+    }
+
+    pub fn remove<T: 'static>(&mut self, entity: Entity) -> Option<T> {
+        let storage = self.storages.get_mut(&TypeId::of::<T>())?.get_mut();
+        downcast_mut::<T>(storage).remove(entity)
+    }
+
+    pub fn has<T: 'static>(&self, entity: Entity) -> bool {
+        self.borrow_storage::<T>()
+            .is_some_and(|storage| storage.dense_index(entity).is_some())
+    }
+
+    pub fn get<T: 'static>(&self, entity: Entity) -> Option<Ref<'_, T>> {
+        Ref::filter_map(self.borrow_storage::<T>()?, |storage| storage.get(entity)).ok()
+    }
+
+    pub fn get_mut<T: 'static>(&self, entity: Entity) -> Option<RefMut<'_, T>> {
+        RefMut::filter_map(self.borrow_storage_mut::<T>()?, |storage| {
+            storage.get_mut(entity)
+        })
+        .ok()
+    }
+
+    // world.query::<(&Transform, &mut Velocity)>().iter() visits every entity having both.
+    pub fn query<Q: Query>(&self) -> QueryBorrow<'_, Q> {
+        QueryBorrow::create(self)
+    }
+
+    pub fn insert_resource<R: 'static>(&mut self, resource: R) -> Option<R> {
+        self.resources
+            .insert(TypeId::of::<R>(), RefCell::new(Box::new(resource)))
+            .and_then(|previous| previous.into_inner().downcast::<R>().ok())
+            .map(|previous| *previous)
+    }
+
+    pub fn remove_resource<R: 'static>(&mut self) -> Option<R> {
+        let resource = self.resources.remove(&TypeId::of::<R>())?;
+        resource
+            .into_inner()
+            .downcast::<R>()
+            .ok()
+            .map(|resource| *resource)
+    }
+
+    pub fn has_resource<R: 'static>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<R>())
+    }
+
+    pub fn get_resource<R: 'static>(&self) -> Option<Ref<'_, R>> {
+        let cell = self.resources.get(&TypeId::of::<R>())?;
+        let resource = cell.try_borrow().unwrap_or_else(|_| {
+            panic!("Resource {} is already borrowed mutably", type_name::<R>())
+        });
+        Ref::filter_map(resource, |resource| resource.downcast_ref::<R>()).ok()
+    }
+
+    pub fn get_resource_mut<R: 'static>(&self) -> Option<RefMut<'_, R>> {
+        let cell = self.resources.get(&TypeId::of::<R>())?;
+        let resource = cell
+            .try_borrow_mut()
+            .unwrap_or_else(|_| panic!("Resource {} is already borrowed", type_name::<R>()));
+        RefMut::filter_map(resource, |resource| resource.downcast_mut::<R>()).ok()
+    }
+
+    // Hands every resource over to the target world, replacing the ones it already had.
+    pub fn move_resources_to(&mut self, target: &mut World) {
+        target.resources.extend(self.resources.drain());
+    }
+
+    pub(crate) fn borrow_storage<T: 'static>(&self) -> Option<Ref<'_, ComponentStorage<T>>> {
+        let cell = self.storages.get(&TypeId::of::<T>())?;
+        let storage = cell.try_borrow().unwrap_or_else(|_| {
+            panic!("Component {} is already borrowed mutably", type_name::<T>())
+        });
+        Some(Ref::map(storage, |storage| downcast::<T>(storage.as_ref())))
+    }
+
+    pub(crate) fn borrow_storage_mut<T: 'static>(&self) -> Option<RefMut<'_, ComponentStorage<T>>> {
+        let cell = self.storages.get(&TypeId::of::<T>())?;
+        let storage = cell
+            .try_borrow_mut()
+            .unwrap_or_else(|_| panic!("Component {} is already borrowed", type_name::<T>()));
+        Some(RefMut::map(storage, |storage| downcast_mut::<T>(storage)))
+    }
+}
+
+// Storages are keyed by the TypeId of their component, so the downcasts cannot fail.
+fn downcast<T: 'static>(storage: &dyn AnyStorage) -> &ComponentStorage<T> {
+    storage
+        .as_any()
+        .downcast_ref::<ComponentStorage<T>>()
+        .expect("storage matches its type id")
+}
+
+fn downcast_mut<T: 'static>(storage: &mut Box<dyn AnyStorage>) -> &mut ComponentStorage<T> {
+    storage
+        .as_any_mut()
+        .downcast_mut::<ComponentStorage<T>>()
+        .expect("storage matches its type id")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Position(f32);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Name(&'static str);
+
+    #[derive(Debug, PartialEq)]
+    struct Gravity(f32);
+
+    #[test]
+    fn despawn_removes_every_component() {
+        let mut world = World::create();
+        let a = world.spawn_with((Position(1.0), Name("a")));
+        let b = world.spawn_with((Position(2.0),));
+        assert!(world.despawn(a));
+        assert!(!world.despawn(a));
+        assert!(!world.is_alive(a));
+        assert!(!world.has::<Position>(a) && !world.has::<Name>(a));
+        assert!(world.insert(a, Position(3.0)).is_err());
+
+        // The freed index is reused with a new generation that starts empty.
+        let c = world.spawn();
+        assert_ne!(c, a);
+        assert!(!world.has::<Position>(c));
+        assert_eq!(world.get::<Position>(b).as_deref(), Some(&Position(2.0)));
+        assert_eq!(world.get_entity_count(), 2);
+    }
+
+    #[test]
+    fn clear_empties_entities_and_storages() {
+        let mut world = World::create();
+        let entity = world.spawn_with((Position(1.0),));
+        world.insert_resource(Gravity(9.8));
+        world.clear();
+        assert_eq!(world.get_entity_count(), 0);
+        assert!(!world.has::<Position>(entity));
+        assert!(world.has_resource::<Gravity>());
+    }
+
+    #[test]
+    fn resources_are_replaced_and_moved() {
+        let mut world = World::create();
+        assert_eq!(world.insert_resource(Gravity(9.8)), None);
+        assert_eq!(world.insert_resource(Gravity(1.6)), Some(Gravity(9.8)));
+        world.get_resource_mut::<Gravity>().unwrap().0 *= 2.0;
+        assert_eq!(world.get_resource::<Gravity>().unwrap().0, 3.2);
+        assert!(world.get_resource::<Name>().is_none());
+
+        let mut target = World::create();
+        target.insert_resource(Gravity(0.0));
+        world.move_resources_to(&mut target);
+        assert!(!world.has_resource::<Gravity>());
+        assert_eq!(target.remove_resource::<Gravity>(), Some(Gravity(3.2)));
+        assert!(!target.has_resource::<Gravity>());
+    }
+
+    #[test]
+    fn cloned_entities_receive_cloned_components() {
+        let mut world = World::create();
+        let a = world.spawn_with((Position(1.0), Name("a")));
+        let b = world.spawn_with((Position(2.0),));
+        world.insert_resource(Gravity(9.8));
+
+        let mut copy = world.clone_entities();
+        assert_eq!(copy.get_entities(), world.get_entities());
+        assert!(!copy.has::<Position>(a) && !copy.has_resource::<Gravity>());
+        world.clone_components::<Position>(&mut copy);
+        assert_eq!(copy.get::<Position>(a).as_deref(), Some(&Position(1.0)));
+        assert_eq!(copy.get::<Position>(b).as_deref(), Some(&Position(2.0)));
+        assert!(!copy.has::<Name>(a));
+
+        // Entities despawned in the target are skipped.
+        copy.despawn(b);
+        world.get_mut::<Position>(a).unwrap().0 = 5.0;
+        world.clone_components::<Position>(&mut copy);
+        assert_eq!(copy.get::<Position>(a).as_deref(), Some(&Position(5.0)));
+        assert!(!copy.has::<Position>(b));
+    }
+}
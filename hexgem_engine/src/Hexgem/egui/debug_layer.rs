@@ -0,0 +1,191 @@
+use std::{collections::VecDeque, time::Instant};
+
+use egui::{pos2, Color32, Sense, Shape, Stroke};
+use glfw::Window;
+
+use crate::{
+    Hexgem::layer_stack::LayerStates,
+    HexgemEvent::{Event, EventType},
+    HexgemRenderer::Renderer2D,
+    Layer, RingBufferSink,
+};
+
+use super::egui_window::EguiWindow;
+
+const FRAME_HISTORY: usize = 240;
+const EVENT_HISTORY: usize = 64;
+
+pub struct DebugLayer {
+    egui_window: EguiWindow,
+    layer_states: LayerStates,
+    last_frame: Instant,
+    frame_times: VecDeque<f32>,
+    events: VecDeque<String>,
+    log_none_events: bool,
+    log_buffer: Option<RingBufferSink>,
+}
+
+impl DebugLayer {
+    pub fn create(window: &mut Window, layer_states: LayerStates) -> Self {
+        let egui_window = EguiWindow::create(window);
+        Self {
+            egui_window,
+            layer_states,
+            last_frame: Instant::now(),
+            frame_times: VecDeque::with_capacity(FRAME_HISTORY),
+            events: VecDeque::with_capacity(EVENT_HISTORY),
+            log_none_events: false,
+            log_buffer: None,
+        }
+    }
+
+    pub fn set_log_buffer(&mut self, buffer: RingBufferSink) {
+        self.log_buffer = Some(buffer);
+    }
+
+    fn record_frame(&mut self) {
+        let now = Instant::now();
+        let frame_time = now.duration_since(self.last_frame).as_secs_f32() * 1000.0;
+        self.last_frame = now;
+        if self.frame_times.len() == FRAME_HISTORY {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+
+    fn record_event(&mut self, event: &Box<dyn Event>) {
+        let event_type = event.get_event_type();
+        if event_type == EventType::None && !self.log_none_events {
+            return;
+        }
+        if self.events.len() == EVENT_HISTORY {
+            self.events.pop_front();
+        }
+        self.events.push_back(format!("{:?}", event_type));
+    }
+
+    fn average_frame_time(&self) -> f32 {
+        if self.frame_times.is_empty() {
+            return 0.0;
+        }
+        self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32
+    }
+
+    fn frame_time_plot(ui: &mut egui::Ui, frame_times: &VecDeque<f32>) {
+        let size = egui::vec2(ui.available_width(), 60.0);
+        let (response, painter) = ui.allocate_painter(size, Sense::hover());
+        let rect = response.rect;
+        painter.rect_filled(rect, 2.0, Color32::from_black_alpha(96));
+        let max = frame_times.iter().cloned().fold(1.0_f32, f32::max);
+        let step = rect.width() / (FRAME_HISTORY - 1) as f32;
+        let points = frame_times
+            .iter()
+            .enumerate()
+            .map(|(i, time)| {
+                pos2(
+                    rect.left() + i as f32 * step,
+                    rect.bottom() - time / max * rect.height(),
+                )
+            })
+            .collect::<Vec<_>>();
+        painter.add(Shape::line(points, Stroke::new(1.0, Color32::LIGHT_GREEN)));
+        ui.label(format!("max {:.2} ms", max));
+    }
+}
+
+impl Layer for DebugLayer {
+    fn get_name(&self) -> &'static str {
+        "Debug Layer"
+    }
+
+    fn on_event(&mut self, event: &mut Box<dyn Event>, window: &mut dyn crate::Window) {
+        self.record_event(event);
+        self.egui_window.context.handle_event(event);
+        if self.egui_window.context.consumes(event) {
+            *event.handled() = true;
+        }
+    }
+
+    fn on_update(&mut self, window: &mut Box<dyn crate::Window>, timestep: crate::Timestep) {
+        self.record_frame();
+        let name = self.get_name();
+        let frame_time = self.average_frame_time();
+        let fps = if frame_time > 0.0 {
+            1000.0 / frame_time
+        } else {
+            0.0
+        };
+        let mut vsync = window.is_vsync();
+        let stats = Renderer2D::get_stats();
+        let Self {
+            egui_window,
+            layer_states,
+            frame_times,
+            events,
+            log_none_events,
+            log_buffer,
+            ..
+        } = self;
+
+        egui_window.render(|context| {
+            egui::Window::new("Hexgem debug").show(context, |ui| {
+                ui.label(format!("{:.1} FPS ({:.2} ms)", fps, frame_time));
+                Self::frame_time_plot(ui, frame_times);
+                ui.checkbox(&mut vsync, "VSync");
+
+                ui.collapsing("Renderer2D", |ui| {
+                    ui.label(format!("Draw calls: {}", stats.draw_calls));
+                    ui.label(format!("Quads: {}", stats.quad_count));
+                    ui.label(format!("Circles: {}", stats.circle_count));
+                    ui.label(format!("Lines: {}", stats.line_count));
+                    ui.label(format!("Vertices: {}", stats.vertex_count()));
+                    ui.label(format!("Indices: {}", stats.index_count()));
+                });
+
+                ui.collapsing("Layers", |ui| {
+                    for state in layer_states.borrow_mut().iter_mut().rev() {
+                        let label = if state.overlay {
+                            format!("{} (overlay)", state.name)
+                        } else {
+                            state.name.to_string()
+                        };
+                        ui.add_enabled(
+                            state.name != name,
+                            egui::Checkbox::new(&mut state.enabled, label),
+                        );
+                    }
+                });
+
+                ui.collapsing("Events", |ui| {
+                    ui.checkbox(log_none_events, "Show None events");
+                    egui::ScrollArea::vertical()
+                        .max_height(160.0)
+                        .stick_to_bottom(true)
+                        .show(ui, |ui| {
+                            for event in events.iter() {
+                                ui.monospace(event);
+                            }
+                        });
+                });
+
+                if let Some(buffer) = log_buffer {
+                    ui.collapsing("Log", |ui| {
+                        egui::ScrollArea::vertical()
+                            .id_source("log")
+                            .max_height(200.0)
+                            .stick_to_bottom(true)
+                            .show(ui, |ui| {
+                                for line in buffer.lines() {
+                                    ui.monospace(line);
+                                }
+                            });
+                    });
+                }
+            });
+        });
+
+        if vsync != window.is_vsync() {
+            window.set_vsync(vsync);
+        }
+    }
+}
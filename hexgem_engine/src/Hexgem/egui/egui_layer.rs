@@ -5,12 +5,24 @@ use glfw::Window;
 
 pub struct EguiLayer {
     egui_window: EguiWindow,
+    block_events: bool,
 }
 
 impl EguiLayer {
     pub fn create(window: &mut Window) -> Self {
         let egui_window = EguiWindow::create(window);
-        Self { egui_window }
+        Self {
+            egui_window,
+            block_events: true,
+        }
+    }
+
+    pub fn block_events(&mut self, block: bool) {
+        self.block_events = block;
+    }
+
+    pub fn is_blocking_events(&self) -> bool {
+        self.block_events
     }
 }
 
@@ -25,9 +37,33 @@ impl Layer for EguiLayer {
         window: &mut dyn crate::Window,
     ) {
         self.egui_window.context.handle_event(event);
+        if self.block_events && self.egui_window.context.consumes(event) {
+            *event.handled() = true;
+        }
     }
 
-    fn on_update(&mut self, window: &mut Box<dyn crate::Window>) {
-        self.egui_window.render();
+    fn on_update(&mut self, window: &mut Box<dyn crate::Window>, timestep: crate::Timestep) {
+        self.egui_window.render(|context| {
+            egui::Window::new("Egui with GLFW").show(context, |ui| {
+                egui::TopBottomPanel::top("Top").show(context, |ui| {
+                    ui.menu_button("File", |ui| {
+                        {
+                            let _ = ui.button("test 1");
+                        }
+                        ui.separator();
+                        {
+                            let _ = ui.button("test 2");
+                        }
+                    });
+                });
+
+                ui.label("A simple sine wave plotted onto a GL texture then blitted to an egui managed Image.");
+                ui.label(" ");
+                ui.text_edit_multiline(&mut "Provide Text");
+                ui.label(" ");
+                ui.add(egui::Slider::new(&mut 50., 0.0..=50.0).text("Amplitude"));
+                ui.label(" ");
+            });
+        });
     }
 }
@@ -1,6 +1,6 @@
 use crate::{
     Hexgem::{core::Size, platform::EguiPlatform::HexgemEventHandler},
-    HexgemEvent::Event,
+    HexgemEvent::{Event, EventCategory},
 };
 use egui_backend::egui::{vec2, Pos2, Rect};
 use egui_gl_glfw as egui_backend;
@@ -21,8 +21,11 @@ impl EguiWindow {
         Self { painter, context }
     }
 
-    pub fn render(&mut self) {
-        self.context.render(&mut self.painter);
+    pub fn render<F>(&mut self, ui: F)
+    where
+        F: FnOnce(&egui::Context),
+    {
+        self.context.render(&mut self.painter, ui);
     }
 }
 pub struct EguiContext {
@@ -58,32 +61,30 @@ impl EguiContext {
         self.input_state.handle_event(event);
     }
 
-    pub fn render(&mut self, painter: &mut egui_backend::Painter) {
-        self.input_state.input.time = Some(self.start_time.elapsed().as_secs_f64());
-        self.context.begin_frame(self.input_state.input.take());
-        self.input_state.pixels_per_point = self.scale;
-        egui::Window::new("Egui with GLFW").show(&self.context, |ui| {
-            egui::TopBottomPanel::top("Top").show(&self.context, |ui| {
-                ui.menu_button("File", |ui| {
-                    {
-                        let _ = ui.button("test 1");
-                    }
-                    ui.separator();
-                    {
-                        let _ = ui.button("test 2");
-                    }
-                });
-            });
+    pub fn wants_pointer_input(&self) -> bool {
+        self.context.wants_pointer_input()
+    }
 
+    pub fn wants_keyboard_input(&self) -> bool {
+        self.context.wants_keyboard_input()
+    }
 
-            ui.label("A simple sine wave plotted onto a GL texture then blitted to an egui managed Image.");
-            ui.label(" ");
-            ui.text_edit_multiline(&mut "Provide Text");
-            ui.label(" ");            
-            ui.add(egui::Slider::new(&mut 50., 0.0..=50.0).text("Amplitude"));
-            ui.label(" ");
+    pub fn consumes(&self, event: &Box<dyn Event>) -> bool {
+        let wants_pointer =
+            event.is_in_category(EventCategory::Mouse) && self.wants_pointer_input();
+        let wants_keyboard =
+            event.is_in_category(EventCategory::Keyboard) && self.wants_keyboard_input();
+        wants_pointer || wants_keyboard
+    }
 
-        });
+    pub fn render<F>(&mut self, painter: &mut egui_backend::Painter, ui: F)
+    where
+        F: FnOnce(&egui::Context),
+    {
+        self.input_state.input.time = Some(self.start_time.elapsed().as_secs_f64());
+        self.context.begin_frame(self.input_state.input.take());
+        self.input_state.pixels_per_point = self.scale;
+        ui(&self.context);
         let egui::FullOutput {
             platform_output,
             textures_delta,
@@ -1,4 +1,6 @@
+mod debug_layer;
 mod egui_layer;
 mod egui_window;
+pub use debug_layer::DebugLayer;
 pub use egui_layer::EguiLayer;
 pub use egui_window::*;
@@ -0,0 +1,101 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug)]
+pub enum HexgemError {
+    BackendInit(String),
+    WindowCreation(String),
+    GlContext(String),
+    Renderer(String),
+    Texture(String),
+    Framebuffer(String),
+    Capture(String),
+    ImageComparison(String),
+    Font(String),
+    Ecs(String),
+    Scene(String),
+    Pack(String),
+    Shader {
+        name: String,
+        file: String,
+        line: Option<u32>,
+        message: String,
+    },
+    SceneFormat {
+        file: String,
+        entity: Option<String>,
+        field: Option<String>,
+        message: String,
+    },
+    Asset {
+        path: String,
+        message: String,
+    },
+    EventMismatch {
+        event_type: String,
+        expected: &'static str,
+    },
+}
+
+impl Display for HexgemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HexgemError::BackendInit(message) => {
+                write!(f, "Could not initialize window backend: {}", message)
+            }
+            HexgemError::WindowCreation(message) => {
+                write!(f, "Could not create window: {}", message)
+            }
+            HexgemError::GlContext(message) => {
+                write!(f, "Could not set up GL context: {}", message)
+            }
+            HexgemError::Renderer(message) => write!(f, "Renderer error: {}", message),
+            HexgemError::Texture(message) => write!(f, "Texture error: {}", message),
+            HexgemError::Framebuffer(message) => write!(f, "Framebuffer error: {}", message),
+            HexgemError::Capture(message) => write!(f, "Capture error: {}", message),
+            HexgemError::ImageComparison(message) => {
+                write!(f, "Image comparison failed: {}", message)
+            }
+            HexgemError::Font(message) => write!(f, "Font error: {}", message),
+            HexgemError::Ecs(message) => write!(f, "ECS error: {}", message),
+            HexgemError::Scene(message) => write!(f, "Scene error: {}", message),
+            HexgemError::Pack(message) => write!(f, "Pack error: {}", message),
+            HexgemError::Shader {
+                name,
+                file,
+                line,
+                message,
+            } => match line {
+                Some(line) => write!(f, "Shader {} ({}:{}): {}", name, file, line, message),
+                None => write!(f, "Shader {} ({}): {}", name, file, message),
+            },
+            HexgemError::SceneFormat {
+                file,
+                entity,
+                field,
+                message,
+            } => {
+                write!(f, "Scene {}", file)?;
+                if let Some(entity) = entity {
+                    write!(f, ", entity {}", entity)?;
+                }
+                if let Some(field) = field {
+                    write!(f, ", field {}", field)?;
+                }
+                write!(f, ": {}", message)
+            }
+            HexgemError::Asset { path, message } => write!(f, "Asset {}: {}", path, message),
+            HexgemError::EventMismatch {
+                event_type,
+                expected,
+            } => write!(
+                f,
+                "Cannot downcast {} event to desired {} type",
+                event_type, expected
+            ),
+        }
+    }
+}
+
+impl Error for HexgemError {}
+
+pub type HexgemResult<T> = Result<T, HexgemError>;
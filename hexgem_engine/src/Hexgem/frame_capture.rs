@@ -0,0 +1,344 @@
+use std::{
+    cell::RefCell,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use log::{info, warn};
+
+use crate::{
+    HexgemEvent::{Event, EventType, Key, KeyboardEvent},
+    HexgemRenderer::{save_gif, Framebuffer, ImageData},
+};
+
+use super::{
+    error::{HexgemError, HexgemResult},
+    window::Window,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CaptureOutput {
+    Directory(PathBuf),
+    // Frames are kept as images and collected with FrameCapture::take_captured.
+    Memory,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CaptureFormat {
+    PngSequence,
+    Gif,
+}
+
+struct CaptureSequence {
+    name: String,
+    format: CaptureFormat,
+    remaining: u32,
+    frame: u32,
+    frames: Vec<ImageData>,
+}
+
+struct FrameCaptureState {
+    output: CaptureOutput,
+    prefix: String,
+    hotkey: Option<Key>,
+    frame_delay: Duration,
+    next_index: u32,
+    screenshot: Option<Option<PathBuf>>,
+    sequence: Option<CaptureSequence>,
+    captured: Vec<ImageData>,
+}
+
+impl Default for FrameCaptureState {
+    fn default() -> Self {
+        Self {
+            output: CaptureOutput::Directory(PathBuf::from("screenshots")),
+            prefix: "screenshot".to_string(),
+            hotkey: Some(Key::F12),
+            frame_delay: Duration::from_millis(33),
+            next_index: 0,
+            screenshot: None,
+            sequence: None,
+            captured: vec![],
+        }
+    }
+}
+
+thread_local! {
+    static FRAME_CAPTURE: RefCell<FrameCaptureState> = RefCell::new(FrameCaptureState::default());
+}
+
+// Captures rendered frames at the end of Application frames, after every layer has drawn.
+pub struct FrameCapture;
+
+impl FrameCapture {
+    pub fn set_output(output: CaptureOutput) {
+        FRAME_CAPTURE.with(|state| state.borrow_mut().output = output);
+    }
+
+    pub fn get_output() -> CaptureOutput {
+        FRAME_CAPTURE.with(|state| state.borrow().output.clone())
+    }
+
+    pub fn set_prefix(prefix: &str) {
+        FRAME_CAPTURE.with(|state| state.borrow_mut().prefix = prefix.to_string());
+    }
+
+    pub fn set_hotkey(hotkey: Option<Key>) {
+        FRAME_CAPTURE.with(|state| state.borrow_mut().hotkey = hotkey);
+    }
+
+    pub fn get_hotkey() -> Option<Key> {
+        FRAME_CAPTURE.with(|state| state.borrow().hotkey)
+    }
+
+    // Time every frame of a captured GIF stays on screen.
+    pub fn set_frame_delay(frame_delay: Duration) {
+        FRAME_CAPTURE.with(|state| state.borrow_mut().frame_delay = frame_delay);
+    }
+
+    pub fn screenshot() {
+        FRAME_CAPTURE.with(|state| state.borrow_mut().screenshot = Some(None));
+    }
+
+    // Saves the next frame to the given path, regardless of the configured output.
+    pub fn screenshot_to<P: AsRef<Path>>(path: P) {
+        let path = path.as_ref().to_path_buf();
+        FRAME_CAPTURE.with(|state| state.borrow_mut().screenshot = Some(Some(path)));
+    }
+
+    pub fn capture_frames(count: u32, format: CaptureFormat) {
+        FRAME_CAPTURE.with(|state| {
+            let mut state = state.borrow_mut();
+            if count == 0 {
+                return;
+            }
+            if state.sequence.is_some() {
+                warn!(
+                    "Frame capture already running, ignoring request for {} frames",
+                    count
+                );
+                return;
+            }
+            let name = state.next_name();
+            state.sequence = Some(CaptureSequence {
+                name,
+                format,
+                remaining: count,
+                frame: 0,
+                frames: vec![],
+            });
+        });
+    }
+
+    pub fn is_pending() -> bool {
+        FRAME_CAPTURE.with(|state| {
+            let state = state.borrow();
+            state.screenshot.is_some() || state.sequence.is_some()
+        })
+    }
+
+    pub fn is_capturing_sequence() -> bool {
+        FRAME_CAPTURE.with(|state| state.borrow().sequence.is_some())
+    }
+
+    pub fn take_captured() -> Vec<ImageData> {
+        FRAME_CAPTURE.with(|state| std::mem::take(&mut state.borrow_mut().captured))
+    }
+
+    pub fn on_event(event: &dyn Event) {
+        if event.get_event_type() != EventType::KeyPressed {
+            return;
+        }
+        let Some(event) = event.as_any().downcast_ref::<KeyboardEvent>() else {
+            return;
+        };
+        if event.pressed && !event.repeat && Self::get_hotkey() == Some(event.key) {
+            Self::screenshot();
+        }
+    }
+
+    pub fn on_frame_end(window: &dyn Window) -> HexgemResult<()> {
+        if !Self::is_pending() {
+            return Ok(());
+        }
+        let image = window.capture()?;
+        FRAME_CAPTURE.with(|state| state.borrow_mut().on_frame(image))
+    }
+
+    // Serves pending requests from a framebuffer instead of the window, for example an
+    // editor viewport. Call it after rendering into the framebuffer.
+    pub fn on_framebuffer_end(framebuffer: &Framebuffer, attachment: usize) -> HexgemResult<()> {
+        if !Self::is_pending() {
+            return Ok(());
+        }
+        let image = framebuffer.read_image(attachment)?;
+        FRAME_CAPTURE.with(|state| state.borrow_mut().on_frame(image))
+    }
+}
+
+impl FrameCaptureState {
+    // A failed write is reported only after the frame went through both the screenshot and
+    // the running sequence, so one bad frame never drops the rest of a capture.
+    fn on_frame(&mut self, image: ImageData) -> HexgemResult<()> {
+        let screenshot = match self.screenshot.take() {
+            Some(path) => self.store_screenshot(image.clone(), path),
+            None => Ok(()),
+        };
+        let sequence = self.on_sequence_frame(image);
+        screenshot.and(sequence)
+    }
+
+    fn on_sequence_frame(&mut self, image: ImageData) -> HexgemResult<()> {
+        let Some(mut sequence) = self.sequence.take() else {
+            return Ok(());
+        };
+        sequence.remaining -= 1;
+        let saved = match (&self.output, sequence.format) {
+            (CaptureOutput::Memory, _) => {
+                self.captured.push(image);
+                Ok(())
+            }
+            (CaptureOutput::Directory(directory), CaptureFormat::PngSequence) => {
+                let path = directory.join(format!("{}_{:04}.png", sequence.name, sequence.frame));
+                save_image(&image, &path)
+            }
+            (CaptureOutput::Directory(_), CaptureFormat::Gif) => {
+                sequence.frames.push(image);
+                Ok(())
+            }
+        };
+        sequence.frame += 1;
+        if sequence.remaining > 0 {
+            self.sequence = Some(sequence);
+            return saved;
+        }
+        saved?;
+        match (&self.output, sequence.format) {
+            (CaptureOutput::Directory(directory), CaptureFormat::Gif) => {
+                let path = directory.join(format!("{}.gif", sequence.name));
+                create_parent(&path)?;
+                save_gif(&sequence.frames, self.frame_delay, &path)?;
+                info!("Saved {} frames to {}", sequence.frame, path.display());
+            }
+            (CaptureOutput::Directory(directory), CaptureFormat::PngSequence) => info!(
+                "Saved {} frames to {}",
+                sequence.frame,
+                directory.join(format!("{}_*.png", sequence.name)).display()
+            ),
+            (CaptureOutput::Memory, _) => {}
+        }
+        Ok(())
+    }
+
+    fn store_screenshot(&mut self, image: ImageData, path: Option<PathBuf>) -> HexgemResult<()> {
+        let path = match (path, &self.output) {
+            (Some(path), _) => path,
+            (None, CaptureOutput::Directory(directory)) => {
+                let directory = directory.clone();
+                directory.join(format!("{}.png", self.next_name()))
+            }
+            (None, CaptureOutput::Memory) => {
+                self.captured.push(image);
+                return Ok(());
+            }
+        };
+        save_image(&image, &path)?;
+        info!("Saved screenshot to {}", path.display());
+        Ok(())
+    }
+
+    // Skips names already used by earlier runs so captures never overwrite each other.
+    fn next_name(&mut self) -> String {
+        loop {
+            let name = format!("{}_{:04}", self.prefix, self.next_index);
+            self.next_index += 1;
+            let CaptureOutput::Directory(directory) = &self.output else {
+                return name;
+            };
+            let taken = ["png", "gif"]
+                .iter()
+                .map(|extension| format!("{}.{}", name, extension))
+                .chain([format!("{}_0000.png", name)])
+                .any(|file| directory.join(file).exists());
+            if !taken {
+                return name;
+            }
+        }
+    }
+}
+
+fn create_parent(path: &Path) -> HexgemResult<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => fs::create_dir_all(parent)
+            .map_err(|err| HexgemError::Capture(format!("{}: {}", parent.display(), err))),
+        _ => Ok(()),
+    }
+}
+
+fn save_image(image: &ImageData, path: &Path) -> HexgemResult<()> {
+    create_parent(path)?;
+    image.save(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A directory below a regular file, so every write into it fails.
+    fn unwritable_directory(name: &str) -> PathBuf {
+        let file = std::env::temp_dir().join(format!(
+            "hexgem_frame_capture_{}_{}",
+            name,
+            std::process::id()
+        ));
+        fs::write(&file, b"").unwrap();
+        file.join("frames")
+    }
+
+    fn frame() -> ImageData {
+        ImageData::filled(2, 2, [255, 0, 0, 255]).unwrap()
+    }
+
+    fn sequence(count: u32, format: CaptureFormat) -> CaptureSequence {
+        CaptureSequence {
+            name: "capture".to_string(),
+            format,
+            remaining: count,
+            frame: 0,
+            frames: vec![],
+        }
+    }
+
+    #[test]
+    fn failed_sequence_write_keeps_the_capture_running() {
+        let mut state = FrameCaptureState {
+            output: CaptureOutput::Directory(unwritable_directory("sequence")),
+            sequence: Some(sequence(3, CaptureFormat::PngSequence)),
+            ..Default::default()
+        };
+        assert!(state.on_frame(frame()).is_err());
+        let running = state.sequence.as_ref().unwrap();
+        assert_eq!(running.remaining, 2);
+        assert_eq!(running.frame, 1);
+        assert!(state.on_frame(frame()).is_err());
+        assert!(state.on_frame(frame()).is_err());
+        assert!(state.sequence.is_none());
+    }
+
+    #[test]
+    fn failed_screenshot_still_records_the_sequence_frame() {
+        let mut state = FrameCaptureState {
+            output: CaptureOutput::Memory,
+            screenshot: Some(Some(unwritable_directory("screenshot").join("shot.png"))),
+            sequence: Some(sequence(2, CaptureFormat::PngSequence)),
+            ..Default::default()
+        };
+        assert!(state.on_frame(frame()).is_err());
+        assert!(state.screenshot.is_none());
+        assert_eq!(state.captured.len(), 1);
+        assert!(state.on_frame(frame()).is_ok());
+        assert_eq!(state.captured.len(), 2);
+        assert!(state.sequence.is_none());
+    }
+}
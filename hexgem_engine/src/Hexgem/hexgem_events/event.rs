@@ -1,8 +1,11 @@
 use std::any::type_name;
 
+use log::error;
+
 use crate::{
     bitOperations,
     Hexgem::core::{bit, ToAny},
+    HexgemError,
 };
 pub struct CategoryBitFlag(u32);
 bitOperations!(CategoryBitFlag);
@@ -93,7 +96,24 @@ impl<'a> EventDispatcher<'a> {
         Self { event }
     }
 
-    pub fn dispatch<I: Event + 'static, F>(&self, event_type: EventType, mut callback: F) -> bool
+    pub fn dispatch<I: Event + 'static, F>(&self, event_type: EventType, callback: F) -> bool
+    where
+        F: FnMut(&I) -> Option<bool>,
+    {
+        match self.try_dispatch(event_type, callback) {
+            Ok(handled) => handled,
+            Err(err) => {
+                error!("{}", err);
+                false
+            }
+        }
+    }
+
+    pub fn try_dispatch<I: Event + 'static, F>(
+        &self,
+        event_type: EventType,
+        mut callback: F,
+    ) -> Result<bool, HexgemError>
     where
         F: FnMut(&I) -> Option<bool>,
     {
@@ -101,15 +121,16 @@ impl<'a> EventDispatcher<'a> {
             let event_any = &self.event.as_any();
             let event = match event_any.downcast_ref::<I>() {
                 Some(e) => e,
-                None => panic!(
-                    "Cannot downcast {:?} event to desired {} type",
-                    event_type,
-                    type_name::<I>()
-                ),
+                None => {
+                    return Err(HexgemError::EventMismatch {
+                        event_type: format!("{:?}", event_type),
+                        expected: type_name::<I>(),
+                    })
+                }
             };
             let opt = callback(event);
-            return opt.map_or(true, |result| result);
+            return Ok(opt.map_or(true, |result| result));
         }
-        return false;
+        return Ok(false);
     }
 }
@@ -1,5 +1,6 @@
 use crate::Hexgem::core;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum MouseButton {
     LeftButton,
     RightButton,
@@ -11,6 +12,7 @@ pub enum MouseButton {
     Button8,
     Unknown,
 }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Key {
     Space,
     Apostrophe,
@@ -77,15 +77,24 @@ eventImpl!(
 pub struct MouseMoveEvent {
     handled: bool,
     pub position: Position<f64>,
+    // Motion reported by the backend itself, set where the position stops moving while the
+    // cursor is locked (SDL relative mode).
+    pub delta: Option<Position<f64>>,
 }
 
 impl MouseMoveEvent {
     pub fn create(position: Position<f64>) -> Self {
         Self {
             position,
+            delta: None,
             handled: false,
         }
     }
+
+    pub fn with_delta(mut self, delta: Position<f64>) -> Self {
+        self.delta = Some(delta);
+        self
+    }
 }
 eventImpl!(
     MouseMoveEvent,
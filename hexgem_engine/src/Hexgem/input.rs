@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+
+use glam::Vec2;
+
+use crate::HexgemEvent::{
+    Event, EventType, Key, KeyboardEvent, MouseButton, MouseButtonEvent, MouseMoveEvent,
+    MouseScrollEvent,
+};
+
+// Tracks held keys and buttons from the event stream so layers can poll them in on_update.
+#[derive(Default)]
+pub struct InputState {
+    keys: HashSet<Key>,
+    buttons: HashSet<MouseButton>,
+    mouse_position: Option<Vec2>,
+    mouse_delta: Vec2,
+    scroll_delta: Vec2,
+}
+
+impl InputState {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn on_event(&mut self, event: &dyn Event) {
+        let event_any = event.as_any();
+        match event.get_event_type() {
+            EventType::KeyPressed | EventType::KeyReleased => {
+                if let Some(event) = event_any.downcast_ref::<KeyboardEvent>() {
+                    if event.pressed {
+                        self.keys.insert(event.key);
+                    } else {
+                        self.keys.remove(&event.key);
+                    }
+                }
+            }
+            EventType::MouseButtonPressed | EventType::MouseButtonReleased => {
+                if let Some(event) = event_any.downcast_ref::<MouseButtonEvent>() {
+                    if event.pressed {
+                        self.buttons.insert(event.button);
+                    } else {
+                        self.buttons.remove(&event.button);
+                    }
+                }
+            }
+            EventType::MouseMoved => {
+                if let Some(event) = event_any.downcast_ref::<MouseMoveEvent>() {
+                    let position = Vec2::new(event.position.x as f32, event.position.y as f32);
+                    // GLFW keeps reporting unbounded positions while the cursor is disabled, so
+                    // the difference works there. SDL pins the position in relative mode and
+                    // sends the motion along instead.
+                    if let Some(delta) = event.delta {
+                        self.mouse_delta += Vec2::new(delta.x as f32, delta.y as f32);
+                    } else if let Some(last) = self.mouse_position {
+                        self.mouse_delta += position - last;
+                    }
+                    self.mouse_position = Some(position);
+                }
+            }
+            EventType::MouseScrolled => {
+                if let Some(event) = event_any.downcast_ref::<MouseScrollEvent>() {
+                    self.scroll_delta += Vec2::new(event.dx as f32, event.dy as f32);
+                }
+            }
+            EventType::WindowLostFocus => self.clear(),
+            _ => {}
+        }
+    }
+
+    pub fn is_key_pressed(&self, key: Key) -> bool {
+        self.keys.contains(&key)
+    }
+
+    pub fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.buttons.contains(&button)
+    }
+
+    pub fn get_mouse_position(&self) -> Option<Vec2> {
+        self.mouse_position
+    }
+
+    // Movement accumulated since the last call.
+    pub fn take_mouse_delta(&mut self) -> Vec2 {
+        std::mem::take(&mut self.mouse_delta)
+    }
+
+    pub fn take_scroll_delta(&mut self) -> Vec2 {
+        std::mem::take(&mut self.scroll_delta)
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.buttons.clear();
+        self.mouse_delta = Vec2::ZERO;
+        self.scroll_delta = Vec2::ZERO;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hexgem::core::Position;
+
+    fn move_to(x: f64, y: f64) -> MouseMoveEvent {
+        MouseMoveEvent::create(Position { x, y })
+    }
+
+    #[test]
+    fn mouse_delta_follows_positions() {
+        let mut input = InputState::create();
+        input.on_event(&move_to(10.0, 10.0));
+        assert_eq!(input.take_mouse_delta(), Vec2::ZERO);
+        input.on_event(&move_to(15.0, 8.0));
+        input.on_event(&move_to(20.0, 4.0));
+        assert_eq!(input.take_mouse_delta(), Vec2::new(10.0, -6.0));
+        assert_eq!(input.take_mouse_delta(), Vec2::ZERO);
+        assert_eq!(input.get_mouse_position(), Some(Vec2::new(20.0, 4.0)));
+    }
+
+    #[test]
+    fn reported_delta_is_used_while_the_position_is_pinned() {
+        let mut input = InputState::create();
+        for _ in 0..3 {
+            input.on_event(&move_to(640.0, 360.0).with_delta(Position { x: 4.0, y: -2.0 }));
+        }
+        assert_eq!(input.take_mouse_delta(), Vec2::new(12.0, -6.0));
+        assert_eq!(input.get_mouse_position(), Some(Vec2::new(640.0, 360.0)));
+    }
+}
@@ -2,7 +2,7 @@ use log::info;
 
 use crate::HexgemEvent::Event;
 
-use super::window::Window;
+use super::{timestep::Timestep, window::Window};
 
 pub trait Layer {
     fn get_name(&self) -> &'static str;
@@ -12,7 +12,7 @@ pub trait Layer {
     fn on_detach(&mut self) {
         info!("{} layer has been detached", self.get_name());
     }
-    fn on_update(&mut self, window: &mut Box<dyn Window>) {
+    fn on_update(&mut self, window: &mut Box<dyn Window>, timestep: Timestep) {
         // info!("Called update on {} layer", self.get_name());
     }
     fn on_event(&mut self, event: &mut Box<dyn Event>, window: &mut dyn Window);
@@ -1,11 +1,22 @@
+use std::{cell::RefCell, rc::Rc};
+
 use super::layer::Layer;
 
 type LayerList = Vec<Box<dyn Layer>>;
 type LayerIterator<'a> = Vec<&'a mut Box<dyn Layer>>;
 
+pub struct LayerState {
+    pub name: &'static str,
+    pub overlay: bool,
+    pub enabled: bool,
+}
+
+pub type LayerStates = Rc<RefCell<Vec<LayerState>>>;
+
 pub struct LayerStack {
     overlayers: LayerList,
     layers: LayerList,
+    states: LayerStates,
 }
 
 impl LayerStack {
@@ -13,6 +24,7 @@ impl LayerStack {
         Self {
             overlayers: vec![],
             layers: vec![],
+            states: Rc::new(RefCell::new(vec![])),
         }
     }
     pub fn push_layer<T>(&mut self, layer: T)
@@ -20,11 +32,23 @@ impl LayerStack {
         T: Layer + 'static,
     {
         // layer.on_attach();
+        self.states.borrow_mut().insert(
+            self.layers.len(),
+            LayerState {
+                name: layer.get_name(),
+                overlay: false,
+                enabled: true,
+            },
+        );
         self.layers.push(Box::new(layer));
     }
 
     pub fn pop_layer(&mut self) -> Option<Box<dyn Layer>> {
-        self.layers.pop()
+        let layer = self.layers.pop();
+        if layer.is_some() {
+            self.states.borrow_mut().remove(self.layers.len());
+        }
+        layer
         // if let Some(layer) = self.layers.pop() {
         //     layer.on_detach();
         //     Some(layer)
@@ -37,10 +61,19 @@ impl LayerStack {
         T: Layer + 'static,
     {
         // layer.on_attach();
+        self.states.borrow_mut().push(LayerState {
+            name: layer.get_name(),
+            overlay: true,
+            enabled: true,
+        });
         self.overlayers.push(Box::new(layer));
     }
     pub fn pop_overlay(&mut self) -> Option<Box<dyn Layer>> {
-        self.overlayers.pop()
+        let overlay = self.overlayers.pop();
+        if overlay.is_some() {
+            self.states.borrow_mut().pop();
+        }
+        overlay
         // if let Some(layer) = self.overlayers.pop() {
         //     layer.on_detach();
         //     Some(layer)
@@ -49,16 +82,26 @@ impl LayerStack {
         // }
     }
 
+    pub fn states(&self) -> LayerStates {
+        Rc::clone(&self.states)
+    }
+
     pub fn layers(&mut self) -> LayerIterator {
-        let mut vector = Vec::from_iter(self.overlayers.iter_mut());
-        vector.extend(self.layers.iter_mut());
+        let states = self.states.borrow();
+        let vector = self
+            .layers
+            .iter_mut()
+            .chain(self.overlayers.iter_mut())
+            .zip(states.iter())
+            .filter(|(_, state)| state.enabled)
+            .map(|(layer, _)| layer)
+            .collect();
         return vector;
     }
 
     pub fn layers_rev(&mut self) -> LayerIterator {
-        let mut vector =
-            Vec::from_iter(self.overlayers.iter_mut().rev().collect::<LayerIterator>());
-        vector.extend(self.layers.iter_mut().rev());
+        let mut vector = self.layers();
+        vector.reverse();
         return vector;
     }
 }
@@ -10,6 +10,7 @@ impl ColorLog for Level {
             Level::Error => format!("\x1b[31;1m[{}]\x1b[0m", &self),
             Level::Warn => format!("\x1b[33;1m[{}]\x1b[0m", &self),
             Level::Info => format!("\x1b[34;1m[{}]\x1b[0m", &self),
+            Level::Trace => format!("\x1b[35;1m[{}]\x1b[0m", &self),
             _ => format!("\x1b[32;1m[{}]\x1b[0m", &self),
         };
     }
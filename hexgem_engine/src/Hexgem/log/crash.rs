@@ -0,0 +1,276 @@
+use std::{
+    backtrace::Backtrace, collections::VecDeque, env, fs, io, panic::PanicHookInfo, path::PathBuf,
+    sync::Mutex, thread, time::SystemTime,
+};
+
+use crate::HexgemEvent::{Event, EventType};
+
+use super::{entry::format_timestamp, sink::RingBufferSink};
+
+pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+const DEFAULT_CRASH_DIRECTORY: &str = "logs/crash";
+pub(super) const DEFAULT_HISTORY: usize = 50;
+
+static EVENTS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static EVENT_HISTORY: Mutex<usize> = Mutex::new(DEFAULT_HISTORY);
+
+pub type CrashCallback = Box<dyn Fn(&CrashReport) + Send + Sync>;
+
+pub struct CrashReport {
+    pub message: String,
+    pub location: Option<String>,
+    pub thread: String,
+    pub version: &'static str,
+    pub timestamp: String,
+    pub backtrace: String,
+    pub log_lines: Vec<String>,
+    pub events: Vec<String>,
+    pub path: Option<PathBuf>,
+}
+
+impl CrashReport {
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "Hexgem crash report\nversion: {}\ntime: {}\nthread: {}\nmessage: {}\nlocation: {}\n",
+            self.version,
+            self.timestamp,
+            self.thread,
+            self.message,
+            self.location.as_deref().unwrap_or("unknown")
+        );
+        text.push_str("\n--- backtrace ---\n");
+        text.push_str(&self.backtrace);
+        text.push_str("\n--- last log lines ---\n");
+        for line in self.log_lines.iter() {
+            text.push_str(line);
+            text.push('\n');
+        }
+        text.push_str("\n--- last events ---\n");
+        for event in self.events.iter() {
+            text.push_str(event);
+            text.push('\n');
+        }
+        text
+    }
+}
+
+pub struct CrashReporter {
+    directory: Option<PathBuf>,
+    log_buffer: Option<RingBufferSink>,
+    callback: Option<CrashCallback>,
+}
+
+impl CrashReporter {
+    pub(super) fn create(
+        directory: Option<PathBuf>,
+        log_buffer: Option<RingBufferSink>,
+        callback: Option<CrashCallback>,
+    ) -> Self {
+        Self {
+            directory,
+            log_buffer,
+            callback,
+        }
+    }
+
+    // Next to the executable rather than the working directory, so reports end up in the same
+    // place however the game was launched.
+    pub fn default_directory() -> PathBuf {
+        env::current_exe()
+            .ok()
+            .and_then(|exe| {
+                exe.parent()
+                    .map(|directory| directory.join(DEFAULT_CRASH_DIRECTORY))
+            })
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CRASH_DIRECTORY))
+    }
+
+    pub fn set_event_history(size: usize) {
+        if let Ok(mut history) = EVENT_HISTORY.lock() {
+            *history = size;
+        }
+        if let Ok(mut events) = EVENTS.lock() {
+            while events.len() > size {
+                events.pop_front();
+            }
+        }
+    }
+
+    pub fn record_event(event: &Box<dyn Event>) {
+        let event_type = event.get_event_type();
+        if event_type == EventType::None {
+            return;
+        }
+        let size = EVENT_HISTORY.lock().map_or(DEFAULT_HISTORY, |size| *size);
+        if size == 0 {
+            return;
+        }
+        if let Ok(mut events) = EVENTS.lock() {
+            if events.len() >= size {
+                events.pop_front();
+            }
+            events.push_back(format!(
+                "{} {:?}",
+                format_timestamp(SystemTime::now()),
+                event_type
+            ));
+        }
+    }
+
+    pub(super) fn report(&self, message: &str, location: Option<String>) -> CrashReport {
+        let current = thread::current();
+        let mut report = CrashReport {
+            message: message.to_string(),
+            location,
+            thread: current.name().unwrap_or("<unnamed>").to_string(),
+            version: ENGINE_VERSION,
+            timestamp: format_timestamp(SystemTime::now()),
+            backtrace: Backtrace::force_capture().to_string(),
+            log_lines: self
+                .log_buffer
+                .as_ref()
+                .map_or(vec![], |buffer| buffer.lines()),
+            events: EVENTS
+                .lock()
+                .map_or(vec![], |events| events.iter().cloned().collect()),
+            path: None,
+        };
+        if let Some(directory) = &self.directory {
+            match Self::write(directory, &report) {
+                Ok(path) => report.path = Some(path),
+                Err(err) => eprintln!("Could not write crash report: {}", err),
+            }
+        }
+        if let Some(callback) = &self.callback {
+            callback(&report);
+        }
+        report
+    }
+
+    pub(super) fn message(info: &PanicHookInfo) -> String {
+        if let Some(mess) = info.payload().downcast_ref::<&str>() {
+            mess.to_string()
+        } else if let Some(mess) = info.payload().downcast_ref::<String>() {
+            mess.clone()
+        } else {
+            String::from("Panic occured!")
+        }
+    }
+
+    fn write(directory: &PathBuf, report: &CrashReport) -> io::Result<PathBuf> {
+        fs::create_dir_all(directory)?;
+        let name = format!("crash-{}.txt", report.timestamp.replace([':', '.'], "-"));
+        let path = directory.join(name);
+        fs::write(&path, report.to_text())?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use log::{Level, Record};
+
+    use super::*;
+    use crate::{
+        Hexgem::log::{entry::LogEntry, sink::LogSink},
+        HexgemEvent::{NoneEvent, WindowCloseEvent, WindowResizeEvent},
+        HexgemMath::Size,
+    };
+
+    #[test]
+    fn event_history_keeps_the_latest_events() {
+        // The history is process wide, this is the only test recording into it.
+        CrashReporter::set_event_history(2);
+        let events: [Box<dyn Event>; 4] = [
+            Box::new(WindowCloseEvent::create()),
+            Box::new(NoneEvent::create()),
+            Box::new(WindowResizeEvent::create(Size {
+                width: 1,
+                height: 1,
+            })),
+            Box::new(WindowCloseEvent::create()),
+        ];
+        for event in events.iter() {
+            CrashReporter::record_event(event);
+        }
+        let recorded = EVENTS.lock().unwrap().clone();
+        assert_eq!(recorded.len(), 2);
+        assert!(recorded[0].ends_with(" WindowResize"), "{:?}", recorded);
+        assert!(recorded[1].ends_with(" WindowClose"), "{:?}", recorded);
+
+        CrashReporter::set_event_history(1);
+        assert_eq!(EVENTS.lock().unwrap().len(), 1);
+        CrashReporter::set_event_history(0);
+        CrashReporter::record_event(&events[0]);
+        assert!(EVENTS.lock().unwrap().is_empty());
+        CrashReporter::set_event_history(DEFAULT_HISTORY);
+    }
+
+    #[test]
+    fn report_is_written_and_passed_to_the_callback() {
+        let directory = std::env::temp_dir().join(format!("hexgem_crash_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let buffer = RingBufferSink::create(2);
+        for message in ["one", "two", "three"] {
+            buffer.write(&LogEntry::from_record(
+                &Record::builder()
+                    .args(format_args!("{}", message))
+                    .level(Level::Error)
+                    .target("game")
+                    .build(),
+            ));
+        }
+        let calls = Arc::new(AtomicUsize::new(0));
+        let reporter = CrashReporter::create(Some(directory.clone()), Some(buffer), {
+            let calls = calls.clone();
+            Some(Box::new(move |report: &CrashReport| {
+                assert_eq!(report.message, "boom");
+                calls.fetch_add(1, Ordering::SeqCst);
+            }))
+        });
+
+        let report = reporter.report("boom", Some("src/main.rs:3:5".to_string()));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(report.log_lines, ["[ERROR] - two", "[ERROR] - three"]);
+        let path = report.path.clone().unwrap();
+        assert!(path.starts_with(&directory));
+        let text = fs::read_to_string(&path).unwrap();
+        assert_eq!(text, report.to_text());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn report_text_has_every_section() {
+        let report = CrashReport {
+            message: "boom".to_string(),
+            location: None,
+            thread: "main".to_string(),
+            version: "1.2.3",
+            timestamp: "2024-03-01T12:30:05.123Z".to_string(),
+            backtrace: "0: main".to_string(),
+            log_lines: vec!["[ERROR] - one".to_string()],
+            events: vec!["2024-03-01T12:30:05.000Z WindowClose".to_string()],
+            path: None,
+        };
+        assert_eq!(
+            report.to_text(),
+            "Hexgem crash report\n\
+             version: 1.2.3\n\
+             time: 2024-03-01T12:30:05.123Z\n\
+             thread: main\n\
+             message: boom\n\
+             location: unknown\n\
+             \n--- backtrace ---\n\
+             0: main\n\
+             --- last log lines ---\n\
+             [ERROR] - one\n\
+             \n--- last events ---\n\
+             2024-03-01T12:30:05.000Z WindowClose\n"
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use super::format::{FieldCollector, FieldValue};
+use crate::Hexgem::level::ColorLog;
+use log::{Level, Record};
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Clone, Debug)]
+pub struct LogEntry {
+    pub time: SystemTime,
+    pub level: Level,
+    pub target: String,
+    pub module_path: Option<String>,
+    pub location: Option<String>,
+    pub message: String,
+    pub fields: Vec<(String, FieldValue)>,
+}
+
+impl LogEntry {
+    pub fn from_record(record: &Record) -> Self {
+        let target = record.target();
+        let location = {
+            let source: Vec<&str> = target.split(":").collect();
+            if Path::new(source[0]).is_file() {
+                Some(String::from(target))
+            } else {
+                match (record.file(), record.line()) {
+                    (Some(file_path), Some(line)) => Some([file_path, &line.to_string()].join(":")),
+                    _ => None,
+                }
+            }
+        };
+        let mut fields = FieldCollector(vec![]);
+        let _ = record.key_values().visit(&mut fields);
+        Self {
+            time: SystemTime::now(),
+            level: record.level(),
+            target: target.to_string(),
+            module_path: record.module_path().map(String::from),
+            location,
+            message: record.args().to_string(),
+            fields: fields.0,
+        }
+    }
+
+    pub fn client(&self) -> Option<&'static str> {
+        self.module_path.as_deref().map(|path| path.get_client())
+    }
+
+    pub fn timestamp(&self) -> String {
+        format_timestamp(self.time)
+    }
+
+    pub fn format(&self, color: bool) -> String {
+        let mut message = self.message.clone();
+        for (key, value) in self.fields.iter() {
+            message.push_str(&format!(" {}={}", key, value));
+        }
+        let level = if color {
+            self.level.format_color()
+        } else {
+            format!("[{}]", self.level)
+        };
+        match (&self.module_path, &self.location, self.client()) {
+            (Some(mod_path), Some(path), Some(client)) => {
+                let client = if color {
+                    format!("\x1b[36;4m{}\x1b[0m", client)
+                } else {
+                    client.to_string()
+                };
+                match (self.level, color) {
+                    (Level::Error | Level::Warn, true) => format!(
+                        "{} - {}: {} \x1b[90;3min {} at {}\x1b[0m",
+                        level, client, message, path, mod_path
+                    ),
+                    (Level::Error | Level::Warn, false) => format!(
+                        "{} - {}: {} in {} at {}",
+                        level, client, message, path, mod_path
+                    ),
+                    _ => format!("{} - {}: {}", level, client, message),
+                }
+            }
+            _ => format!("{} - {}", level, message),
+        }
+    }
+}
+
+pub(crate) trait ClientGet {
+    fn get_client(&self) -> &'static str;
+}
+
+impl ClientGet for &str {
+    fn get_client(&self) -> &'static str {
+        let parts = self.split("::").collect::<Vec<&str>>();
+        if parts[0] == "hexgem_engine" {
+            return "Hexgem engine";
+        } else {
+            return "Hexgem application";
+        }
+    }
+}
+
+// RFC 3339 in UTC, e.g. `2024-03-01T12:30:05.123Z`.
+pub fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, day_secs) = (secs / 86_400, secs % 86_400);
+
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        day_secs / 3_600,
+        day_secs % 3_600 / 60,
+        day_secs % 60,
+        since_epoch.subsec_millis()
+    )
+}
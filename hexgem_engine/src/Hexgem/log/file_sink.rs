@@ -0,0 +1,200 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use super::{entry::LogEntry, format::LogFormat, sink::LogSink};
+
+const DEFAULT_MAX_SIZE: u64 = 10 * 1024 * 1024;
+const DEFAULT_MAX_FILES: usize = 5;
+
+struct OpenFile {
+    file: File,
+    size: u64,
+}
+
+pub struct FileSink {
+    path: PathBuf,
+    max_size: u64,
+    max_files: usize,
+    format: LogFormat,
+    file: Mutex<OpenFile>,
+}
+
+impl FileSink {
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::rotating(path, DEFAULT_MAX_SIZE, DEFAULT_MAX_FILES)
+    }
+
+    /// Once `max_size` bytes would be exceeded the file is moved to `<path>.1`, older files shift
+    /// up by one and everything past `<path>.<max_files>` is removed.
+    pub fn rotating<P: AsRef<Path>>(path: P, max_size: u64, max_files: usize) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let file = Self::open(&path)?;
+        Ok(Self {
+            path,
+            max_size,
+            max_files,
+            format: LogFormat::Text,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn with_format(mut self, format: LogFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn open(path: &Path) -> io::Result<OpenFile> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(OpenFile { file, size })
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&self, open_file: &mut OpenFile) -> io::Result<()> {
+        open_file.file.flush()?;
+        if self.max_files == 0 {
+            open_file.file = File::create(&self.path)?;
+            open_file.size = 0;
+            return Ok(());
+        }
+        let _ = fs::remove_file(self.rotated_path(self.max_files));
+        for index in (1..self.max_files).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated_path(1))?;
+        *open_file = Self::open(&self.path)?;
+        Ok(())
+    }
+
+    fn write_line(&self, line: &str) -> io::Result<()> {
+        let mut open_file = match self.file.lock() {
+            Ok(file) => file,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let length = line.len() as u64 + 1;
+        if open_file.size > 0 && open_file.size + length > self.max_size {
+            self.rotate(&mut open_file)?;
+        }
+        writeln!(open_file.file, "{}", line)?;
+        open_file.size += length;
+        Ok(())
+    }
+}
+
+impl LogSink for FileSink {
+    fn write(&self, entry: &LogEntry) {
+        if let Err(err) = self.write_line(&self.format.format_line(entry)) {
+            eprintln!("Could not write log to {}: {}", self.path.display(), err);
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut open_file) = self.file.lock() {
+            let _ = open_file.file.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use log::{Level, Record};
+
+    use super::*;
+
+    fn write(sink: &FileSink, message: &str) {
+        sink.write(&LogEntry::from_record(
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .level(Level::Info)
+                .target("game")
+                .build(),
+        ));
+    }
+
+    fn directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("hexgem_file_sink_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    fn read(path: PathBuf) -> Vec<String> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| line.split_once(' ').unwrap().1.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn rotation_shifts_files_and_drops_the_oldest() {
+        let directory = directory("rotation");
+        let path = directory.join("logs/game.log");
+        // Every line is over half the limit, so each one starts a new file.
+        let sink = FileSink::rotating(&path, 50, 2).unwrap();
+        for message in ["one", "two", "three", "four", "five"] {
+            write(&sink, message);
+        }
+        sink.flush();
+
+        assert_eq!(read(path.clone()), ["[INFO] - five"]);
+        assert_eq!(read(sink.rotated_path(1)), ["[INFO] - four"]);
+        assert_eq!(read(sink.rotated_path(2)), ["[INFO] - three"]);
+        assert!(!sink.rotated_path(3).exists());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn lines_are_appended_until_the_limit() {
+        let directory = directory("append");
+        let path = directory.join("game.log");
+        let sink = FileSink::rotating(&path, 1024, 2).unwrap();
+        write(&sink, "one");
+        write(&sink, "two");
+        drop(sink);
+
+        // Reopening continues the existing file.
+        let sink = FileSink::rotating(&path, 1024, 2).unwrap();
+        write(&sink, "three");
+        sink.flush();
+        assert_eq!(
+            read(path),
+            ["[INFO] - one", "[INFO] - two", "[INFO] - three"]
+        );
+        assert!(!sink.rotated_path(1).exists());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn no_rotated_files_truncates_instead() {
+        let directory = directory("truncate");
+        let path = directory.join("game.log");
+        let sink = FileSink::rotating(&path, 50, 0).unwrap();
+        for message in ["one", "two", "three"] {
+            write(&sink, message);
+        }
+        sink.flush();
+        assert_eq!(read(path), ["[INFO] - three"]);
+        assert!(!sink.rotated_path(1).exists());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+use log::{LevelFilter, Metadata};
+use std::str::FromStr;
+
+struct Directive {
+    module: String,
+    level: LevelFilter,
+}
+
+pub struct LogFilter {
+    level: LevelFilter,
+    directives: Vec<Directive>,
+}
+
+impl LogFilter {
+    pub fn create(level: LevelFilter) -> Self {
+        Self {
+            level,
+            directives: vec![],
+        }
+    }
+
+    pub fn set_level(&mut self, level: LevelFilter) {
+        self.level = level;
+    }
+
+    pub fn add_directive(&mut self, module: &str, level: LevelFilter) {
+        let module = module.trim_end_matches("::").to_string();
+        match self.directives.iter_mut().find(|d| d.module == module) {
+            Some(directive) => directive.level = level,
+            None => self.directives.push(Directive { module, level }),
+        }
+    }
+
+    /// Parses `RUST_LOG` style directives, e.g. `warn,hexgem_engine::platform=error,game=trace`.
+    pub fn parse(&mut self, spec: &str) {
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let mut split = part.splitn(2, '=');
+            let (name, level) = (split.next().unwrap_or("").trim(), split.next());
+            match level {
+                Some(level) => match LevelFilter::from_str(level.trim()) {
+                    Ok(level) => self.add_directive(name, level),
+                    Err(_) => eprintln!("Invalid log level '{}' in directive '{}'", level, part),
+                },
+                None => match LevelFilter::from_str(name) {
+                    Ok(level) => self.level = level,
+                    Err(_) => self.add_directive(name, LevelFilter::Trace),
+                },
+            }
+        }
+    }
+
+    pub fn max_level(&self) -> LevelFilter {
+        self.directives
+            .iter()
+            .map(|d| d.level)
+            .fold(self.level, Ord::max)
+    }
+
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        let target = normalize_target(target);
+        self.directives
+            .iter()
+            .filter(|d| {
+                target == d.module
+                    || (target.starts_with(&d.module) && target[d.module.len()..].starts_with("::"))
+            })
+            .max_by_key(|d| d.module.len())
+            .map_or(self.level, |d| d.level)
+    }
+
+    pub fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level_for(metadata.target())
+    }
+}
+
+// Engine modules live under the private `Hexgem` module but are exported from the crate root,
+// so `hexgem_engine::platform` should match `hexgem_engine::Hexgem::platform::...` too.
+fn normalize_target(target: &str) -> String {
+    match target.strip_prefix("hexgem_engine::Hexgem") {
+        Some(rest) => format!("hexgem_engine{}", rest),
+        None => target.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(spec: &str) -> LogFilter {
+        let mut filter = LogFilter::create(LevelFilter::Info);
+        filter.parse(spec);
+        filter
+    }
+
+    #[test]
+    fn bare_level_sets_the_default() {
+        let filter = parse("warn");
+        assert_eq!(filter.level_for("game"), LevelFilter::Warn);
+        assert_eq!(filter.max_level(), LevelFilter::Warn);
+        assert_eq!(parse("").level_for("game"), LevelFilter::Info);
+    }
+
+    #[test]
+    fn module_directives_override_the_default() {
+        let filter = parse(" error , game=trace,hexgem_engine::platform=warn ");
+        assert_eq!(filter.level_for("other"), LevelFilter::Error);
+        assert_eq!(filter.level_for("game"), LevelFilter::Trace);
+        assert_eq!(filter.level_for("game::player"), LevelFilter::Trace);
+        assert_eq!(filter.level_for("gameplay"), LevelFilter::Error);
+        assert_eq!(filter.max_level(), LevelFilter::Trace);
+        // A bare module name enables everything in it.
+        assert_eq!(parse("editor").level_for("editor::ui"), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn longest_prefix_wins() {
+        let filter = parse(
+            "hexgem_engine=info,hexgem_engine::renderer::text=trace,hexgem_engine::renderer=error",
+        );
+        assert_eq!(
+            filter.level_for("hexgem_engine::renderer::text::layout"),
+            LevelFilter::Trace
+        );
+        assert_eq!(
+            filter.level_for("hexgem_engine::renderer::buffer"),
+            LevelFilter::Error
+        );
+        assert_eq!(filter.level_for("hexgem_engine::ecs"), LevelFilter::Info);
+        // Targets inside the private Hexgem module match the exported paths.
+        assert_eq!(
+            filter.level_for("hexgem_engine::Hexgem::renderer::buffer"),
+            LevelFilter::Error
+        );
+    }
+
+    #[test]
+    fn later_directives_replace_earlier_ones() {
+        let filter = parse("game=trace,game::=warn");
+        assert_eq!(filter.level_for("game"), LevelFilter::Warn);
+        assert_eq!(filter.directives.len(), 1);
+    }
+
+    #[test]
+    fn invalid_levels_are_ignored() {
+        let filter = parse("debug,game=loud,editor=off");
+        assert_eq!(filter.level_for("game"), LevelFilter::Debug);
+        assert_eq!(filter.level_for("editor"), LevelFilter::Off);
+        assert_eq!(filter.directives.len(), 1);
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt::{self, Display, Write};
+
+use log::kv::{self, Key, Value, VisitSource};
+
+use super::entry::LogEntry;
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl LogFormat {
+    pub fn format(&self, entry: &LogEntry, color: bool) -> String {
+        match self {
+            LogFormat::Text => entry.format(color),
+            LogFormat::Json => to_json(entry),
+        }
+    }
+
+    // For sinks that keep lines around, like files. JSON carries its own timestamp.
+    pub fn format_line(&self, entry: &LogEntry) -> String {
+        match self {
+            LogFormat::Text => format!("{} {}", entry.timestamp(), entry.format(false)),
+            LogFormat::Json => to_json(entry),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    Bool(bool),
+    Int(i64),
+    Uint(u64),
+    Float(f64),
+    Text(String),
+}
+
+impl From<Value<'_>> for FieldValue {
+    fn from(value: Value<'_>) -> Self {
+        if let Some(v) = value.to_bool() {
+            FieldValue::Bool(v)
+        } else if let Some(v) = value.to_i64() {
+            FieldValue::Int(v)
+        } else if let Some(v) = value.to_u64() {
+            FieldValue::Uint(v)
+        } else if let Some(v) = value.to_f64() {
+            FieldValue::Float(v)
+        } else {
+            FieldValue::Text(value.to_string())
+        }
+    }
+}
+
+impl Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Bool(v) => write!(f, "{}", v),
+            FieldValue::Int(v) => write!(f, "{}", v),
+            FieldValue::Uint(v) => write!(f, "{}", v),
+            FieldValue::Float(v) => write!(f, "{}", v),
+            FieldValue::Text(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+pub(super) struct FieldCollector(pub Vec<(String, FieldValue)>);
+
+impl<'kvs> VisitSource<'kvs> for FieldCollector {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        self.0.push((key.to_string(), FieldValue::from(value)));
+        Ok(())
+    }
+}
+
+fn to_json(entry: &LogEntry) -> String {
+    let mut json = String::from("{");
+    push_field(&mut json, "timestamp", &entry.timestamp());
+    json.push(',');
+    push_field(&mut json, "level", entry.level.as_str());
+    if let Some(client) = entry.client() {
+        json.push(',');
+        push_field(&mut json, "client", client);
+    }
+    json.push(',');
+    push_field(&mut json, "target", &entry.target);
+    if let Some(module) = &entry.module_path {
+        json.push(',');
+        push_field(&mut json, "module", module);
+    }
+    if let Some(location) = &entry.location {
+        json.push(',');
+        push_field(&mut json, "location", location);
+    }
+    json.push(',');
+    push_field(&mut json, "message", &entry.message);
+    if !entry.fields.is_empty() {
+        json.push_str(",\"fields\":{");
+        for (i, (key, value)) in entry.fields.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            push_json_string(&mut json, key);
+            json.push(':');
+            push_value(&mut json, value);
+        }
+        json.push('}');
+    }
+    json.push('}');
+    json
+}
+
+fn push_field(json: &mut String, key: &str, value: &str) {
+    push_json_string(json, key);
+    json.push(':');
+    push_json_string(json, value);
+}
+
+fn push_value(json: &mut String, value: &FieldValue) {
+    match value {
+        FieldValue::Text(text) => push_json_string(json, text),
+        FieldValue::Float(v) if !v.is_finite() => json.push_str("null"),
+        _ => {
+            let _ = write!(json, "{}", value);
+        }
+    }
+}
+
+pub(crate) fn push_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use log::Level;
+
+    use super::*;
+
+    fn entry(message: &str, fields: Vec<(&str, FieldValue)>) -> LogEntry {
+        LogEntry {
+            time: UNIX_EPOCH + Duration::from_millis(1_500),
+            level: Level::Warn,
+            target: "game".to_string(),
+            module_path: None,
+            location: None,
+            message: message.to_string(),
+            fields: fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn json_escapes_quotes_and_control_characters() {
+        let json =
+            LogFormat::Json.format(&entry("say \"hi\"\\\n\tend\r\u{1}\u{1f}", vec![]), false);
+        assert_eq!(
+            json,
+            r#"{"timestamp":"1970-01-01T00:00:01.500Z","level":"WARN","target":"game","message":"say \"hi\"\\\n\tend\r\u0001\u001f"}"#
+        );
+    }
+
+    #[test]
+    fn json_keeps_non_ascii_as_is() {
+        let json = LogFormat::Json.format(&entry("héllo → 世界 🎮", vec![]), false);
+        assert!(json.contains(r#""message":"héllo → 世界 🎮""#), "{}", json);
+    }
+
+    #[test]
+    fn json_fields_are_escaped_and_typed() {
+        let json = LogFormat::Json.format(
+            &entry(
+                "fields",
+                vec![
+                    ("name", FieldValue::Text("a \"b\"\nç".to_string())),
+                    ("key \"quoted\"", FieldValue::Int(-3)),
+                    ("ok", FieldValue::Bool(true)),
+                    ("ratio", FieldValue::Float(0.5)),
+                    ("nan", FieldValue::Float(f64::NAN)),
+                ],
+            ),
+            false,
+        );
+        assert!(
+            json.ends_with(
+                r#""fields":{"name":"a \"b\"\nç","key \"quoted\"":-3,"ok":true,"ratio":0.5,"nan":null}}"#
+            ),
+            "{}",
+            json
+        );
+    }
+
+    #[test]
+    fn text_lines_are_prefixed_with_the_timestamp() {
+        let entry = entry("saved", vec![("slot", FieldValue::Uint(2))]);
+        assert_eq!(
+            LogFormat::Text.format(&entry, false),
+            "[WARN] - saved slot=2"
+        );
+        assert_eq!(
+            LogFormat::Text.format_line(&entry),
+            "1970-01-01T00:00:01.500Z [WARN] - saved slot=2"
+        );
+        assert_eq!(
+            LogFormat::Json.format_line(&entry),
+            LogFormat::Json.format(&entry, false)
+        );
+    }
+}
@@ -0,0 +1,209 @@
+mod crash;
+mod entry;
+mod file_sink;
+mod filter;
+mod format;
+mod sink;
+
+use log::*;
+use std::{
+    env,
+    panic::{self, Location},
+    path::PathBuf,
+};
+
+pub use crash::{CrashCallback, CrashReport, CrashReporter, ENGINE_VERSION};
+pub use entry::{format_timestamp, LogEntry};
+pub use file_sink::FileSink;
+pub use filter::LogFilter;
+#[cfg(feature = "profiling")]
+pub(crate) use format::push_json_string;
+pub use format::{FieldValue, LogFormat};
+pub use sink::{CaptureSink, ConsoleSink, LogSink, RingBufferSink};
+
+pub const LOG_ENV: &str = "HEXGEM_LOG";
+const FALLBACK_LOG_ENV: &str = "RUST_LOG";
+
+pub struct HexgemLogger {
+    filter: LogFilter,
+    sinks: Vec<Box<dyn LogSink>>,
+}
+
+impl log::Log for HexgemLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.filter.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            let entry = LogEntry::from_record(record);
+            for sink in self.sinks.iter() {
+                sink.write(&entry);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        for sink in self.sinks.iter() {
+            sink.flush();
+        }
+    }
+}
+
+pub struct HexgemLoggerBuilder {
+    filter: LogFilter,
+    console: bool,
+    format: LogFormat,
+    sinks: Vec<Box<dyn LogSink>>,
+    clear_terminal: bool,
+    crash_directory: Option<PathBuf>,
+    crash_log_lines: usize,
+    crash_events: usize,
+    crash_callback: Option<CrashCallback>,
+}
+
+impl HexgemLoggerBuilder {
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.filter.set_level(level);
+        self
+    }
+
+    pub fn module_filter(mut self, module: &str, level: LevelFilter) -> Self {
+        self.filter.add_directive(module, level);
+        self
+    }
+
+    pub fn parse_filters(mut self, spec: &str) -> Self {
+        self.filter.parse(spec);
+        self
+    }
+
+    pub fn console(mut self, enabled: bool) -> Self {
+        self.console = enabled;
+        self
+    }
+
+    pub fn format(mut self, format: LogFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn sink<S>(mut self, sink: S) -> Self
+    where
+        S: LogSink + 'static,
+    {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    pub fn clear_terminal(mut self, clear: bool) -> Self {
+        self.clear_terminal = clear;
+        self
+    }
+
+    pub fn crash_directory(mut self, directory: Option<PathBuf>) -> Self {
+        self.crash_directory = directory;
+        self
+    }
+
+    pub fn crash_log_lines(mut self, lines: usize) -> Self {
+        self.crash_log_lines = lines;
+        self
+    }
+
+    pub fn crash_events(mut self, events: usize) -> Self {
+        self.crash_events = events;
+        self
+    }
+
+    pub fn on_crash<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&CrashReport) + Send + Sync + 'static,
+    {
+        self.crash_callback = Some(Box::new(callback));
+        self
+    }
+
+    pub fn parse_env(self) -> Self {
+        match env::var(LOG_ENV).or_else(|_| env::var(FALLBACK_LOG_ENV)) {
+            Ok(spec) => self.parse_filters(&spec),
+            Err(_) => self,
+        }
+    }
+
+    pub fn build(mut self) -> HexgemLogger {
+        if self.console {
+            let console = ConsoleSink::create().with_format(self.format);
+            self.sinks.insert(0, Box::new(console));
+        }
+        HexgemLogger {
+            filter: self.filter,
+            sinks: self.sinks,
+        }
+    }
+
+    pub fn init(mut self) -> Result<(), SetLoggerError> {
+        let log_buffer = if self.crash_log_lines > 0 {
+            let buffer = RingBufferSink::create(self.crash_log_lines);
+            self.sinks.push(Box::new(buffer.clone()));
+            Some(buffer)
+        } else {
+            None
+        };
+        let reporter = CrashReporter::create(
+            self.crash_directory.take(),
+            log_buffer,
+            self.crash_callback.take(),
+        );
+        CrashReporter::set_event_history(self.crash_events);
+        let clear_terminal = self.clear_terminal;
+        let logger = self.build();
+        let max_level = logger.filter.max_level();
+        HexgemLogger::set_panic_hook(reporter);
+        if clear_terminal {
+            println!("\x1bc");
+        }
+        log::set_boxed_logger(Box::new(logger)).map(|()| log::set_max_level(max_level))
+    }
+}
+
+impl HexgemLogger {
+    pub fn builder() -> HexgemLoggerBuilder {
+        HexgemLoggerBuilder {
+            filter: LogFilter::create(LevelFilter::Debug),
+            console: true,
+            format: LogFormat::Text,
+            sinks: vec![],
+            clear_terminal: false,
+            crash_directory: Some(CrashReporter::default_directory()),
+            crash_log_lines: 50,
+            crash_events: crash::DEFAULT_HISTORY,
+            crash_callback: None,
+        }
+    }
+
+    pub fn init() -> Result<(), SetLoggerError> {
+        Self::builder().parse_env().init()
+    }
+
+    fn set_panic_hook(reporter: CrashReporter) {
+        panic::set_hook({
+            Box::new(move |info| {
+                let location = info.location();
+                let content = CrashReporter::message(info);
+                Self::log(&content, location);
+                let report = reporter.report(&content, location.map(|l| l.to_string()));
+                if let Some(path) = report.path {
+                    error!("Crash report written to {}", path.display());
+                }
+            })
+        });
+    }
+
+    fn log(content: &str, location: Option<&Location<'_>>) {
+        match location {
+            Some(location) => error!(target: &location.to_string(), "{}", content),
+            None => error!("{}", content),
+        };
+    }
+}
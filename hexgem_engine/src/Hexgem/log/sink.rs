@@ -0,0 +1,198 @@
+use std::{
+    collections::VecDeque,
+    io::{self, IsTerminal, Write},
+    sync::{Arc, Mutex},
+};
+
+use super::{entry::LogEntry, format::LogFormat};
+
+pub trait LogSink: Send + Sync {
+    fn write(&self, entry: &LogEntry);
+    fn flush(&self) {}
+}
+
+pub struct ConsoleSink {
+    color: bool,
+    format: LogFormat,
+}
+
+impl ConsoleSink {
+    pub fn create() -> Self {
+        Self {
+            color: io::stdout().is_terminal(),
+            format: LogFormat::Text,
+        }
+    }
+
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_format(mut self, format: LogFormat) -> Self {
+        self.format = format;
+        self
+    }
+}
+
+impl LogSink for ConsoleSink {
+    fn write(&self, entry: &LogEntry) {
+        println!("{}", self.format.format(entry, self.color));
+    }
+
+    fn flush(&self) {
+        let _ = io::stdout().flush();
+    }
+}
+
+#[derive(Clone)]
+pub struct RingBufferSink {
+    capacity: usize,
+    entries: Arc<Mutex<VecDeque<LogEntry>>>,
+}
+
+impl RingBufferSink {
+    pub fn create(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    pub fn entries(&self) -> Vec<LogEntry> {
+        match self.entries.lock() {
+            Ok(entries) => entries.iter().cloned().collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.entries()
+            .iter()
+            .map(|entry| entry.format(false))
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
+
+impl LogSink for RingBufferSink {
+    fn write(&self, entry: &LogEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.len() == self.capacity {
+                entries.pop_front();
+            }
+            entries.push_back(entry.clone());
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct CaptureSink {
+    entries: Arc<Mutex<Vec<LogEntry>>>,
+}
+
+impl CaptureSink {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn entries(&self) -> Vec<LogEntry> {
+        match self.entries.lock() {
+            Ok(entries) => entries.clone(),
+            Err(_) => vec![],
+        }
+    }
+
+    pub fn contains(&self, message: &str) -> bool {
+        self.entries()
+            .iter()
+            .any(|entry| entry.message.contains(message))
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
+
+impl LogSink for CaptureSink {
+    fn write(&self, entry: &LogEntry) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.push(entry.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use log::{Level, Log, Record};
+
+    use super::*;
+    use crate::Hexgem::log::HexgemLogger;
+
+    fn entry(level: Level, message: &str) -> LogEntry {
+        LogEntry::from_record(
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .level(level)
+                .target("game")
+                .build(),
+        )
+    }
+
+    #[test]
+    fn capture_sink_records_what_the_logger_lets_through() {
+        let capture = CaptureSink::create();
+        let logger = HexgemLogger::builder()
+            .console(false)
+            .level(log::LevelFilter::Info)
+            .sink(capture.clone())
+            .build();
+        for (level, message) in [(Level::Info, "loaded"), (Level::Debug, "details")] {
+            logger.log(
+                &Record::builder()
+                    .args(format_args!("{}", message))
+                    .level(level)
+                    .target("game")
+                    .build(),
+            );
+        }
+        assert!(capture.contains("load"));
+        assert!(!capture.contains("details"));
+        let entries = capture.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].level, Level::Info);
+        assert_eq!(entries[0].target, "game");
+
+        capture.clear();
+        assert!(capture.entries().is_empty());
+    }
+
+    #[test]
+    fn ring_buffer_evicts_the_oldest_entries() {
+        let buffer = RingBufferSink::create(2);
+        for message in ["one", "two", "three"] {
+            buffer.write(&entry(Level::Warn, message));
+        }
+        let messages: Vec<_> = buffer.entries().into_iter().map(|e| e.message).collect();
+        assert_eq!(messages, ["two", "three"]);
+        assert_eq!(buffer.lines(), ["[WARN] - two", "[WARN] - three"]);
+
+        // Clones share the buffer, which is how the crash reporter reads it.
+        buffer.clone().clear();
+        assert!(buffer.entries().is_empty());
+
+        let disabled = RingBufferSink::create(0);
+        disabled.write(&entry(Level::Warn, "one"));
+        assert!(disabled.entries().is_empty());
+    }
+}
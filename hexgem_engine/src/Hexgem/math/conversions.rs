@@ -0,0 +1,118 @@
+use glam::{IVec2, Vec2};
+
+use crate::Hexgem::core::{Position, Size};
+
+impl From<Position<f32>> for Vec2 {
+    fn from(value: Position<f32>) -> Self {
+        Vec2::new(value.x, value.y)
+    }
+}
+
+impl From<Position<f64>> for Vec2 {
+    fn from(value: Position<f64>) -> Self {
+        Vec2::new(value.x as f32, value.y as f32)
+    }
+}
+
+impl From<Position<i32>> for Vec2 {
+    fn from(value: Position<i32>) -> Self {
+        Vec2::new(value.x as f32, value.y as f32)
+    }
+}
+
+impl From<Position<i32>> for IVec2 {
+    fn from(value: Position<i32>) -> Self {
+        IVec2::new(value.x, value.y)
+    }
+}
+
+impl From<Vec2> for Position<f32> {
+    fn from(value: Vec2) -> Self {
+        Position {
+            x: value.x,
+            y: value.y,
+        }
+    }
+}
+
+impl From<Size<f32>> for Vec2 {
+    fn from(value: Size<f32>) -> Self {
+        Vec2::new(value.width, value.height)
+    }
+}
+
+impl From<Size<i32>> for Vec2 {
+    fn from(value: Size<i32>) -> Self {
+        Vec2::new(value.width as f32, value.height as f32)
+    }
+}
+
+impl From<Size<u32>> for Vec2 {
+    fn from(value: Size<u32>) -> Self {
+        Vec2::new(value.width as f32, value.height as f32)
+    }
+}
+
+impl From<Size<i32>> for IVec2 {
+    fn from(value: Size<i32>) -> Self {
+        IVec2::new(value.width, value.height)
+    }
+}
+
+impl From<Vec2> for Size<f32> {
+    fn from(value: Vec2) -> Self {
+        Size {
+            width: value.x,
+            height: value.y,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions() {
+        assert_eq!(
+            Vec2::from(Position { x: 1.5f32, y: -2.0 }),
+            Vec2::new(1.5, -2.0)
+        );
+        assert_eq!(
+            Vec2::from(Position { x: 1.5f64, y: -2.0 }),
+            Vec2::new(1.5, -2.0)
+        );
+        assert_eq!(Vec2::from(Position { x: 3, y: -4 }), Vec2::new(3.0, -4.0));
+        assert_eq!(IVec2::from(Position { x: 3, y: -4 }), IVec2::new(3, -4));
+        let position: Position<f32> = Vec2::new(0.25, 8.0).into();
+        assert_eq!(position, Position { x: 0.25, y: 8.0 });
+    }
+
+    #[test]
+    fn sizes() {
+        let float = Size {
+            width: 2.5f32,
+            height: 4.0,
+        };
+        assert_eq!(Vec2::from(float), Vec2::new(2.5, 4.0));
+        let window = Size {
+            width: 1280,
+            height: 720,
+        };
+        assert_eq!(Vec2::from(window), Vec2::new(1280.0, 720.0));
+        assert_eq!(IVec2::from(window), IVec2::new(1280, 720));
+        let unsigned = Size {
+            width: 7u32,
+            height: 9,
+        };
+        assert_eq!(Vec2::from(unsigned), Vec2::new(7.0, 9.0));
+        let back: Size<f32> = Vec2::new(3.0, 6.0).into();
+        assert_eq!(
+            back,
+            Size {
+                width: 3.0,
+                height: 6.0
+            }
+        );
+    }
+}
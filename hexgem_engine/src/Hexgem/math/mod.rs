@@ -0,0 +1,12 @@
+mod conversions;
+mod projection;
+mod rect;
+mod transform;
+
+pub mod HexgemMath {
+    pub use super::projection::{orthographic, perspective};
+    pub use super::rect::Rect;
+    pub use super::transform::{Transform2D, Transform3D};
+    pub use crate::Hexgem::core::{Position, Size};
+    pub use glam::{IVec2, Mat3, Mat4, Quat, UVec2, Vec2, Vec3, Vec4};
+}
@@ -0,0 +1,64 @@
+use glam::Mat4;
+
+// OpenGL clip space: right handed, depth mapped to [-1, 1].
+pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Mat4 {
+    Mat4::orthographic_rh_gl(left, right, bottom, top, near, far)
+}
+
+pub fn perspective(fov_y_radians: f32, aspect_ratio: f32, near: f32, far: f32) -> Mat4 {
+    Mat4::perspective_rh_gl(fov_y_radians, aspect_ratio, near, far)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3, Vec4};
+
+    use super::*;
+
+    #[test]
+    fn orthographic_matches_reference() {
+        let (left, right, bottom, top, near, far) = (-2.0, 6.0, -1.0, 3.0, 0.5, 10.0);
+        // Column major, the classic glOrtho matrix.
+        let expected = Mat4::from_cols(
+            Vec4::new(2.0 / (right - left), 0.0, 0.0, 0.0),
+            Vec4::new(0.0, 2.0 / (top - bottom), 0.0, 0.0),
+            Vec4::new(0.0, 0.0, -2.0 / (far - near), 0.0),
+            Vec4::new(
+                -(right + left) / (right - left),
+                -(top + bottom) / (top - bottom),
+                -(far + near) / (far - near),
+                1.0,
+            ),
+        );
+        let projection = orthographic(left, right, bottom, top, near, far);
+        assert!(projection.abs_diff_eq(expected, 1e-6));
+        assert!(projection.abs_diff_eq(
+            Mat4::orthographic_rh_gl(left, right, bottom, top, near, far),
+            1e-6
+        ));
+        let corner = projection.project_point3(Vec3::new(left, bottom, -near));
+        assert!(corner.abs_diff_eq(Vec3::new(-1.0, -1.0, -1.0), 1e-5));
+        let corner = projection.project_point3(Vec3::new(right, top, -far));
+        assert!(corner.abs_diff_eq(Vec3::new(1.0, 1.0, 1.0), 1e-5));
+    }
+
+    #[test]
+    fn perspective_matches_reference() {
+        let (fov, aspect, near, far) = (1.0f32, 16.0 / 9.0, 0.1, 100.0);
+        // Column major, the classic gluPerspective matrix.
+        let f = 1.0 / (fov / 2.0).tan();
+        let expected = Mat4::from_cols(
+            Vec4::new(f / aspect, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, f, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, (far + near) / (near - far), -1.0),
+            Vec4::new(0.0, 0.0, 2.0 * far * near / (near - far), 0.0),
+        );
+        let projection = perspective(fov, aspect, near, far);
+        assert!(projection.abs_diff_eq(expected, 1e-5));
+        assert!(projection.abs_diff_eq(Mat4::perspective_rh_gl(fov, aspect, near, far), 1e-6));
+        let near_point = projection.project_point3(Vec3::new(0.0, 0.0, -near));
+        assert!((near_point.z + 1.0).abs() < 1e-4);
+        let far_point = projection.project_point3(Vec3::new(0.0, 0.0, -far));
+        assert!((far_point.z - 1.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,217 @@
+use glam::Vec2;
+
+use crate::Hexgem::core::{Position, Size};
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct Rect {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Rect {
+    pub fn from_min_max(a: Vec2, b: Vec2) -> Self {
+        Self {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    pub fn from_position_size(position: Vec2, size: Vec2) -> Self {
+        Self::from_min_max(position, position + size)
+    }
+
+    pub fn from_center_size(center: Vec2, size: Vec2) -> Self {
+        let half = size.abs() * 0.5;
+        Self {
+            min: center - half,
+            max: center + half,
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f32 {
+        self.max.y - self.min.y
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn area(&self) -> f32 {
+        self.width() * self.height()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width() <= 0.0 || self.height() <= 0.0
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.min.cmplt(other.max).all() && other.min.cmplt(self.max).all()
+    }
+
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        if !self.intersects(other) {
+            return None;
+        }
+        Some(Rect {
+            min: self.min.max(other.min),
+            max: self.max.min(other.max),
+        })
+    }
+
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    pub fn translate(&self, offset: Vec2) -> Rect {
+        Rect {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+
+    pub fn expand(&self, amount: f32) -> Rect {
+        Rect::from_min_max(
+            self.min - Vec2::splat(amount),
+            self.max + Vec2::splat(amount),
+        )
+    }
+}
+
+impl From<(Position<f32>, Size<f32>)> for Rect {
+    fn from((position, size): (Position<f32>, Size<f32>)) -> Self {
+        Rect::from_position_size(position.into(), size.into())
+    }
+}
+
+impl From<(Position<i32>, Size<i32>)> for Rect {
+    fn from((position, size): (Position<i32>, Size<i32>)) -> Self {
+        Rect::from_position_size(position.into(), size.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(min: (f32, f32), max: (f32, f32)) -> Rect {
+        Rect::from_min_max(Vec2::new(min.0, min.1), Vec2::new(max.0, max.1))
+    }
+
+    #[test]
+    fn constructors_normalize_corners() {
+        let flipped = Rect::from_min_max(Vec2::new(4.0, 1.0), Vec2::new(0.0, 3.0));
+        assert_eq!(flipped, rect((0.0, 1.0), (4.0, 3.0)));
+        let negative = Rect::from_position_size(Vec2::new(2.0, 2.0), Vec2::new(-2.0, -1.0));
+        assert_eq!(negative, rect((0.0, 1.0), (2.0, 2.0)));
+        let centered = Rect::from_center_size(Vec2::ZERO, Vec2::new(-4.0, 2.0));
+        assert_eq!(centered, rect((-2.0, -1.0), (2.0, 1.0)));
+        assert_eq!(centered.center(), Vec2::ZERO);
+        assert_eq!(centered.area(), 8.0);
+    }
+
+    #[test]
+    fn contains_is_inclusive_on_edges() {
+        let r = rect((0.0, 0.0), (2.0, 2.0));
+        assert!(r.contains(Vec2::ZERO));
+        assert!(r.contains(Vec2::new(2.0, 2.0)));
+        assert!(r.contains(Vec2::new(2.0, 0.0)));
+        assert!(!r.contains(Vec2::new(2.0001, 1.0)));
+        assert!(!r.contains(Vec2::new(1.0, -0.0001)));
+        assert!(r.contains_rect(&r));
+        assert!(r.contains_rect(&rect((0.5, 0.5), (1.0, 1.0))));
+        assert!(!r.contains_rect(&rect((1.0, 1.0), (3.0, 1.5))));
+    }
+
+    #[test]
+    fn touching_rects_do_not_intersect() {
+        let a = rect((0.0, 0.0), (2.0, 2.0));
+        let edge = rect((2.0, 0.0), (4.0, 2.0));
+        let corner = rect((2.0, 2.0), (3.0, 3.0));
+        assert!(!a.intersects(&edge) && a.intersection(&edge).is_none());
+        assert!(!a.intersects(&corner) && a.intersection(&corner).is_none());
+    }
+
+    #[test]
+    fn intersection_is_the_overlap() {
+        let a = rect((0.0, 0.0), (2.0, 2.0));
+        let b = rect((1.0, -1.0), (3.0, 1.0));
+        assert_eq!(a.intersection(&b), Some(rect((1.0, 0.0), (2.0, 1.0))));
+        assert_eq!(a.intersection(&b), b.intersection(&a));
+        let inner = rect((0.5, 0.5), (1.0, 1.0));
+        assert_eq!(a.intersection(&inner), Some(inner));
+        assert!(a.intersection(&rect((5.0, 5.0), (6.0, 6.0))).is_none());
+    }
+
+    #[test]
+    fn union_covers_both() {
+        let a = rect((0.0, 0.0), (1.0, 1.0));
+        let b = rect((3.0, -2.0), (4.0, 0.5));
+        let union = a.union(&b);
+        assert_eq!(union, rect((0.0, -2.0), (4.0, 1.0)));
+        assert!(union.contains_rect(&a) && union.contains_rect(&b));
+        assert_eq!(a.union(&a), a);
+    }
+
+    #[test]
+    fn empty_and_degenerate() {
+        assert!(Rect::default().is_empty());
+        assert!(rect((0.0, 0.0), (3.0, 0.0)).is_empty());
+        assert!(!rect((0.0, 0.0), (3.0, 1.0)).is_empty());
+        let point = rect((1.0, 1.0), (1.0, 1.0));
+        assert!(point.contains(Vec2::ONE));
+        assert!(!point.intersects(&point));
+    }
+
+    #[test]
+    fn translate_and_expand() {
+        let r = rect((0.0, 0.0), (2.0, 1.0));
+        assert_eq!(
+            r.translate(Vec2::new(1.0, -1.0)),
+            rect((1.0, -1.0), (3.0, 0.0))
+        );
+        assert_eq!(r.expand(1.0), rect((-1.0, -1.0), (3.0, 2.0)));
+        // Shrinking past zero size flips back into a valid rect around the center.
+        assert_eq!(r.expand(-1.0), rect((1.0, 0.0), (1.0, 1.0)));
+    }
+
+    #[test]
+    fn from_position_and_size() {
+        let r: Rect = (
+            Position { x: 1, y: 2 },
+            Size {
+                width: 3,
+                height: 4,
+            },
+        )
+            .into();
+        assert_eq!(r, rect((1.0, 2.0), (4.0, 6.0)));
+        let r: Rect = (
+            Position { x: 0.5, y: 0.5 },
+            Size {
+                width: 1.0,
+                height: 2.0,
+            },
+        )
+            .into();
+        assert_eq!(r, rect((0.5, 0.5), (1.5, 2.5)));
+    }
+}
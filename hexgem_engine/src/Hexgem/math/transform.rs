@@ -0,0 +1,246 @@
+use glam::{Mat3, Mat4, Quat, Vec2, Vec3};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform2D {
+    pub translation: Vec2,
+    pub rotation: f32,
+    pub scale: Vec2,
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Self {
+            translation: Vec2::ZERO,
+            rotation: 0.0,
+            scale: Vec2::ONE,
+        }
+    }
+}
+
+impl Transform2D {
+    pub fn from_translation(translation: Vec2) -> Self {
+        Self {
+            translation,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec2) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn matrix(&self) -> Mat3 {
+        Mat3::from_scale_angle_translation(self.scale, self.rotation, self.translation)
+    }
+
+    pub fn matrix_3d(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(
+            self.scale.extend(1.0),
+            Quat::from_rotation_z(self.rotation),
+            self.translation.extend(0.0),
+        )
+    }
+
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
+        self.matrix().transform_point2(point)
+    }
+}
+
+impl From<Transform2D> for Transform3D {
+    fn from(value: Transform2D) -> Self {
+        Self {
+            translation: value.translation.extend(0.0),
+            rotation: Quat::from_rotation_z(value.rotation),
+            scale: value.scale.extend(1.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform3D {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Default for Transform3D {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            scale: Vec3::ONE,
+        }
+    }
+}
+
+impl Transform3D {
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self {
+            translation,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_matrix(matrix: Mat4) -> Self {
+        let (scale, rotation, translation) = matrix.to_scale_rotation_translation();
+        Self {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    pub fn with_rotation(mut self, rotation: Quat) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec3) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn looking_at(mut self, target: Vec3, up: Vec3) -> Self {
+        let view = Mat4::look_at_rh(self.translation, target, up);
+        self.rotation = Quat::from_mat4(&view.inverse());
+        self
+    }
+
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+
+    pub fn forward(&self) -> Vec3 {
+        self.rotation * Vec3::NEG_Z
+    }
+
+    pub fn right(&self) -> Vec3 {
+        self.rotation * Vec3::X
+    }
+
+    pub fn up(&self) -> Vec3 {
+        self.rotation * Vec3::Y
+    }
+
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.matrix().transform_point3(point)
+    }
+
+    pub fn mul_transform(&self, child: &Transform3D) -> Transform3D {
+        Transform3D::from_matrix(self.matrix() * child.matrix())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    fn assert_vec3(a: Vec3, b: Vec3) {
+        assert!(a.abs_diff_eq(b, 1e-5), "{} != {}", a, b);
+    }
+
+    #[test]
+    fn transform_2d_applies_scale_rotation_translation() {
+        let transform = Transform2D::from_translation(Vec2::new(10.0, 0.0))
+            .with_rotation(FRAC_PI_2)
+            .with_scale(Vec2::new(2.0, 1.0));
+        let point = transform.transform_point(Vec2::new(1.0, 0.0));
+        assert!(point.abs_diff_eq(Vec2::new(10.0, 2.0), 1e-5), "{}", point);
+        let point_3d = transform
+            .matrix_3d()
+            .transform_point3(Vec3::new(1.0, 0.0, 0.0));
+        assert_vec3(point_3d, Vec3::new(10.0, 2.0, 0.0));
+    }
+
+    #[test]
+    fn transform_2d_converts_to_3d() {
+        let transform = Transform2D::from_translation(Vec2::new(1.0, 2.0))
+            .with_rotation(0.3)
+            .with_scale(Vec2::splat(3.0));
+        let transform_3d: Transform3D = transform.into();
+        assert!(transform_3d
+            .matrix()
+            .abs_diff_eq(transform.matrix_3d(), 1e-5));
+    }
+
+    #[test]
+    fn default_is_identity() {
+        assert_eq!(Transform2D::default().matrix(), Mat3::IDENTITY);
+        assert_eq!(Transform3D::default().matrix(), Mat4::IDENTITY);
+    }
+
+    #[test]
+    fn composition_matches_matrix_product() {
+        let parent = Transform3D::from_translation(Vec3::new(5.0, 0.0, 0.0))
+            .with_rotation(Quat::from_rotation_y(FRAC_PI_2))
+            .with_scale(Vec3::splat(2.0));
+        let child = Transform3D::from_translation(Vec3::new(0.0, 0.0, -1.0))
+            .with_rotation(Quat::from_rotation_x(0.4));
+        let combined = parent.mul_transform(&child);
+        let point = Vec3::new(1.0, 2.0, 3.0);
+        assert_vec3(
+            combined.transform_point(point),
+            parent.transform_point(child.transform_point(point)),
+        );
+        assert!(combined
+            .matrix()
+            .abs_diff_eq(parent.matrix() * child.matrix(), 1e-5));
+    }
+
+    #[test]
+    fn inverse_round_trips() {
+        // Uniform scale, the inverse of a rotated non-uniform scale is a shear and has no TRS
+        // form.
+        let transform = Transform3D::from_translation(Vec3::new(-3.0, 1.5, 8.0))
+            .with_rotation(Quat::from_euler(glam::EulerRot::YXZ, 0.7, -0.2, 1.1))
+            .with_scale(Vec3::splat(2.0));
+        let inverse = Transform3D::from_matrix(transform.matrix().inverse());
+        let point = Vec3::new(4.0, -2.0, 0.25);
+        assert_vec3(
+            inverse.transform_point(transform.transform_point(point)),
+            point,
+        );
+        assert!(transform
+            .mul_transform(&Transform3D::from_matrix(transform.matrix().inverse()))
+            .matrix()
+            .abs_diff_eq(Mat4::IDENTITY, 1e-5));
+    }
+
+    #[test]
+    fn from_matrix_round_trips() {
+        let transform = Transform3D::from_translation(Vec3::new(1.0, 2.0, 3.0))
+            .with_rotation(Quat::from_rotation_z(0.5))
+            .with_scale(Vec3::new(2.0, 3.0, 4.0));
+        let decomposed = Transform3D::from_matrix(transform.matrix());
+        assert_vec3(decomposed.translation, transform.translation);
+        assert_vec3(decomposed.scale, transform.scale);
+        assert!(decomposed.rotation.abs_diff_eq(transform.rotation, 1e-5));
+    }
+
+    #[test]
+    fn directions_follow_rotation() {
+        let transform = Transform3D::default();
+        assert_vec3(transform.forward(), Vec3::NEG_Z);
+        assert_vec3(transform.right(), Vec3::X);
+        assert_vec3(transform.up(), Vec3::Y);
+        let turned = transform.with_rotation(Quat::from_rotation_y(FRAC_PI_2));
+        assert_vec3(turned.forward(), Vec3::NEG_X);
+        assert_vec3(turned.right(), Vec3::NEG_Z);
+    }
+
+    #[test]
+    fn looking_at_faces_the_target() {
+        let transform = Transform3D::from_translation(Vec3::new(0.0, 0.0, 5.0))
+            .looking_at(Vec3::new(5.0, 0.0, 5.0), Vec3::Y);
+        assert_vec3(transform.forward(), Vec3::X);
+        assert_vec3(transform.up(), Vec3::Y);
+    }
+}
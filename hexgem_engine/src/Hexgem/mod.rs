@@ -1,17 +1,41 @@
 mod application;
+mod asset;
 mod core;
+mod ecs;
 mod egui;
+mod error;
+mod frame_capture;
 mod hexgem_events;
+mod input;
 mod layer;
 mod layer_stack;
 mod level;
 mod log;
+mod math;
+mod pack;
 mod platform;
+mod profiler;
+mod renderer;
+mod scene;
+mod timestep;
 mod window;
 pub use self::egui::*;
 pub use self::log::*;
-pub use ::log::{debug, error, info, warn};
+pub use ::log::{debug, error, info, trace, warn, Level, LevelFilter};
 pub use application::*;
+pub use asset::*;
+pub use ecs::*;
+pub use error::{HexgemError, HexgemResult};
+pub use frame_capture::{CaptureFormat, CaptureOutput, FrameCapture};
 pub use hexgem_events::*;
+pub use input::InputState;
 pub use layer::*;
-pub use window::Window;
+pub use layer_stack::{LayerState, LayerStates};
+pub use math::*;
+pub use pack::*;
+pub use platform::HexgemWindow::HeadlessWindow;
+pub use profiler::*;
+pub use renderer::*;
+pub use scene::*;
+pub use timestep::Timestep;
+pub use window::{Window, WindowBackend, WindowProps};
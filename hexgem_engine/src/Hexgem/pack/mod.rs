@@ -0,0 +1,11 @@
+mod pack_builder;
+mod pack_entry;
+mod pack_file;
+
+pub(crate) use pack_entry::is_valid_entry_name;
+
+pub mod HexgemPack {
+    pub use super::pack_builder::PackBuilder;
+    pub use super::pack_entry::{AssetKind, PackCompression, PackEntry};
+    pub use super::pack_file::Pack;
+}
@@ -0,0 +1,225 @@
+use std::{
+    fs,
+    io::{Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use flate2::{write::DeflateEncoder, Compression};
+
+use crate::Hexgem::error::{HexgemError, HexgemResult};
+
+use super::pack_entry::{
+    is_valid_entry_name, AssetKind, PackCompression, DATA_ALIGNMENT, HEADER_SIZE, PACK_MAGIC,
+    PACK_VERSION,
+};
+
+struct PendingEntry {
+    name: String,
+    kind: AssetKind,
+    compression: PackCompression,
+    data: Vec<u8>,
+}
+
+// Collects entries in memory and writes them out as a pack. Entries that do not shrink when
+// deflated, like PNGs or compressed audio, are stored as they are.
+pub struct PackBuilder {
+    compression: PackCompression,
+    entries: Vec<PendingEntry>,
+}
+
+impl Default for PackBuilder {
+    fn default() -> Self {
+        Self {
+            compression: PackCompression::Deflate,
+            entries: vec![],
+        }
+    }
+}
+
+impl PackBuilder {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    // Compression used by entries added after this call.
+    pub fn compression(mut self, compression: PackCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    pub fn get_entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn add(&mut self, name: &str, data: Vec<u8>) -> HexgemResult<()> {
+        self.add_with_kind(name, AssetKind::from_path(name), data)
+    }
+
+    pub fn add_with_kind(
+        &mut self,
+        name: &str,
+        kind: AssetKind,
+        data: Vec<u8>,
+    ) -> HexgemResult<()> {
+        if !is_valid_entry_name(name) {
+            return Err(HexgemError::Pack(format!(
+                "Invalid entry name {:?}, expected a relative path with '/' separators",
+                name
+            )));
+        }
+        if self.entries.iter().any(|entry| entry.name == name) {
+            return Err(HexgemError::Pack(format!("Duplicate entry {}", name)));
+        }
+        self.entries.push(PendingEntry {
+            name: name.to_string(),
+            kind,
+            compression: self.compression,
+            data,
+        });
+        Ok(())
+    }
+
+    pub fn add_file<P: AsRef<Path>>(&mut self, name: &str, path: P) -> HexgemResult<()> {
+        let path = path.as_ref();
+        let data = fs::read(path)
+            .map_err(|err| HexgemError::Pack(format!("{}: {}", path.display(), err)))?;
+        self.add(name, data)
+    }
+
+    // Adds every file below the directory, named by their path relative to it. Returns how many
+    // files were added.
+    pub fn add_directory<P: AsRef<Path>>(&mut self, directory: P) -> HexgemResult<usize> {
+        let directory = directory.as_ref();
+        let mut files = vec![];
+        collect_files(directory, directory, &mut files)?;
+        // Sorted so the same directory always produces the same pack.
+        files.sort();
+        for (name, path) in files.iter() {
+            self.add_file(name, path)?;
+        }
+        Ok(files.len())
+    }
+
+    pub fn write<W: Write + Seek>(&self, writer: &mut W) -> HexgemResult<()> {
+        let io_error = |err: std::io::Error| HexgemError::Pack(err.to_string());
+        writer.seek(SeekFrom::Start(0)).map_err(io_error)?;
+        writer.write_all(&[0; HEADER_SIZE]).map_err(io_error)?;
+        let mut position = HEADER_SIZE as u64;
+        let mut toc = vec![];
+        for entry in self.entries.iter() {
+            let padding = position.next_multiple_of(DATA_ALIGNMENT) - position;
+            writer
+                .write_all(&vec![0; padding as usize])
+                .map_err(io_error)?;
+            position += padding;
+
+            let (compression, stored) = match entry.compression {
+                PackCompression::None => (PackCompression::None, None),
+                PackCompression::Deflate => {
+                    let deflated = deflate(&entry.data).map_err(io_error)?;
+                    if deflated.len() < entry.data.len() {
+                        (PackCompression::Deflate, Some(deflated))
+                    } else {
+                        (PackCompression::None, None)
+                    }
+                }
+            };
+            let stored = stored.as_deref().unwrap_or(&entry.data);
+            writer.write_all(stored).map_err(io_error)?;
+
+            toc.extend((entry.name.len() as u16).to_le_bytes());
+            toc.extend(entry.name.as_bytes());
+            toc.push(entry.kind.to_byte());
+            toc.push(compression.to_byte());
+            toc.extend(position.to_le_bytes());
+            toc.extend((stored.len() as u64).to_le_bytes());
+            toc.extend((entry.data.len() as u64).to_le_bytes());
+            toc.extend(crc32fast::hash(&entry.data).to_le_bytes());
+            position += stored.len() as u64;
+        }
+        writer.write_all(&toc).map_err(io_error)?;
+
+        let mut header = Vec::with_capacity(HEADER_SIZE);
+        header.extend(PACK_MAGIC);
+        header.extend(PACK_VERSION.to_le_bytes());
+        header.extend(0u16.to_le_bytes());
+        header.extend((self.entries.len() as u32).to_le_bytes());
+        header.extend(crc32fast::hash(&toc).to_le_bytes());
+        header.extend(position.to_le_bytes());
+        header.extend((toc.len() as u64).to_le_bytes());
+        writer.seek(SeekFrom::Start(0)).map_err(io_error)?;
+        writer.write_all(&header).map_err(io_error)?;
+        writer.flush().map_err(io_error)
+    }
+
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> HexgemResult<()> {
+        let path = path.as_ref();
+        let mut file = fs::File::create(path)
+            .map_err(|err| HexgemError::Pack(format!("{}: {}", path.display(), err)))?;
+        self.write(&mut file)
+    }
+}
+
+fn deflate(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+fn collect_files(
+    root: &Path,
+    directory: &Path,
+    files: &mut Vec<(String, std::path::PathBuf)>,
+) -> HexgemResult<()> {
+    let read_dir = fs::read_dir(directory)
+        .map_err(|err| HexgemError::Pack(format!("{}: {}", directory.display(), err)))?;
+    for dir_entry in read_dir {
+        let dir_entry = dir_entry
+            .map_err(|err| HexgemError::Pack(format!("{}: {}", directory.display(), err)))?;
+        let path = dir_entry.path();
+        let file_type = dir_entry
+            .file_type()
+            .map_err(|err| HexgemError::Pack(format!("{}: {}", path.display(), err)))?;
+        // Symlinked directories are skipped rather than followed, they could loop back on
+        // themselves. Symlinked files are packed with the contents they point to.
+        if file_type.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if file_type.is_file() || (file_type.is_symlink() && path.is_file()) {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let name = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((name, path));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn add_directory_skips_symlinked_directories() {
+        let directory =
+            std::env::temp_dir().join(format!("hexgem_pack_symlinks_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(directory.join("sub")).unwrap();
+        fs::write(directory.join("sub/a.txt"), b"a").unwrap();
+        fs::write(directory.join("b.txt"), b"b").unwrap();
+        std::os::unix::fs::symlink(&directory, directory.join("sub/loop")).unwrap();
+        std::os::unix::fs::symlink(directory.join("b.txt"), directory.join("c.txt")).unwrap();
+
+        let mut builder = PackBuilder::create();
+        let count = builder.add_directory(&directory);
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(count.unwrap(), 3);
+        let mut names: Vec<_> = builder.entries.iter().map(|entry| &entry.name).collect();
+        names.sort();
+        assert_eq!(names, ["b.txt", "c.txt", "sub/a.txt"]);
+        assert_eq!(builder.entries[1].data, b"b");
+    }
+}
@@ -0,0 +1,141 @@
+use std::path::{Component, Path};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+    Scene,
+    Texture,
+    Shader,
+    Font,
+    Audio,
+    Data,
+}
+
+impl AssetKind {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        let extension = path
+            .as_ref()
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "toml" | "scene" => AssetKind::Scene,
+            "png" | "jpg" | "jpeg" | "gif" => AssetKind::Texture,
+            "glsl" | "vert" | "frag" => AssetKind::Shader,
+            "ttf" | "otf" | "fnt" => AssetKind::Font,
+            "wav" | "ogg" | "mp3" | "flac" => AssetKind::Audio,
+            _ => AssetKind::Data,
+        }
+    }
+
+    pub(crate) fn to_byte(self) -> u8 {
+        match self {
+            AssetKind::Scene => 0,
+            AssetKind::Texture => 1,
+            AssetKind::Shader => 2,
+            AssetKind::Font => 3,
+            AssetKind::Audio => 4,
+            AssetKind::Data => 5,
+        }
+    }
+
+    pub(crate) fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(AssetKind::Scene),
+            1 => Some(AssetKind::Texture),
+            2 => Some(AssetKind::Shader),
+            3 => Some(AssetKind::Font),
+            4 => Some(AssetKind::Audio),
+            5 => Some(AssetKind::Data),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PackCompression {
+    None,
+    #[default]
+    Deflate,
+}
+
+impl PackCompression {
+    pub(crate) fn to_byte(self) -> u8 {
+        match self {
+            PackCompression::None => 0,
+            PackCompression::Deflate => 1,
+        }
+    }
+
+    pub(crate) fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(PackCompression::None),
+            1 => Some(PackCompression::Deflate),
+            _ => None,
+        }
+    }
+}
+
+// Table of contents record. Offsets are from the start of the pack, the checksum is the
+// CRC-32 of the uncompressed data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackEntry {
+    pub(crate) name: String,
+    pub(crate) kind: AssetKind,
+    pub(crate) compression: PackCompression,
+    pub(crate) offset: u64,
+    pub(crate) stored_size: u64,
+    pub(crate) size: u64,
+    pub(crate) checksum: u32,
+}
+
+impl PackEntry {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_kind(&self) -> AssetKind {
+        self.kind
+    }
+
+    pub fn get_compression(&self) -> PackCompression {
+        self.compression
+    }
+
+    pub fn get_offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn get_stored_size(&self) -> u64 {
+        self.stored_size
+    }
+
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn get_checksum(&self) -> u32 {
+        self.checksum
+    }
+}
+
+// Names are relative paths with '/' separators. Anything that could escape the extraction
+// directory is refused.
+pub(crate) fn is_valid_entry_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains('\\')
+        && name.len() <= u16::MAX as usize
+        && Path::new(name)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+// Layout, all little endian: a 32 byte header, the entry data each aligned to DATA_ALIGNMENT,
+// then the table of contents.
+//   header: magic, version u16, reserved u16, entry count u32, toc checksum u32,
+//           toc offset u64, toc size u64
+//   entry:  name length u16, name, kind u8, compression u8, offset u64, stored size u64,
+//           size u64, checksum u32
+pub(crate) const PACK_MAGIC: &[u8; 4] = b"HXPK";
+pub(crate) const PACK_VERSION: u16 = 1;
+pub(crate) const HEADER_SIZE: usize = 32;
+pub(crate) const DATA_ALIGNMENT: u64 = 16;
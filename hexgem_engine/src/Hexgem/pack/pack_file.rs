@@ -0,0 +1,387 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use flate2::read::DeflateDecoder;
+use memmap2::Mmap;
+
+use crate::Hexgem::error::{HexgemError, HexgemResult};
+
+use super::pack_entry::{
+    is_valid_entry_name, AssetKind, PackCompression, PackEntry, HEADER_SIZE, PACK_MAGIC,
+    PACK_VERSION,
+};
+
+// The size comes from the table of contents, so a corrupt pack could ask for anything. Larger
+// entries grow the buffer as they inflate.
+const MAX_PREALLOC: u64 = 1 << 20;
+
+enum PackData {
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl PackData {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            PackData::Mapped(mmap) => mmap,
+            PackData::Owned(bytes) => bytes,
+        }
+    }
+}
+
+// Read side of a pack. Files are memory mapped, so opening only parses the table of contents
+// and uncompressed entries are read without copying.
+pub struct Pack {
+    name: String,
+    data: PackData,
+    entries: Vec<PackEntry>,
+    index: HashMap<String, usize>,
+}
+
+impl Pack {
+    pub fn open<P: AsRef<Path>>(path: P) -> HexgemResult<Self> {
+        let path = path.as_ref();
+        let name = path.display().to_string();
+        let file =
+            fs::File::open(path).map_err(|err| HexgemError::Pack(format!("{}: {}", name, err)))?;
+        // Packs are read only assets, the mapping is not expected to change underneath us.
+        let mmap = unsafe { Mmap::map(&file) }
+            .map_err(|err| HexgemError::Pack(format!("{}: {}", name, err)))?;
+        Self::parse(name, PackData::Mapped(mmap))
+    }
+
+    pub fn from_bytes(name: &str, bytes: Vec<u8>) -> HexgemResult<Self> {
+        Self::parse(name.to_string(), PackData::Owned(bytes))
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_entries(&self) -> &[PackEntry] {
+        &self.entries
+    }
+
+    pub fn get_entry(&self, name: &str) -> Option<&PackEntry> {
+        self.index.get(name).map(|index| &self.entries[*index])
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.index.contains_key(name)
+    }
+
+    // Uncompressed entries borrow straight from the mapping, compressed ones are inflated.
+    // Either way the checksum is verified.
+    pub fn read(&self, name: &str) -> HexgemResult<Cow<'_, [u8]>> {
+        let entry = self
+            .get_entry(name)
+            .ok_or_else(|| HexgemError::Pack(format!("{} has no entry {}", self.name, name)))?;
+        self.read_entry(entry)
+    }
+
+    pub fn read_entry(&self, entry: &PackEntry) -> HexgemResult<Cow<'_, [u8]>> {
+        let stored = self
+            .data
+            .bytes()
+            .get(entry.offset as usize..(entry.offset + entry.stored_size) as usize)
+            .ok_or_else(|| self.entry_error(entry, "out of bounds"))?;
+        let data = match entry.compression {
+            PackCompression::None => Cow::Borrowed(stored),
+            PackCompression::Deflate => {
+                let mut data = Vec::with_capacity(entry.size.min(MAX_PREALLOC) as usize);
+                // Reading one byte past the size is enough to tell the entry is too long.
+                DeflateDecoder::new(stored)
+                    .take(entry.size.saturating_add(1))
+                    .read_to_end(&mut data)
+                    .map_err(|err| self.entry_error(entry, &err.to_string()))?;
+                Cow::Owned(data)
+            }
+        };
+        if data.len() as u64 != entry.size {
+            return Err(self.entry_error(
+                entry,
+                &format!("expected {} bytes, found {}", entry.size, data.len()),
+            ));
+        }
+        if crc32fast::hash(&data) != entry.checksum {
+            return Err(self.entry_error(entry, "checksum mismatch"));
+        }
+        Ok(data)
+    }
+
+    // Reads every entry, failing on the first one that is corrupt.
+    pub fn verify(&self) -> HexgemResult<()> {
+        for entry in self.entries.iter() {
+            self.read_entry(entry)?;
+        }
+        Ok(())
+    }
+
+    // Writes every entry below the directory, returning the paths written.
+    pub fn extract<P: AsRef<Path>>(&self, directory: P) -> HexgemResult<Vec<PathBuf>> {
+        let directory = directory.as_ref();
+        let mut written = vec![];
+        for entry in self.entries.iter() {
+            let data = self.read_entry(entry)?;
+            let path = directory.join(&entry.name);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|err| HexgemError::Pack(format!("{}: {}", parent.display(), err)))?;
+            }
+            fs::write(&path, &data)
+                .map_err(|err| HexgemError::Pack(format!("{}: {}", path.display(), err)))?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    fn entry_error(&self, entry: &PackEntry, message: &str) -> HexgemError {
+        HexgemError::Pack(format!("{} entry {}: {}", self.name, entry.name, message))
+    }
+
+    fn parse(name: String, data: PackData) -> HexgemResult<Self> {
+        let error = |message: &str| HexgemError::Pack(format!("{}: {}", name, message));
+        let bytes = data.bytes();
+        if bytes.len() < HEADER_SIZE || &bytes[0..4] != PACK_MAGIC {
+            return Err(error("not a hexgem pack"));
+        }
+        let mut header = ByteReader::create(&bytes[4..HEADER_SIZE]);
+        let version = header.u16().unwrap_or_default();
+        if version != PACK_VERSION {
+            return Err(error(&format!(
+                "unsupported version {}, expected {}",
+                version, PACK_VERSION
+            )));
+        }
+        header.u16();
+        let (Some(entry_count), Some(toc_checksum), Some(toc_offset), Some(toc_size)) =
+            (header.u32(), header.u32(), header.u64(), header.u64())
+        else {
+            return Err(error("truncated header"));
+        };
+        let toc_end = toc_offset
+            .checked_add(toc_size)
+            .filter(|end| *end <= bytes.len() as u64 && toc_offset >= HEADER_SIZE as u64)
+            .ok_or_else(|| error("table of contents is out of bounds"))?;
+        let toc = &bytes[toc_offset as usize..toc_end as usize];
+        if crc32fast::hash(toc) != toc_checksum {
+            return Err(error("table of contents checksum mismatch"));
+        }
+
+        let mut reader = ByteReader::create(toc);
+        let mut entries = Vec::with_capacity((entry_count as usize).min(toc.len()));
+        let mut index = HashMap::new();
+        for _ in 0..entry_count {
+            let entry = reader
+                .entry()
+                .ok_or_else(|| error("malformed table of contents"))?;
+            if !is_valid_entry_name(&entry.name) || index.contains_key(&entry.name) {
+                return Err(error(&format!("invalid entry name {:?}", entry.name)));
+            }
+            let in_bounds = entry
+                .offset
+                .checked_add(entry.stored_size)
+                .is_some_and(|end| entry.offset >= HEADER_SIZE as u64 && end <= toc_offset);
+            if !in_bounds {
+                return Err(error(&format!("entry {} is out of bounds", entry.name)));
+            }
+            index.insert(entry.name.clone(), entries.len());
+            entries.push(entry);
+        }
+        Ok(Self {
+            name,
+            data,
+            entries,
+            index,
+        })
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    fn create(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take(&mut self, count: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < count {
+            return None;
+        }
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2)
+            .map(|bytes| u16::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take(8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn entry(&mut self) -> Option<PackEntry> {
+        let name_length = self.u16()? as usize;
+        let name = String::from_utf8(self.take(name_length)?.to_vec()).ok()?;
+        Some(PackEntry {
+            name,
+            kind: AssetKind::from_byte(self.u8()?)?,
+            compression: PackCompression::from_byte(self.u8()?)?,
+            offset: self.u64()?,
+            stored_size: self.u64()?,
+            size: self.u64()?,
+            checksum: self.u32()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::super::{pack_builder::PackBuilder, pack_entry::DATA_ALIGNMENT};
+    use super::*;
+
+    fn build(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = PackBuilder::create();
+        for (name, data) in entries {
+            builder.add(name, data.to_vec()).unwrap();
+        }
+        let mut cursor = Cursor::new(vec![]);
+        builder.write(&mut cursor).unwrap();
+        cursor.into_inner()
+    }
+
+    fn toc_range(bytes: &[u8]) -> std::ops::Range<usize> {
+        let offset = u64::from_le_bytes(bytes[16..24].try_into().unwrap()) as usize;
+        let size = u64::from_le_bytes(bytes[24..32].try_into().unwrap()) as usize;
+        offset..offset + size
+    }
+
+    // Patches the table of contents and fixes its checksum, so only the patched field is wrong.
+    fn patch_toc(bytes: &mut [u8], at: usize, patch: &[u8]) {
+        let toc = toc_range(bytes);
+        bytes[toc.start + at..toc.start + at + patch.len()].copy_from_slice(patch);
+        let checksum = crc32fast::hash(&bytes[toc]);
+        bytes[12..16].copy_from_slice(&checksum.to_le_bytes());
+    }
+
+    fn parse_error(bytes: Vec<u8>) -> String {
+        match Pack::from_bytes("test.hxpk", bytes) {
+            Ok(_) => panic!("malformed pack parsed"),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "hexgem ".repeat(100);
+        let random: Vec<u8> = (0..64u32).map(|i| (i * 97 % 251) as u8).collect();
+        let bytes = build(&[
+            ("scenes/main.toml", text.as_bytes()),
+            ("textures/noise.png", &random),
+            ("empty.bin", b""),
+        ]);
+        let pack = Pack::from_bytes("test.hxpk", bytes).unwrap();
+        pack.verify().unwrap();
+        assert_eq!(pack.get_entries().len(), 3);
+
+        let scene = pack.get_entry("scenes/main.toml").unwrap();
+        assert_eq!(scene.get_kind(), AssetKind::Scene);
+        assert_eq!(scene.get_compression(), PackCompression::Deflate);
+        assert!(scene.get_stored_size() < scene.get_size());
+        assert_eq!(pack.read("scenes/main.toml").unwrap(), text.as_bytes());
+
+        // Data that does not shrink is stored and read without a copy.
+        let texture = pack.get_entry("textures/noise.png").unwrap();
+        assert_eq!(texture.get_kind(), AssetKind::Texture);
+        assert_eq!(texture.get_compression(), PackCompression::None);
+        assert_eq!(texture.get_offset() % DATA_ALIGNMENT, 0);
+        assert!(matches!(
+            pack.read("textures/noise.png").unwrap(),
+            Cow::Borrowed(data) if data == random.as_slice()
+        ));
+
+        assert!(pack.read("empty.bin").unwrap().is_empty());
+        assert!(pack.read("missing.bin").is_err());
+    }
+
+    #[test]
+    fn truncated_packs_are_rejected() {
+        let bytes = build(&[("a.txt", b"hello")]);
+        assert!(parse_error(bytes[..HEADER_SIZE - 1].to_vec()).contains("not a hexgem pack"));
+        let toc = toc_range(&bytes);
+        assert!(parse_error(bytes[..toc.end - 1].to_vec())
+            .contains("table of contents is out of bounds"));
+    }
+
+    #[test]
+    fn bad_toc_checksum_is_rejected() {
+        let mut bytes = build(&[("a.txt", b"hello")]);
+        let toc = toc_range(&bytes);
+        bytes[toc.start + 2] ^= 0xFF;
+        assert!(parse_error(bytes).contains("table of contents checksum mismatch"));
+    }
+
+    #[test]
+    fn out_of_bounds_entries_are_rejected() {
+        // The stored size follows the name length, name, kind, compression and offset.
+        let stored_size_at = 2 + "a.txt".len() + 2 + 8;
+        let mut bytes = build(&[("a.txt", b"hello")]);
+        patch_toc(&mut bytes, stored_size_at, &1000u64.to_le_bytes());
+        assert!(parse_error(bytes).contains("entry a.txt is out of bounds"));
+
+        let mut bytes = build(&[("a.txt", b"hello")]);
+        patch_toc(&mut bytes, stored_size_at, &u64::MAX.to_le_bytes());
+        assert!(parse_error(bytes).contains("entry a.txt is out of bounds"));
+    }
+
+    #[test]
+    fn oversized_entries_fail_to_read() {
+        // A huge size must not be trusted for allocation, the read just comes up short.
+        let size_at = 2 + "a.txt".len() + 2 + 8 + 8;
+        let mut bytes = build(&[("a.txt", "hello ".repeat(20).as_bytes())]);
+        patch_toc(&mut bytes, size_at, &u64::MAX.to_le_bytes());
+        let pack = Pack::from_bytes("test.hxpk", bytes).unwrap();
+        let err = pack.read("a.txt").unwrap_err().to_string();
+        assert!(err.contains("expected 18446744073709551615 bytes, found 120"));
+    }
+
+    #[test]
+    fn escaping_names_are_rejected() {
+        let mut builder = PackBuilder::create();
+        for name in [
+            "../a.txt",
+            "/a.txt",
+            "a/../../b.txt",
+            "a\\b.txt",
+            "./a.txt",
+            "",
+        ] {
+            assert!(builder.add(name, vec![]).is_err(), "{:?}", name);
+        }
+
+        let mut bytes = build(&[("ab/x.txt", b"hello")]);
+        patch_toc(&mut bytes, 2, b"../");
+        assert!(parse_error(bytes).contains("invalid entry name \"../x.txt\""));
+    }
+}
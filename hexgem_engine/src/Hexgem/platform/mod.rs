@@ -1,4 +1,8 @@
 mod egui;
+mod opengl;
+mod software;
 mod window;
 pub use egui::EguiPlatform;
+pub use opengl::OpenGlPlatform;
+pub use software::SoftwarePlatform;
 pub use window::*;
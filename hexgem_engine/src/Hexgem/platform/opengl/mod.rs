@@ -0,0 +1,14 @@
+mod opengl_buffer;
+mod opengl_framebuffer;
+mod opengl_renderer_api;
+mod opengl_shader;
+mod opengl_texture;
+mod opengl_vertex_array;
+pub mod OpenGlPlatform {
+    pub use super::opengl_buffer::{OpenGlIndexBuffer, OpenGlVertexBuffer};
+    pub use super::opengl_framebuffer::OpenGlFramebuffer;
+    pub use super::opengl_renderer_api::OpenGlRendererApi;
+    pub use super::opengl_shader::OpenGlShader;
+    pub use super::opengl_texture::OpenGlTexture;
+    pub use super::opengl_vertex_array::OpenGlVertexArray;
+}
@@ -0,0 +1,153 @@
+use std::{mem, ptr};
+
+use gl::types::{GLsizeiptr, GLuint};
+
+use crate::{
+    toAnyImpl,
+    Hexgem::renderer::HexgemRenderer::{BufferLayout, IndexBuffer, VertexBuffer},
+};
+
+pub struct OpenGlVertexBuffer {
+    renderer_id: GLuint,
+    size: usize,
+    layout: BufferLayout,
+}
+
+impl OpenGlVertexBuffer {
+    pub fn create(size: usize, data: Option<&[u8]>) -> Self {
+        let mut renderer_id = 0;
+        let usage = match data {
+            Some(_) => gl::STATIC_DRAW,
+            None => gl::DYNAMIC_DRAW,
+        };
+        unsafe {
+            gl::GenBuffers(1, &mut renderer_id);
+            gl::BindBuffer(gl::ARRAY_BUFFER, renderer_id);
+            gl::BufferData(gl::ARRAY_BUFFER, size as GLsizeiptr, ptr::null(), usage);
+            if let Some(data) = data {
+                gl::BufferSubData(
+                    gl::ARRAY_BUFFER,
+                    0,
+                    data.len().min(size) as GLsizeiptr,
+                    data.as_ptr() as *const _,
+                );
+            }
+        }
+        Self {
+            renderer_id,
+            size,
+            layout: BufferLayout::default(),
+        }
+    }
+
+    pub fn get_renderer_id(&self) -> GLuint {
+        self.renderer_id
+    }
+}
+
+toAnyImpl!(OpenGlVertexBuffer);
+
+impl VertexBuffer for OpenGlVertexBuffer {
+    fn bind(&self) {
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.renderer_id);
+        }
+    }
+
+    fn unbind(&self) {
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+    }
+
+    fn set_data(&mut self, data: &[u8]) {
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.renderer_id);
+            if data.len() > self.size {
+                self.size = data.len();
+                gl::BufferData(
+                    gl::ARRAY_BUFFER,
+                    self.size as GLsizeiptr,
+                    data.as_ptr() as *const _,
+                    gl::DYNAMIC_DRAW,
+                );
+                return;
+            }
+            gl::BufferSubData(
+                gl::ARRAY_BUFFER,
+                0,
+                data.len() as GLsizeiptr,
+                data.as_ptr() as *const _,
+            );
+        }
+    }
+
+    fn get_layout(&self) -> &BufferLayout {
+        &self.layout
+    }
+
+    fn set_layout(&mut self, layout: BufferLayout) {
+        self.layout = layout;
+    }
+}
+
+impl Drop for OpenGlVertexBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.renderer_id);
+        }
+    }
+}
+
+pub struct OpenGlIndexBuffer {
+    renderer_id: GLuint,
+    count: u32,
+}
+
+impl OpenGlIndexBuffer {
+    pub fn create(indices: &[u32]) -> Self {
+        let mut renderer_id = 0;
+        unsafe {
+            gl::GenBuffers(1, &mut renderer_id);
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, renderer_id);
+            gl::BufferData(
+                gl::ELEMENT_ARRAY_BUFFER,
+                mem::size_of_val(indices) as GLsizeiptr,
+                indices.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+        }
+        Self {
+            renderer_id,
+            count: indices.len() as u32,
+        }
+    }
+}
+
+toAnyImpl!(OpenGlIndexBuffer);
+
+impl IndexBuffer for OpenGlIndexBuffer {
+    fn bind(&self) {
+        unsafe {
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.renderer_id);
+        }
+    }
+
+    fn unbind(&self) {
+        unsafe {
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
+        }
+    }
+
+    fn get_count(&self) -> u32 {
+        self.count
+    }
+}
+
+impl Drop for OpenGlIndexBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.renderer_id);
+        }
+    }
+}
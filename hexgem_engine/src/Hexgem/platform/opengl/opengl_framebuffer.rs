@@ -0,0 +1,403 @@
+use std::ptr;
+
+use gl::types::{GLenum, GLint, GLsizei, GLuint};
+
+use crate::{
+    toAnyImpl,
+    Hexgem::{
+        error::{HexgemError, HexgemResult},
+        renderer::HexgemRenderer::{
+            AttachmentPixels, AttachmentValue, FramebufferHandle, FramebufferSpec,
+            FramebufferTextureFormat,
+        },
+    },
+};
+
+pub struct OpenGlFramebuffer {
+    renderer_id: GLuint,
+    resolve_id: GLuint,
+    color_attachments: Vec<GLuint>,
+    resolve_attachments: Vec<GLuint>,
+    depth_attachment: GLuint,
+    spec: FramebufferSpec,
+}
+
+impl OpenGlFramebuffer {
+    pub fn create(spec: &FramebufferSpec) -> HexgemResult<Self> {
+        let mut framebuffer = Self {
+            renderer_id: 0,
+            resolve_id: 0,
+            color_attachments: vec![],
+            resolve_attachments: vec![],
+            depth_attachment: 0,
+            spec: spec.clone(),
+        };
+        framebuffer.invalidate()?;
+        Ok(framebuffer)
+    }
+
+    pub fn get_renderer_id(&self) -> GLuint {
+        self.renderer_id
+    }
+
+    // Texture that can be sampled, the resolved one when the framebuffer is multisampled.
+    pub fn get_color_attachment_id(&self, attachment: usize) -> Option<GLuint> {
+        if self.is_multisampled() {
+            return self.resolve_attachments.get(attachment).copied();
+        }
+        self.color_attachments.get(attachment).copied()
+    }
+
+    fn is_multisampled(&self) -> bool {
+        self.spec.samples > 1
+    }
+
+    fn invalidate(&mut self) -> HexgemResult<()> {
+        self.release();
+        let width = self.spec.width as GLsizei;
+        let height = self.spec.height as GLsizei;
+        let samples = self.spec.samples;
+        let formats: Vec<FramebufferTextureFormat> = self.spec.color_attachments().collect();
+        unsafe {
+            gl::GenFramebuffers(1, &mut self.renderer_id);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.renderer_id);
+            for (index, format) in formats.iter().enumerate() {
+                let texture = create_attachment(*format, width, height, samples);
+                gl::FramebufferTexture2D(
+                    gl::FRAMEBUFFER,
+                    gl::COLOR_ATTACHMENT0 + index as GLenum,
+                    texture_target(samples),
+                    texture,
+                    0,
+                );
+                self.color_attachments.push(texture);
+            }
+            if let Some(format) = self.spec.depth_attachment() {
+                self.depth_attachment = create_attachment(format, width, height, samples);
+                gl::FramebufferTexture2D(
+                    gl::FRAMEBUFFER,
+                    depth_attachment_point(format),
+                    texture_target(samples),
+                    self.depth_attachment,
+                    0,
+                );
+            }
+            set_draw_buffers(formats.len());
+            let status = check_status("framebuffer");
+
+            if status.is_ok() && self.is_multisampled() {
+                gl::GenFramebuffers(1, &mut self.resolve_id);
+                gl::BindFramebuffer(gl::FRAMEBUFFER, self.resolve_id);
+                for (index, format) in formats.iter().enumerate() {
+                    let texture = create_attachment(*format, width, height, 1);
+                    gl::FramebufferTexture2D(
+                        gl::FRAMEBUFFER,
+                        gl::COLOR_ATTACHMENT0 + index as GLenum,
+                        gl::TEXTURE_2D,
+                        texture,
+                        0,
+                    );
+                    self.resolve_attachments.push(texture);
+                }
+                set_draw_buffers(formats.len());
+            }
+            let status = status.and_then(|_| {
+                if self.is_multisampled() {
+                    return check_status("resolve framebuffer");
+                }
+                Ok(())
+            });
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            status
+        }
+    }
+
+    fn release(&mut self) {
+        unsafe {
+            if self.renderer_id != 0 {
+                gl::DeleteFramebuffers(1, &self.renderer_id);
+            }
+            if self.resolve_id != 0 {
+                gl::DeleteFramebuffers(1, &self.resolve_id);
+            }
+            if !self.color_attachments.is_empty() {
+                gl::DeleteTextures(
+                    self.color_attachments.len() as GLsizei,
+                    self.color_attachments.as_ptr(),
+                );
+            }
+            if !self.resolve_attachments.is_empty() {
+                gl::DeleteTextures(
+                    self.resolve_attachments.len() as GLsizei,
+                    self.resolve_attachments.as_ptr(),
+                );
+            }
+            if self.depth_attachment != 0 {
+                gl::DeleteTextures(1, &self.depth_attachment);
+            }
+        }
+        self.renderer_id = 0;
+        self.resolve_id = 0;
+        self.color_attachments.clear();
+        self.resolve_attachments.clear();
+        self.depth_attachment = 0;
+    }
+}
+
+toAnyImpl!(OpenGlFramebuffer);
+
+impl FramebufferHandle for OpenGlFramebuffer {
+    fn bind(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.renderer_id);
+            gl::Viewport(
+                0,
+                0,
+                self.spec.width as GLsizei,
+                self.spec.height as GLsizei,
+            );
+        }
+    }
+
+    fn unbind(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    fn resize(&mut self, spec: &FramebufferSpec) -> HexgemResult<()> {
+        self.spec = spec.clone();
+        self.invalidate()
+    }
+
+    fn resolve(&self) {
+        if !self.is_multisampled() {
+            return;
+        }
+        let width = self.spec.width as GLint;
+        let height = self.spec.height as GLint;
+        unsafe {
+            // Resolving can happen in the middle of a pass, so leave whatever was bound in place.
+            let previous_read = get_framebuffer_binding(gl::READ_FRAMEBUFFER_BINDING);
+            let previous_draw = get_framebuffer_binding(gl::DRAW_FRAMEBUFFER_BINDING);
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.renderer_id);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.resolve_id);
+            for index in 0..self.color_attachments.len() {
+                let attachment = gl::COLOR_ATTACHMENT0 + index as GLenum;
+                gl::ReadBuffer(attachment);
+                gl::DrawBuffer(attachment);
+                gl::BlitFramebuffer(
+                    0,
+                    0,
+                    width,
+                    height,
+                    0,
+                    0,
+                    width,
+                    height,
+                    gl::COLOR_BUFFER_BIT,
+                    gl::NEAREST,
+                );
+            }
+            set_draw_buffers(self.color_attachments.len());
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, previous_read);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, previous_draw);
+        }
+    }
+
+    fn read_pixels(
+        &self,
+        attachment: usize,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> AttachmentPixels {
+        let format = self.spec.color_attachments().nth(attachment);
+        let count = width as usize * height as usize;
+        let framebuffer = if self.is_multisampled() {
+            self.resolve_id
+        } else {
+            self.renderer_id
+        };
+        unsafe {
+            let previous_read = get_framebuffer_binding(gl::READ_FRAMEBUFFER_BINDING);
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, framebuffer);
+            gl::ReadBuffer(gl::COLOR_ATTACHMENT0 + attachment as GLenum);
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            let read = |pixel_format: GLenum, pixel_type: GLenum, data: *mut _| {
+                gl::ReadPixels(
+                    x as GLint,
+                    y as GLint,
+                    width as GLsizei,
+                    height as GLsizei,
+                    pixel_format,
+                    pixel_type,
+                    data,
+                )
+            };
+            let pixels = match format {
+                Some(FramebufferTextureFormat::RedInteger) => {
+                    let mut values = vec![0i32; count];
+                    read(gl::RED_INTEGER, gl::INT, values.as_mut_ptr() as *mut _);
+                    AttachmentPixels::Int(values)
+                }
+                Some(FramebufferTextureFormat::Rgba16F) => {
+                    let mut values = vec![0f32; count * 4];
+                    read(gl::RGBA, gl::FLOAT, values.as_mut_ptr() as *mut _);
+                    AttachmentPixels::Float(values)
+                }
+                _ => {
+                    let mut values = vec![0u8; count * 4];
+                    read(gl::RGBA, gl::UNSIGNED_BYTE, values.as_mut_ptr() as *mut _);
+                    AttachmentPixels::Rgba8(values)
+                }
+            };
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, previous_read);
+            pixels
+        }
+    }
+
+    fn clear_attachment(&self, attachment: usize, value: AttachmentValue) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.renderer_id);
+            match value {
+                AttachmentValue::Color(color) => {
+                    gl::ClearBufferfv(gl::COLOR, attachment as GLint, color.to_array().as_ptr())
+                }
+                AttachmentValue::Int(value) => {
+                    gl::ClearBufferiv(gl::COLOR, attachment as GLint, [value, 0, 0, 0].as_ptr())
+                }
+            }
+        }
+    }
+
+    fn bind_color_attachment(&self, attachment: usize, slot: u32) {
+        if let Some(texture) = self.get_color_attachment_id(attachment) {
+            unsafe {
+                gl::ActiveTexture(gl::TEXTURE0 + slot);
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+            }
+        }
+    }
+}
+
+impl Drop for OpenGlFramebuffer {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+fn texture_target(samples: u32) -> GLenum {
+    if samples > 1 {
+        return gl::TEXTURE_2D_MULTISAMPLE;
+    }
+    gl::TEXTURE_2D
+}
+
+fn internal_format(format: FramebufferTextureFormat) -> GLenum {
+    match format {
+        FramebufferTextureFormat::Rgba8 => gl::RGBA8,
+        FramebufferTextureFormat::Rgba16F => gl::RGBA16F,
+        FramebufferTextureFormat::RedInteger => gl::R32I,
+        FramebufferTextureFormat::Depth24Stencil8 => gl::DEPTH24_STENCIL8,
+        FramebufferTextureFormat::Depth32F => gl::DEPTH_COMPONENT32F,
+    }
+}
+
+fn pixel_format(format: FramebufferTextureFormat) -> (GLenum, GLenum) {
+    match format {
+        FramebufferTextureFormat::Rgba8 => (gl::RGBA, gl::UNSIGNED_BYTE),
+        FramebufferTextureFormat::Rgba16F => (gl::RGBA, gl::FLOAT),
+        FramebufferTextureFormat::RedInteger => (gl::RED_INTEGER, gl::INT),
+        FramebufferTextureFormat::Depth24Stencil8 => (gl::DEPTH_STENCIL, gl::UNSIGNED_INT_24_8),
+        FramebufferTextureFormat::Depth32F => (gl::DEPTH_COMPONENT, gl::FLOAT),
+    }
+}
+
+fn depth_attachment_point(format: FramebufferTextureFormat) -> GLenum {
+    match format {
+        FramebufferTextureFormat::Depth24Stencil8 => gl::DEPTH_STENCIL_ATTACHMENT,
+        _ => gl::DEPTH_ATTACHMENT,
+    }
+}
+
+unsafe fn create_attachment(
+    format: FramebufferTextureFormat,
+    width: GLsizei,
+    height: GLsizei,
+    samples: u32,
+) -> GLuint {
+    let mut texture = 0;
+    gl::GenTextures(1, &mut texture);
+    if samples > 1 {
+        gl::BindTexture(gl::TEXTURE_2D_MULTISAMPLE, texture);
+        gl::TexImage2DMultisample(
+            gl::TEXTURE_2D_MULTISAMPLE,
+            samples as GLsizei,
+            internal_format(format),
+            width,
+            height,
+            gl::TRUE,
+        );
+        return texture;
+    }
+    let (pixel_format, pixel_type) = pixel_format(format);
+    let filter = match format {
+        FramebufferTextureFormat::Rgba8 | FramebufferTextureFormat::Rgba16F => gl::LINEAR,
+        _ => gl::NEAREST,
+    };
+    gl::BindTexture(gl::TEXTURE_2D, texture);
+    gl::TexImage2D(
+        gl::TEXTURE_2D,
+        0,
+        internal_format(format) as GLint,
+        width,
+        height,
+        0,
+        pixel_format,
+        pixel_type,
+        ptr::null(),
+    );
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, filter as GLint);
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, filter as GLint);
+    gl::TexParameteri(
+        gl::TEXTURE_2D,
+        gl::TEXTURE_WRAP_S,
+        gl::CLAMP_TO_EDGE as GLint,
+    );
+    gl::TexParameteri(
+        gl::TEXTURE_2D,
+        gl::TEXTURE_WRAP_T,
+        gl::CLAMP_TO_EDGE as GLint,
+    );
+    texture
+}
+
+unsafe fn set_draw_buffers(count: usize) {
+    if count == 0 {
+        gl::DrawBuffer(gl::NONE);
+        return;
+    }
+    let buffers: Vec<GLenum> = (0..count)
+        .map(|index| gl::COLOR_ATTACHMENT0 + index as GLenum)
+        .collect();
+    gl::DrawBuffers(count as GLsizei, buffers.as_ptr());
+}
+
+unsafe fn get_framebuffer_binding(binding: GLenum) -> GLuint {
+    let mut framebuffer = 0;
+    gl::GetIntegerv(binding, &mut framebuffer);
+    framebuffer as GLuint
+}
+
+unsafe fn check_status(name: &str) -> HexgemResult<()> {
+    let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+    if status != gl::FRAMEBUFFER_COMPLETE {
+        return Err(HexgemError::Framebuffer(format!(
+            "{} is incomplete (status 0x{:X})",
+            name, status
+        )));
+    }
+    Ok(())
+}
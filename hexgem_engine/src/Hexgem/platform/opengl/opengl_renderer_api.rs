@@ -0,0 +1,140 @@
+use std::ptr;
+
+use glam::Vec4;
+
+use crate::Hexgem::{
+    error::HexgemResult,
+    renderer::HexgemRenderer::{
+        FramebufferHandle, FramebufferSpec, GraphicsApi, ImageData, IndexBuffer, RendererApi,
+        Shader, ShaderSource, TextureHandle, TextureOptions, VertexArray, VertexBuffer,
+    },
+};
+
+use super::{
+    opengl_buffer::{OpenGlIndexBuffer, OpenGlVertexBuffer},
+    opengl_framebuffer::OpenGlFramebuffer,
+    opengl_shader::OpenGlShader,
+    opengl_texture::OpenGlTexture,
+    opengl_vertex_array::OpenGlVertexArray,
+};
+
+pub struct OpenGlRendererApi;
+
+impl OpenGlRendererApi {
+    pub fn create() -> Self {
+        Self
+    }
+}
+
+impl RendererApi for OpenGlRendererApi {
+    fn get_api(&self) -> GraphicsApi {
+        GraphicsApi::OpenGl
+    }
+
+    fn init(&mut self) {
+        unsafe {
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            gl::Enable(gl::DEPTH_TEST);
+            gl::DepthFunc(gl::LEQUAL);
+        }
+    }
+
+    fn set_clear_color(&mut self, color: Vec4) {
+        unsafe {
+            gl::ClearColor(color.x, color.y, color.z, color.w);
+        }
+    }
+
+    fn clear(&mut self) {
+        unsafe {
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        }
+    }
+
+    fn set_viewport(&mut self, x: i32, y: i32, width: u32, height: u32) {
+        unsafe {
+            gl::Viewport(x, y, width as i32, height as i32);
+        }
+    }
+
+    fn draw_indexed(&mut self, vertex_array: &dyn VertexArray, index_count: Option<u32>) {
+        let index_count = match index_count {
+            Some(count) => count,
+            None => vertex_array
+                .get_index_buffer()
+                .map_or(0, |index_buffer| index_buffer.get_count()),
+        };
+        vertex_array.bind();
+        unsafe {
+            gl::DrawElements(
+                gl::TRIANGLES,
+                index_count as i32,
+                gl::UNSIGNED_INT,
+                ptr::null(),
+            );
+        }
+    }
+
+    fn draw_lines(&mut self, vertex_array: &dyn VertexArray, vertex_count: u32) {
+        vertex_array.bind();
+        unsafe {
+            gl::DrawArrays(gl::LINES, 0, vertex_count as i32);
+        }
+    }
+
+    fn set_line_width(&mut self, width: f32) {
+        unsafe {
+            gl::LineWidth(width);
+        }
+    }
+
+    fn create_vertex_buffer(&mut self, size: usize, data: Option<&[u8]>) -> Box<dyn VertexBuffer> {
+        Box::new(OpenGlVertexBuffer::create(size, data))
+    }
+
+    fn create_index_buffer(&mut self, indices: &[u32]) -> Box<dyn IndexBuffer> {
+        Box::new(OpenGlIndexBuffer::create(indices))
+    }
+
+    fn create_vertex_array(&mut self) -> Box<dyn VertexArray> {
+        Box::new(OpenGlVertexArray::create())
+    }
+
+    fn create_shader(&mut self, source: &ShaderSource) -> HexgemResult<Box<dyn Shader>> {
+        Ok(Box::new(OpenGlShader::create(source)?))
+    }
+
+    fn create_texture(
+        &mut self,
+        image: &ImageData,
+        options: &TextureOptions,
+    ) -> HexgemResult<Box<dyn TextureHandle>> {
+        Ok(Box::new(OpenGlTexture::create(image, options)))
+    }
+
+    fn create_framebuffer(
+        &mut self,
+        spec: &FramebufferSpec,
+    ) -> HexgemResult<Box<dyn FramebufferHandle>> {
+        Ok(Box::new(OpenGlFramebuffer::create(spec)?))
+    }
+
+    fn read_pixels(&mut self, x: i32, y: i32, width: u32, height: u32) -> HexgemResult<ImageData> {
+        let mut pixels = vec![0u8; width as usize * height as usize * 4];
+        unsafe {
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(
+                x,
+                y,
+                width as i32,
+                height as i32,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                pixels.as_mut_ptr() as *mut _,
+            );
+        }
+        let image = ImageData::from_rgba(width, height, pixels)?;
+        ImageData::from_rgba(width, height, image.flipped_rows())
+    }
+}
@@ -0,0 +1,229 @@
+use std::{cell::RefCell, collections::HashMap, ffi::CString, ptr};
+
+use gl::types::{GLchar, GLenum, GLint, GLuint};
+use log::warn;
+
+use crate::{
+    toAnyImpl,
+    Hexgem::{
+        error::HexgemResult,
+        renderer::HexgemRenderer::{Shader, ShaderSource, ShaderStage, UniformValue},
+    },
+};
+
+pub struct OpenGlShader {
+    renderer_id: GLuint,
+    name: String,
+    locations: RefCell<HashMap<String, GLint>>,
+}
+
+impl OpenGlShader {
+    pub fn create(source: &ShaderSource) -> HexgemResult<Self> {
+        let mut shaders = vec![];
+        for stage in source.get_stages() {
+            match compile_stage(stage.stage, &stage.source) {
+                Ok(shader) => shaders.push(shader),
+                Err(log) => {
+                    delete_shaders(&shaders);
+                    return Err(source.compile_error(stage.stage, &log));
+                }
+            }
+        }
+
+        let renderer_id = unsafe { gl::CreateProgram() };
+        let mut status = 0;
+        unsafe {
+            for shader in shaders.iter() {
+                gl::AttachShader(renderer_id, *shader);
+            }
+            gl::LinkProgram(renderer_id);
+            gl::GetProgramiv(renderer_id, gl::LINK_STATUS, &mut status);
+            for shader in shaders.iter() {
+                gl::DetachShader(renderer_id, *shader);
+            }
+        }
+        delete_shaders(&shaders);
+
+        if status == 0 {
+            let log = program_log(renderer_id);
+            unsafe {
+                gl::DeleteProgram(renderer_id);
+            }
+            return Err(source.link_error(&log));
+        }
+
+        Ok(Self {
+            renderer_id,
+            name: source.get_name().to_string(),
+            locations: RefCell::new(HashMap::new()),
+        })
+    }
+
+    pub fn get_renderer_id(&self) -> GLuint {
+        self.renderer_id
+    }
+
+    fn location(&self, name: &str) -> GLint {
+        if let Some(location) = self.locations.borrow().get(name) {
+            return *location;
+        }
+        let location = match CString::new(name) {
+            Ok(c_name) => unsafe { gl::GetUniformLocation(self.renderer_id, c_name.as_ptr()) },
+            Err(_) => -1,
+        };
+        if location == -1 {
+            warn!("Uniform {} not found in shader {}", name, self.name);
+        }
+        self.locations
+            .borrow_mut()
+            .insert(name.to_string(), location);
+        location
+    }
+}
+
+toAnyImpl!(OpenGlShader);
+
+impl Shader for OpenGlShader {
+    fn bind(&self) {
+        unsafe {
+            gl::UseProgram(self.renderer_id);
+        }
+    }
+
+    fn unbind(&self) {
+        unsafe {
+            gl::UseProgram(0);
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_uniform(&self, name: &str, value: UniformValue) {
+        let location = self.location(name);
+        if location == -1 {
+            return;
+        }
+        // glUniform writes to the bound program, so bind ours and restore the previous one.
+        // glProgramUniform would avoid this but needs GL 4.1.
+        let mut previous = 0;
+        unsafe {
+            gl::GetIntegerv(gl::CURRENT_PROGRAM, &mut previous);
+            if previous as GLuint != self.renderer_id {
+                gl::UseProgram(self.renderer_id);
+            }
+            match value {
+                UniformValue::Int(value) => gl::Uniform1i(location, value),
+                UniformValue::IntArray(values) => {
+                    gl::Uniform1iv(location, values.len() as i32, values.as_ptr())
+                }
+                UniformValue::Float(value) => gl::Uniform1f(location, value),
+                UniformValue::FloatArray(values) => {
+                    gl::Uniform1fv(location, values.len() as i32, values.as_ptr())
+                }
+                UniformValue::Float2(value) => gl::Uniform2f(location, value.x, value.y),
+                UniformValue::Float3(value) => gl::Uniform3f(location, value.x, value.y, value.z),
+                UniformValue::Float4(value) => {
+                    gl::Uniform4f(location, value.x, value.y, value.z, value.w)
+                }
+                UniformValue::Mat3(value) => {
+                    gl::UniformMatrix3fv(location, 1, gl::FALSE, value.to_cols_array().as_ptr())
+                }
+                UniformValue::Mat4(value) => {
+                    gl::UniformMatrix4fv(location, 1, gl::FALSE, value.to_cols_array().as_ptr())
+                }
+                UniformValue::Mat4Array(values) => {
+                    let data: Vec<f32> = values
+                        .iter()
+                        .flat_map(|value| value.to_cols_array())
+                        .collect();
+                    gl::UniformMatrix4fv(location, values.len() as i32, gl::FALSE, data.as_ptr())
+                }
+            }
+            if previous as GLuint != self.renderer_id {
+                gl::UseProgram(previous as GLuint);
+            }
+        }
+    }
+}
+
+impl Drop for OpenGlShader {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteProgram(self.renderer_id);
+        }
+    }
+}
+
+fn stage_type(stage: ShaderStage) -> GLenum {
+    match stage {
+        ShaderStage::Vertex => gl::VERTEX_SHADER,
+        ShaderStage::Fragment => gl::FRAGMENT_SHADER,
+        ShaderStage::Geometry => gl::GEOMETRY_SHADER,
+        ShaderStage::Compute => gl::COMPUTE_SHADER,
+    }
+}
+
+fn compile_stage(stage: ShaderStage, source: &str) -> Result<GLuint, String> {
+    let c_source = CString::new(source).map_err(|err| err.to_string())?;
+    unsafe {
+        let shader = gl::CreateShader(stage_type(stage));
+        gl::ShaderSource(shader, 1, &c_source.as_ptr(), ptr::null());
+        gl::CompileShader(shader);
+        let mut status = 0;
+        gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut status);
+        if status == 0 {
+            let log = shader_log(shader);
+            gl::DeleteShader(shader);
+            return Err(log);
+        }
+        Ok(shader)
+    }
+}
+
+fn delete_shaders(shaders: &[GLuint]) {
+    for shader in shaders {
+        unsafe {
+            gl::DeleteShader(*shader);
+        }
+    }
+}
+
+fn shader_log(shader: GLuint) -> String {
+    let mut length = 0;
+    unsafe {
+        gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut length);
+    }
+    let mut buffer = vec![0u8; length.max(1) as usize];
+    unsafe {
+        gl::GetShaderInfoLog(
+            shader,
+            length,
+            ptr::null_mut(),
+            buffer.as_mut_ptr() as *mut GLchar,
+        );
+    }
+    String::from_utf8_lossy(&buffer)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+fn program_log(program: GLuint) -> String {
+    let mut length = 0;
+    unsafe {
+        gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut length);
+    }
+    let mut buffer = vec![0u8; length.max(1) as usize];
+    unsafe {
+        gl::GetProgramInfoLog(
+            program,
+            length,
+            ptr::null_mut(),
+            buffer.as_mut_ptr() as *mut GLchar,
+        );
+    }
+    String::from_utf8_lossy(&buffer)
+        .trim_end_matches('\0')
+        .to_string()
+}
@@ -0,0 +1,136 @@
+use std::ptr;
+
+use gl::types::{GLenum, GLint, GLsizei, GLuint};
+
+use crate::{
+    toAnyImpl,
+    Hexgem::renderer::HexgemRenderer::{
+        ImageData, TextureFilter, TextureHandle, TextureOptions, TextureWrap,
+    },
+};
+
+pub struct OpenGlTexture {
+    renderer_id: GLuint,
+    width: u32,
+    height: u32,
+    mipmaps: bool,
+}
+
+impl OpenGlTexture {
+    pub fn create(image: &ImageData, options: &TextureOptions) -> Self {
+        let width = image.get_width();
+        let height = image.get_height();
+        let mut renderer_id = 0;
+        unsafe {
+            gl::GenTextures(1, &mut renderer_id);
+            gl::BindTexture(gl::TEXTURE_2D, renderer_id);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA8 as GLint,
+                width as GLsizei,
+                height as GLsizei,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                ptr::null(),
+            );
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_MIN_FILTER,
+                min_filter(options) as GLint,
+            );
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_MAG_FILTER,
+                filter(options.mag_filter) as GLint,
+            );
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_S,
+                wrap(options.wrap_s) as GLint,
+            );
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_T,
+                wrap(options.wrap_t) as GLint,
+            );
+        }
+        let texture = Self {
+            renderer_id,
+            width,
+            height,
+            mipmaps: options.mipmaps,
+        };
+        texture.set_data(image);
+        texture
+    }
+
+    pub fn get_renderer_id(&self) -> GLuint {
+        self.renderer_id
+    }
+}
+
+toAnyImpl!(OpenGlTexture);
+
+impl TextureHandle for OpenGlTexture {
+    fn bind(&self, slot: u32) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + slot);
+            gl::BindTexture(gl::TEXTURE_2D, self.renderer_id);
+        }
+    }
+
+    fn set_data(&self, image: &ImageData) {
+        let pixels = image.flipped_rows();
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.renderer_id);
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            gl::TexSubImage2D(
+                gl::TEXTURE_2D,
+                0,
+                0,
+                0,
+                self.width as GLsizei,
+                self.height as GLsizei,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                pixels.as_ptr() as *const _,
+            );
+            if self.mipmaps {
+                gl::GenerateMipmap(gl::TEXTURE_2D);
+            }
+        }
+    }
+}
+
+impl Drop for OpenGlTexture {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteTextures(1, &self.renderer_id);
+        }
+    }
+}
+
+fn filter(filter: TextureFilter) -> GLenum {
+    match filter {
+        TextureFilter::Nearest => gl::NEAREST,
+        TextureFilter::Linear => gl::LINEAR,
+    }
+}
+
+fn min_filter(options: &TextureOptions) -> GLenum {
+    match (options.mipmaps, options.min_filter) {
+        (false, min_filter) => filter(min_filter),
+        (true, TextureFilter::Nearest) => gl::NEAREST_MIPMAP_NEAREST,
+        (true, TextureFilter::Linear) => gl::LINEAR_MIPMAP_LINEAR,
+    }
+}
+
+fn wrap(wrap: TextureWrap) -> GLenum {
+    match wrap {
+        TextureWrap::Repeat => gl::REPEAT,
+        TextureWrap::MirroredRepeat => gl::MIRRORED_REPEAT,
+        TextureWrap::ClampToEdge => gl::CLAMP_TO_EDGE,
+    }
+}
@@ -0,0 +1,113 @@
+use gl::types::{GLint, GLsizei, GLuint};
+
+use crate::{
+    toAnyImpl,
+    Hexgem::{
+        error::{HexgemError, HexgemResult},
+        renderer::HexgemRenderer::{IndexBuffer, ShaderDataType, VertexArray, VertexBuffer},
+    },
+};
+
+pub struct OpenGlVertexArray {
+    renderer_id: GLuint,
+    attribute_index: GLuint,
+    vertex_buffers: Vec<Box<dyn VertexBuffer>>,
+    index_buffer: Option<Box<dyn IndexBuffer>>,
+}
+
+impl OpenGlVertexArray {
+    pub fn create() -> Self {
+        let mut renderer_id = 0;
+        unsafe {
+            gl::GenVertexArrays(1, &mut renderer_id);
+        }
+        Self {
+            renderer_id,
+            attribute_index: 0,
+            vertex_buffers: vec![],
+            index_buffer: None,
+        }
+    }
+}
+
+toAnyImpl!(OpenGlVertexArray);
+
+impl VertexArray for OpenGlVertexArray {
+    fn bind(&self) {
+        unsafe {
+            gl::BindVertexArray(self.renderer_id);
+        }
+    }
+
+    fn unbind(&self) {
+        unsafe {
+            gl::BindVertexArray(0);
+        }
+    }
+
+    fn add_vertex_buffer(&mut self, vertex_buffer: Box<dyn VertexBuffer>) -> HexgemResult<()> {
+        if vertex_buffer.get_layout().is_empty() {
+            return Err(HexgemError::Renderer(
+                "Vertex buffer has no layout".to_string(),
+            ));
+        }
+        self.bind();
+        vertex_buffer.bind();
+        for attribute in vertex_buffer.get_layout().attributes() {
+            unsafe {
+                gl::EnableVertexAttribArray(self.attribute_index);
+                if attribute.data_type.is_integer() {
+                    let gl_type = match attribute.data_type {
+                        ShaderDataType::Bool => gl::UNSIGNED_BYTE,
+                        _ => gl::INT,
+                    };
+                    gl::VertexAttribIPointer(
+                        self.attribute_index,
+                        attribute.components as GLint,
+                        gl_type,
+                        attribute.stride as GLsizei,
+                        attribute.offset as usize as *const _,
+                    );
+                } else {
+                    gl::VertexAttribPointer(
+                        self.attribute_index,
+                        attribute.components as GLint,
+                        gl::FLOAT,
+                        attribute.normalized as u8,
+                        attribute.stride as GLsizei,
+                        attribute.offset as usize as *const _,
+                    );
+                }
+            }
+            self.attribute_index += 1;
+        }
+        self.vertex_buffers.push(vertex_buffer);
+        Ok(())
+    }
+
+    fn set_index_buffer(&mut self, index_buffer: Box<dyn IndexBuffer>) {
+        self.bind();
+        index_buffer.bind();
+        self.index_buffer = Some(index_buffer);
+    }
+
+    fn get_vertex_buffers(&self) -> &[Box<dyn VertexBuffer>] {
+        &self.vertex_buffers
+    }
+
+    fn get_vertex_buffers_mut(&mut self) -> &mut [Box<dyn VertexBuffer>] {
+        &mut self.vertex_buffers
+    }
+
+    fn get_index_buffer(&self) -> Option<&dyn IndexBuffer> {
+        self.index_buffer.as_deref()
+    }
+}
+
+impl Drop for OpenGlVertexArray {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.renderer_id);
+        }
+    }
+}
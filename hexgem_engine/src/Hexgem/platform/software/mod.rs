@@ -0,0 +1,11 @@
+mod software_program;
+mod software_renderer_api;
+mod software_surface;
+pub mod SoftwarePlatform {
+    pub use super::software_program::{
+        get_mat4, CircleProgram, FlatColorProgram, FragmentContext, LineProgram, QuadProgram,
+        ShadedVertex, SoftwareProgram, Uniforms, VertexInput,
+    };
+    pub use super::software_renderer_api::SoftwareRendererApi;
+    pub use super::software_surface::SoftwareSurface;
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use glam::{Mat4, Vec2, Vec3, Vec4};
+
+use crate::Hexgem::renderer::{CpuBindings, HexgemRenderer::UniformValue};
+
+pub type Uniforms = HashMap<String, UniformValue>;
+
+// Attribute values of one vertex, one entry per buffer layout element in declaration order.
+pub struct VertexInput {
+    names: Vec<String>,
+    values: Vec<Vec<f32>>,
+}
+
+impl VertexInput {
+    pub(crate) fn create(names: Vec<String>, values: Vec<Vec<f32>>) -> Self {
+        Self { names, values }
+    }
+
+    pub fn get(&self, index: usize) -> &[f32] {
+        self.values
+            .get(index)
+            .map_or(&[], |values| values.as_slice())
+    }
+
+    pub fn find(&self, name: &str) -> Option<&[f32]> {
+        let index = self.names.iter().position(|element| element == name)?;
+        Some(self.get(index))
+    }
+
+    pub fn get_float(&self, index: usize) -> f32 {
+        self.get(index).first().copied().unwrap_or(0.0)
+    }
+
+    pub fn get_vec2(&self, index: usize) -> Vec2 {
+        self.get_vec4(index, 0.0).truncate().truncate()
+    }
+
+    pub fn get_vec3(&self, index: usize) -> Vec3 {
+        self.get_vec4(index, 0.0).truncate()
+    }
+
+    // Missing components are filled like OpenGL does, w defaults to the given value.
+    pub fn get_vec4(&self, index: usize, w: f32) -> Vec4 {
+        let values = self.get(index);
+        let mut result = Vec4::new(0.0, 0.0, 0.0, w);
+        for (component, value) in values.iter().take(4).enumerate() {
+            result[component] = *value;
+        }
+        result
+    }
+}
+
+pub struct ShadedVertex {
+    pub position: Vec4,
+    pub varyings: Vec<f32>,
+}
+
+pub struct FragmentContext<'a> {
+    uniforms: &'a Uniforms,
+    bindings: &'a CpuBindings,
+}
+
+impl<'a> FragmentContext<'a> {
+    pub(crate) fn create(uniforms: &'a Uniforms, bindings: &'a CpuBindings) -> Self {
+        Self { uniforms, bindings }
+    }
+
+    pub fn get_uniform(&self, name: &str) -> Option<&UniformValue> {
+        self.uniforms.get(name)
+    }
+
+    // Samples the texture unit a sampler uniform (or one element of a sampler array) points
+    // at. Unbound units read as opaque black, like an incomplete texture in OpenGL.
+    pub fn sample(&self, sampler: &str, index: usize, uv: Vec2) -> Vec4 {
+        let slot = match self.uniforms.get(sampler) {
+            Some(UniformValue::Int(slot)) => *slot,
+            Some(UniformValue::IntArray(slots)) => slots.get(index).copied().unwrap_or(0),
+            _ => index as i32,
+        };
+        self.bindings
+            .get_texture(slot.max(0) as u32)
+            .map_or(Vec4::new(0.0, 0.0, 0.0, 1.0), |texture| texture.sample(uv))
+    }
+}
+
+// CPU stand-in for a shader program. The software backend picks the program registered
+// under the name of the bound shader.
+pub trait SoftwareProgram {
+    fn vertex(&self, input: &VertexInput, uniforms: &Uniforms) -> ShadedVertex;
+    // Returning None discards the fragment.
+    fn fragment(&self, varyings: &[f32], context: &FragmentContext) -> Option<Vec4>;
+}
+
+pub fn get_mat4(uniforms: &Uniforms, name: &str) -> Mat4 {
+    match uniforms.get(name) {
+        Some(UniformValue::Mat4(matrix)) => *matrix,
+        _ => Mat4::IDENTITY,
+    }
+}
+
+fn varying_vec4(varyings: &[f32], offset: usize) -> Vec4 {
+    Vec4::from_slice(&varyings[offset..offset + 4])
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+pub struct QuadProgram;
+
+impl SoftwareProgram for QuadProgram {
+    fn vertex(&self, input: &VertexInput, uniforms: &Uniforms) -> ShadedVertex {
+        let color = input.get_vec4(1, 1.0);
+        let tex_coord = input.get_vec2(2) * input.get_float(4);
+        let mut varyings = color.to_array().to_vec();
+        varyings.extend([tex_coord.x, tex_coord.y, input.get_float(3)]);
+        ShadedVertex {
+            position: get_mat4(uniforms, "u_ViewProjection") * input.get_vec3(0).extend(1.0),
+            varyings,
+        }
+    }
+
+    fn fragment(&self, varyings: &[f32], context: &FragmentContext) -> Option<Vec4> {
+        let tex_coord = Vec2::new(varyings[4], varyings[5]);
+        let tex_index = varyings[6].round().max(0.0) as usize;
+        Some(varying_vec4(varyings, 0) * context.sample("u_Textures", tex_index, tex_coord))
+    }
+}
+
+pub struct CircleProgram;
+
+impl SoftwareProgram for CircleProgram {
+    fn vertex(&self, input: &VertexInput, uniforms: &Uniforms) -> ShadedVertex {
+        let mut varyings = input.get_vec3(1).to_array().to_vec();
+        varyings.extend(input.get_vec4(2, 1.0).to_array());
+        varyings.extend([input.get_float(3), input.get_float(4)]);
+        ShadedVertex {
+            position: get_mat4(uniforms, "u_ViewProjection") * input.get_vec3(0).extend(1.0),
+            varyings,
+        }
+    }
+
+    fn fragment(&self, varyings: &[f32], context: &FragmentContext) -> Option<Vec4> {
+        let distance = 1.0 - Vec3::from_slice(&varyings[0..3]).length();
+        let (thickness, fade) = (varyings[7], varyings[8]);
+        let circle =
+            smoothstep(0.0, fade, distance) * smoothstep(thickness + fade, thickness, distance);
+        if circle == 0.0 {
+            return None;
+        }
+        let color = varying_vec4(varyings, 3);
+        Some(color.truncate().extend(color.w * circle))
+    }
+}
+
+pub struct LineProgram;
+
+impl SoftwareProgram for LineProgram {
+    fn vertex(&self, input: &VertexInput, uniforms: &Uniforms) -> ShadedVertex {
+        ShadedVertex {
+            position: get_mat4(uniforms, "u_ViewProjection") * input.get_vec3(0).extend(1.0),
+            varyings: input.get_vec4(1, 1.0).to_array().to_vec(),
+        }
+    }
+
+    fn fragment(&self, varyings: &[f32], context: &FragmentContext) -> Option<Vec4> {
+        Some(varying_vec4(varyings, 0))
+    }
+}
+
+// Used for shaders without a registered program: the first element is the position, a
+// four component second element is the vertex color, tinted by an optional u_Color.
+pub struct FlatColorProgram;
+
+impl SoftwareProgram for FlatColorProgram {
+    fn vertex(&self, input: &VertexInput, uniforms: &Uniforms) -> ShadedVertex {
+        let color = match input.get(1).len() {
+            4 => input.get_vec4(1, 1.0),
+            _ => Vec4::ONE,
+        };
+        let transform = get_mat4(uniforms, "u_ViewProjection") * get_mat4(uniforms, "u_Transform");
+        ShadedVertex {
+            position: transform * input.get_vec3(0).extend(1.0),
+            varyings: color.to_array().to_vec(),
+        }
+    }
+
+    fn fragment(&self, varyings: &[f32], context: &FragmentContext) -> Option<Vec4> {
+        let tint = match context.get_uniform("u_Color") {
+            Some(UniformValue::Float4(color)) => *color,
+            _ => Vec4::ONE,
+        };
+        Some(varying_vec4(varyings, 0) * tint)
+    }
+}
@@ -0,0 +1,582 @@
+use std::{collections::HashMap, rc::Rc};
+
+use glam::{Vec2, Vec3, Vec4};
+use log::warn;
+
+use crate::Hexgem::{
+    error::{HexgemError, HexgemResult},
+    renderer::{
+        CpuBindings, CpuRenderTarget,
+        HexgemRenderer::{
+            CpuFramebuffer, CpuIndexBuffer, CpuShader, CpuTexture, CpuVertexArray, CpuVertexBuffer,
+            FramebufferHandle, FramebufferSpec, GraphicsApi, ImageData, IndexBuffer, RendererApi,
+            Shader, ShaderDataType, ShaderSource, TextureHandle, TextureOptions, VertexArray,
+            VertexBuffer,
+        },
+    },
+};
+
+use super::{
+    software_program::{
+        CircleProgram, FlatColorProgram, FragmentContext, LineProgram, QuadProgram, ShadedVertex,
+        SoftwareProgram, Uniforms, VertexInput,
+    },
+    software_surface::SoftwareSurface,
+};
+
+// A vertex after the viewport transform, ready to be rasterized.
+struct ScreenVertex<'a> {
+    position: Vec3,
+    inverse_w: f32,
+    varyings: &'a [f32],
+}
+
+// Where draw calls end up, the bound framebuffer or else the surface.
+enum RenderTarget {
+    Surface(SoftwareSurface),
+    Framebuffer(CpuRenderTarget),
+}
+
+impl RenderTarget {
+    fn get_width(&self) -> u32 {
+        match self {
+            RenderTarget::Surface(surface) => surface.get_width(),
+            RenderTarget::Framebuffer(framebuffer) => framebuffer.get_width(),
+        }
+    }
+
+    fn get_height(&self) -> u32 {
+        match self {
+            RenderTarget::Surface(surface) => surface.get_height(),
+            RenderTarget::Framebuffer(framebuffer) => framebuffer.get_height(),
+        }
+    }
+
+    fn get_image(&self) -> HexgemResult<ImageData> {
+        match self {
+            RenderTarget::Surface(surface) => Ok(surface.get_image()),
+            RenderTarget::Framebuffer(framebuffer) => framebuffer.get_image(),
+        }
+    }
+
+    fn clear(&self, color: Vec4, depth: f32) {
+        match self {
+            RenderTarget::Surface(surface) => surface.clear(color, depth),
+            RenderTarget::Framebuffer(framebuffer) => framebuffer.clear(color, depth),
+        }
+    }
+
+    fn blend(&self, x: u32, y: u32, depth: f32, color: Vec4) {
+        match self {
+            RenderTarget::Surface(surface) => surface.blend(x, y, depth, color),
+            RenderTarget::Framebuffer(framebuffer) => framebuffer.blend(x, y, depth, color),
+        }
+    }
+}
+
+// The target of a draw call with its viewport, looked up once per call.
+struct DrawTarget {
+    target: RenderTarget,
+    viewport: (i32, i32, u32, u32),
+    // Pixel bounds covered by both the viewport and the target.
+    bounds: (i32, i32, i32, i32),
+}
+
+impl DrawTarget {
+    fn to_screen<'a>(&self, vertex: &'a ShadedVertex) -> Option<ScreenVertex<'a>> {
+        // Geometry behind the camera is dropped instead of clipped.
+        if vertex.position.w <= 0.0 {
+            return None;
+        }
+        let (x, y, width, height) = self.viewport;
+        let ndc = vertex.position.truncate() / vertex.position.w;
+        Some(ScreenVertex {
+            position: Vec3::new(
+                x as f32 + (ndc.x + 1.0) * 0.5 * width as f32,
+                y as f32 + (ndc.y + 1.0) * 0.5 * height as f32,
+                (ndc.z + 1.0) * 0.5,
+            ),
+            inverse_w: 1.0 / vertex.position.w,
+            varyings: &vertex.varyings,
+        })
+    }
+
+    fn shade_pixel(
+        &self,
+        program: &dyn SoftwareProgram,
+        context: &FragmentContext,
+        x: i32,
+        y: i32,
+        depth: f32,
+        varyings: &[f32],
+    ) {
+        let (min_x, min_y, max_x, max_y) = self.bounds;
+        if x < min_x || y < min_y || x >= max_x || y >= max_y || !(0.0..=1.0).contains(&depth) {
+            return;
+        }
+        if let Some(color) = program.fragment(varyings, context) {
+            self.target.blend(x as u32, y as u32, depth, color);
+        }
+    }
+}
+
+// Renders on the CPU into a SoftwareSurface, or into a framebuffer it created while that is
+// bound. Draw calls run the SoftwareProgram registered under the bound shader's name,
+// Renderer2D shaders are registered by default.
+pub struct SoftwareRendererApi {
+    surface: SoftwareSurface,
+    bindings: CpuBindings,
+    programs: HashMap<String, Rc<dyn SoftwareProgram>>,
+    fallback: Rc<dyn SoftwareProgram>,
+    clear_color: Vec4,
+    line_width: f32,
+}
+
+impl SoftwareRendererApi {
+    pub fn create(surface: SoftwareSurface) -> Self {
+        let bindings = CpuBindings::default();
+        bindings.set_viewport((0, 0, surface.get_width(), surface.get_height()));
+        let mut api = Self {
+            surface,
+            bindings,
+            programs: HashMap::new(),
+            fallback: Rc::new(FlatColorProgram),
+            clear_color: Vec4::ZERO,
+            line_width: 1.0,
+        };
+        api.register_program("Renderer2D_Quad", QuadProgram);
+        api.register_program("Renderer2D_Circle", CircleProgram);
+        api.register_program("Renderer2D_Line", LineProgram);
+        api
+    }
+
+    pub fn get_surface(&self) -> &SoftwareSurface {
+        &self.surface
+    }
+
+    pub fn register_program<T: SoftwareProgram + 'static>(&mut self, name: &str, program: T) {
+        self.programs.insert(name.to_string(), Rc::new(program));
+    }
+
+    // Runs the vertex stage for every vertex of the vertex array, indexed like the buffers.
+    fn shade_vertices(
+        &self,
+        vertex_array: &dyn VertexArray,
+        program: &dyn SoftwareProgram,
+        uniforms: &Uniforms,
+        vertex_count: usize,
+    ) -> Vec<ShadedVertex> {
+        let mut names = vec![];
+        for vertex_buffer in vertex_array.get_vertex_buffers() {
+            for element in vertex_buffer.get_layout().get_elements() {
+                names.push(element.name.clone());
+            }
+        }
+        (0..vertex_count)
+            .map(|vertex| {
+                let mut values = vec![];
+                for vertex_buffer in vertex_array.get_vertex_buffers() {
+                    read_vertex(vertex_buffer.as_ref(), vertex, &mut values);
+                }
+                program.vertex(&VertexInput::create(names.clone(), values), uniforms)
+            })
+            .collect()
+    }
+
+    fn get_target(&self) -> RenderTarget {
+        match self.bindings.get_framebuffer() {
+            Some(framebuffer) => RenderTarget::Framebuffer(framebuffer),
+            None => RenderTarget::Surface(self.surface.clone()),
+        }
+    }
+
+    fn get_draw_target(&self) -> DrawTarget {
+        let target = self.get_target();
+        let viewport = self.bindings.get_viewport();
+        let (x, y, width, height) = viewport;
+        let bounds = (
+            x.max(0),
+            y.max(0),
+            (x + width as i32).min(target.get_width() as i32),
+            (y + height as i32).min(target.get_height() as i32),
+        );
+        DrawTarget {
+            target,
+            viewport,
+            bounds,
+        }
+    }
+
+    // Samples pixel centers and follows the top-left fill rule, so triangles sharing an edge
+    // never blend a pixel twice.
+    fn draw_triangle(
+        &self,
+        target: &DrawTarget,
+        program: &dyn SoftwareProgram,
+        context: &FragmentContext,
+        vertices: [&ShadedVertex; 3],
+    ) {
+        let [Some(v0), Some(mut v1), Some(mut v2)] = vertices.map(|v| target.to_screen(v)) else {
+            return;
+        };
+        let mut area = edge(v0.position, v1.position, v2.position.truncate());
+        if area < 0.0 {
+            std::mem::swap(&mut v1, &mut v2);
+            area = -area;
+        }
+        if area == 0.0 {
+            return;
+        }
+        let (min_x, min_y, max_x, max_y) = target.bounds;
+        let positions = [v0.position, v1.position, v2.position];
+        let left = positions.iter().map(|p| p.x).fold(f32::MAX, f32::min);
+        let right = positions.iter().map(|p| p.x).fold(f32::MIN, f32::max);
+        let bottom = positions.iter().map(|p| p.y).fold(f32::MAX, f32::min);
+        let top = positions.iter().map(|p| p.y).fold(f32::MIN, f32::max);
+        let start_x = (left.floor() as i32).max(min_x);
+        let end_x = (right.ceil() as i32).min(max_x);
+        let start_y = (bottom.floor() as i32).max(min_y);
+        let end_y = (top.ceil() as i32).min(max_y);
+
+        let edges = [
+            (v1.position, v2.position),
+            (v2.position, v0.position),
+            (v0.position, v1.position),
+        ];
+        let vertices = [&v0, &v1, &v2];
+        let mut varyings = vec![0.0; v0.varyings.len()];
+        for y in start_y..end_y {
+            for x in start_x..end_x {
+                let point = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let weights = edges.map(|(a, b)| edge(a, b, point));
+                let inside = weights.iter().zip(edges.iter()).all(|(weight, (a, b))| {
+                    *weight > 0.0 || (*weight == 0.0 && is_top_left(*a, *b))
+                });
+                if !inside {
+                    continue;
+                }
+                let barycentric = weights.map(|weight| weight / area);
+                let depth = (0..3)
+                    .map(|i| barycentric[i] * vertices[i].position.z)
+                    .sum::<f32>();
+                // Perspective correct interpolation, weights are divided by clip space w.
+                let corrected = [0, 1, 2].map(|i| barycentric[i] * vertices[i].inverse_w);
+                let total = corrected.iter().sum::<f32>();
+                for (index, varying) in varyings.iter_mut().enumerate() {
+                    *varying = (0..3)
+                        .map(|i| corrected[i] * vertices[i].varyings[index])
+                        .sum::<f32>()
+                        / total;
+                }
+                target.shade_pixel(program, context, x, y, depth, &varyings);
+            }
+        }
+    }
+
+    fn draw_line(
+        &self,
+        target: &DrawTarget,
+        program: &dyn SoftwareProgram,
+        context: &FragmentContext,
+        vertices: [&ShadedVertex; 2],
+    ) {
+        let [Some(start), Some(end)] = vertices.map(|v| target.to_screen(v)) else {
+            return;
+        };
+        let delta = end.position - start.position;
+        let steps = delta.x.abs().max(delta.y.abs()).round().max(1.0) as i32;
+        let width = self.line_width.round().max(1.0) as i32;
+        let horizontal = delta.x.abs() >= delta.y.abs();
+        let mut varyings = vec![0.0; start.varyings.len()];
+        for step in 0..=steps {
+            let t = step as f32 / steps as f32;
+            let point = start.position + delta * t;
+            for (index, varying) in varyings.iter_mut().enumerate() {
+                *varying =
+                    start.varyings[index] + (end.varyings[index] - start.varyings[index]) * t;
+            }
+            let (x, y) = (point.x.floor() as i32, point.y.floor() as i32);
+            // Wide lines grow across the major axis, like OpenGL's aliased lines.
+            for offset in 0..width {
+                let offset = offset - (width - 1) / 2;
+                let (x, y) = if horizontal {
+                    (x, y + offset)
+                } else {
+                    (x + offset, y)
+                };
+                target.shade_pixel(program, context, x, y, point.z, &varyings);
+            }
+        }
+    }
+
+    fn bound_program(&self) -> Option<(Rc<dyn SoftwareProgram>, Uniforms)> {
+        let Some(shader) = self.bindings.get_shader() else {
+            warn!("Software renderer draw call without a bound shader");
+            return None;
+        };
+        let program = self
+            .programs
+            .get(&shader.name)
+            .cloned()
+            .unwrap_or_else(|| self.fallback.clone());
+        let uniforms = shader.uniforms.borrow().clone();
+        Some((program, uniforms))
+    }
+}
+
+impl RendererApi for SoftwareRendererApi {
+    fn get_api(&self) -> GraphicsApi {
+        GraphicsApi::Software
+    }
+
+    fn set_clear_color(&mut self, color: Vec4) {
+        self.clear_color = color;
+    }
+
+    fn clear(&mut self) {
+        self.get_target().clear(self.clear_color, 1.0);
+    }
+
+    fn set_viewport(&mut self, x: i32, y: i32, width: u32, height: u32) {
+        self.bindings.set_viewport((x, y, width, height));
+    }
+
+    fn draw_indexed(&mut self, vertex_array: &dyn VertexArray, index_count: Option<u32>) {
+        let Some((program, uniforms)) = self.bound_program() else {
+            return;
+        };
+        let Some(indices) = vertex_array
+            .get_index_buffer()
+            .and_then(|index_buffer| index_buffer.as_any().downcast_ref::<CpuIndexBuffer>())
+            .map(|index_buffer| index_buffer.get_indices())
+        else {
+            warn!("Software renderer can only draw CPU index buffers");
+            return;
+        };
+        let count = index_count.map_or(indices.len(), |count| (count as usize).min(indices.len()));
+        let indices = &indices[..count - count % 3];
+        let vertex_count = indices.iter().max().map_or(0, |max| *max as usize + 1);
+        let vertices = self.shade_vertices(vertex_array, program.as_ref(), &uniforms, vertex_count);
+        let context = FragmentContext::create(&uniforms, &self.bindings);
+        let target = self.get_draw_target();
+        for triangle in indices.chunks_exact(3) {
+            let triangle = [0, 1, 2].map(|i| &vertices[triangle[i] as usize]);
+            self.draw_triangle(&target, program.as_ref(), &context, triangle);
+        }
+    }
+
+    fn draw_lines(&mut self, vertex_array: &dyn VertexArray, vertex_count: u32) {
+        let Some((program, uniforms)) = self.bound_program() else {
+            return;
+        };
+        let vertex_count = vertex_count as usize - vertex_count as usize % 2;
+        let vertices = self.shade_vertices(vertex_array, program.as_ref(), &uniforms, vertex_count);
+        let context = FragmentContext::create(&uniforms, &self.bindings);
+        let target = self.get_draw_target();
+        for line in vertices.chunks_exact(2) {
+            self.draw_line(&target, program.as_ref(), &context, [&line[0], &line[1]]);
+        }
+    }
+
+    fn set_line_width(&mut self, width: f32) {
+        self.line_width = width;
+    }
+
+    fn create_vertex_buffer(&mut self, size: usize, data: Option<&[u8]>) -> Box<dyn VertexBuffer> {
+        Box::new(CpuVertexBuffer::create(size, data, None))
+    }
+
+    fn create_index_buffer(&mut self, indices: &[u32]) -> Box<dyn IndexBuffer> {
+        Box::new(CpuIndexBuffer::create(indices, None))
+    }
+
+    fn create_vertex_array(&mut self) -> Box<dyn VertexArray> {
+        Box::new(CpuVertexArray::create(None))
+    }
+
+    fn create_shader(&mut self, source: &ShaderSource) -> HexgemResult<Box<dyn Shader>> {
+        Ok(Box::new(
+            CpuShader::create(source, None).with_bindings(self.bindings.clone()),
+        ))
+    }
+
+    fn create_texture(
+        &mut self,
+        image: &ImageData,
+        options: &TextureOptions,
+    ) -> HexgemResult<Box<dyn TextureHandle>> {
+        Ok(Box::new(
+            CpuTexture::create(image, options, None).with_bindings(self.bindings.clone()),
+        ))
+    }
+
+    fn create_framebuffer(
+        &mut self,
+        spec: &FramebufferSpec,
+    ) -> HexgemResult<Box<dyn FramebufferHandle>> {
+        Ok(Box::new(
+            CpuFramebuffer::create(spec, None).with_bindings(self.bindings.clone()),
+        ))
+    }
+
+    fn read_pixels(&mut self, x: i32, y: i32, width: u32, height: u32) -> HexgemResult<ImageData> {
+        let target = self.get_target();
+        let (target_width, target_height) = (target.get_width(), target.get_height());
+        let outside = |start: i32, length: u32, limit: u32| {
+            u32::try_from(start)
+                .ok()
+                .and_then(|start| start.checked_add(length))
+                .filter(|end| *end <= limit)
+                .is_none()
+        };
+        if outside(x, width, target_width) || outside(y, height, target_height) {
+            return Err(HexgemError::Renderer(format!(
+                "Region {}x{} at ({}, {}) is outside of the {}x{} target",
+                width, height, x, y, target_width, target_height
+            )));
+        }
+        let image = target.get_image()?;
+        let top = target_height - y as u32 - height;
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+        for row in top..top + height {
+            for column in x as u32..x as u32 + width {
+                pixels.extend(image.pixel(column, row));
+            }
+        }
+        ImageData::from_rgba(width, height, pixels)
+    }
+}
+
+fn edge(a: Vec3, b: Vec3, point: Vec2) -> f32 {
+    (b.x - a.x) * (point.y - a.y) - (b.y - a.y) * (point.x - a.x)
+}
+
+// For counter clockwise triangles with y pointing up, left edges run downwards and top edges
+// run towards negative x.
+fn is_top_left(a: Vec3, b: Vec3) -> bool {
+    b.y < a.y || (a.y == b.y && b.x < a.x)
+}
+
+fn read_vertex(vertex_buffer: &dyn VertexBuffer, vertex: usize, values: &mut Vec<Vec<f32>>) {
+    let layout = vertex_buffer.get_layout();
+    let data = vertex_buffer
+        .as_any()
+        .downcast_ref::<CpuVertexBuffer>()
+        .map_or(&[][..], |buffer| buffer.get_data());
+    let base = vertex * layout.get_stride() as usize;
+    for element in layout.get_elements() {
+        let offset = base + element.offset as usize;
+        let Some(bytes) = data.get(offset..offset + element.size as usize) else {
+            values.push(vec![]);
+            continue;
+        };
+        let element_values = match element.data_type {
+            ShaderDataType::Bool => vec![bytes[0] as f32],
+            data_type if data_type.is_integer() => bytes
+                .chunks_exact(4)
+                .map(|chunk| i32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) as f32)
+                .collect(),
+            _ => bytes
+                .chunks_exact(4)
+                .map(|chunk| f32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect(),
+        };
+        values.push(element_values);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use glam::{Mat4, Vec2, Vec3, Vec4};
+
+    use super::*;
+    use crate::Hexgem::renderer::HexgemRenderer::{
+        Framebuffer, FramebufferTextureFormat, GoldenImage, Quad, RenderCommand, Renderer2D,
+        Texture2D,
+    };
+
+    const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+
+    fn setup(width: u32, height: u32) -> SoftwareSurface {
+        let surface = SoftwareSurface::create(width, height).unwrap();
+        RenderCommand::init(SoftwareRendererApi::create(surface.clone()));
+        Renderer2D::init().unwrap();
+        RenderCommand::set_clear_color(Vec4::new(0.1, 0.1, 0.1, 1.0));
+        RenderCommand::clear();
+        surface
+    }
+
+    fn checkerboard() -> Rc<Texture2D> {
+        let (dark, light) = ([40, 40, 160, 255], [230, 200, 60, 255]);
+        let pixels = [dark, light, light, dark].concat();
+        let image = ImageData::from_rgba(2, 2, pixels).unwrap();
+        Rc::new(Texture2D::from_image(&image, TextureOptions::pixel_art()).unwrap())
+    }
+
+    fn draw_quads(projection: &Mat4) {
+        Renderer2D::begin_scene(projection);
+        Renderer2D::draw_quad(
+            &Quad::create(Vec3::new(10.0, 14.0, 0.0), Vec2::new(12.0, 16.0))
+                .color(Vec4::new(0.9, 0.2, 0.3, 1.0)),
+        );
+        Renderer2D::draw_quad(
+            &Quad::create(Vec3::new(22.0, 12.0, 0.1), Vec2::splat(12.0))
+                .texture(checkerboard())
+                .rotation(0.3),
+        );
+        Renderer2D::end_scene();
+    }
+
+    #[test]
+    fn renderer_2d_quads_match_golden_image() {
+        let surface = setup(32, 32);
+        draw_quads(&Mat4::orthographic_rh_gl(0.0, 32.0, 0.0, 32.0, -1.0, 1.0));
+        GoldenImage::create(format!("{}/renderer_2d_quads.png", GOLDEN_DIR))
+            .check(&surface.get_image())
+            .unwrap();
+    }
+
+    #[test]
+    fn draws_go_to_the_bound_framebuffer() {
+        let projection = Mat4::orthographic_rh_gl(0.0, 32.0, 0.0, 32.0, -1.0, 1.0);
+        let surface = setup(32, 32);
+        draw_quads(&projection);
+        let expected = surface.get_image();
+        RenderCommand::clear();
+        let cleared = surface.get_image();
+
+        let spec = FramebufferSpec::create(32, 32).attachments(&[
+            FramebufferTextureFormat::Rgba8,
+            FramebufferTextureFormat::Depth24Stencil8,
+        ]);
+        let framebuffer = Framebuffer::create(spec).unwrap();
+        framebuffer.bind();
+        RenderCommand::clear();
+        draw_quads(&projection);
+        framebuffer.unbind();
+
+        assert_eq!(surface.get_image(), cleared);
+        assert_eq!(framebuffer.read_image(0).unwrap(), expected);
+    }
+
+    #[test]
+    fn read_pixels_rejects_regions_outside_the_surface() {
+        let mut api = SoftwareRendererApi::create(SoftwareSurface::create(4, 4).unwrap());
+        assert!(api.read_pixels(0, 0, 4, 4).is_ok());
+        assert!(api.read_pixels(-1, 0, 1, 1).is_err());
+        assert!(api.read_pixels(2, 0, 3, 1).is_err());
+        assert!(api.read_pixels(0, 1, 1, u32::MAX).is_err());
+        assert!(api.read_pixels(1, 0, u32::MAX, 1).is_err());
+    }
+    #[test]
+    fn surfaces_keep_their_buffers_on_zero_sized_resizes() {
+        assert!(SoftwareSurface::create(0, 4).is_err());
+        let surface = SoftwareSurface::create(4, 2).unwrap();
+        surface.resize(0, 0).unwrap();
+        assert_eq!((surface.get_width(), surface.get_height()), (4, 2));
+        surface.resize(8, 3).unwrap();
+        assert_eq!(surface.get_image().get_pixels().len(), 8 * 3 * 4);
+    }
+}
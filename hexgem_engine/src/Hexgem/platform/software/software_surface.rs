@@ -0,0 +1,100 @@
+use std::{cell::RefCell, rc::Rc};
+
+use glam::Vec4;
+
+use crate::Hexgem::{error::HexgemResult, renderer::HexgemRenderer::ImageData};
+
+struct SurfaceData {
+    color: ImageData,
+    depth: Vec<f32>,
+}
+
+// RGBA8 color buffer with a depth buffer, shared between a headless window and the
+// software renderer drawing into it. Coordinates passed in count rows from the bottom.
+#[derive(Clone)]
+pub struct SoftwareSurface {
+    data: Rc<RefCell<SurfaceData>>,
+}
+
+impl SoftwareSurface {
+    pub fn create(width: u32, height: u32) -> HexgemResult<Self> {
+        Ok(Self {
+            data: Rc::new(RefCell::new(SurfaceData {
+                color: ImageData::filled(width, height, [0, 0, 0, 255])?,
+                depth: vec![1.0; width as usize * height as usize],
+            })),
+        })
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.data.borrow().color.get_width()
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.data.borrow().color.get_height()
+    }
+
+    // A zero sized request comes from a minimized window and keeps the current buffers.
+    pub fn resize(&self, width: u32, height: u32) -> HexgemResult<()> {
+        if width == 0 || height == 0 || (width == self.get_width() && height == self.get_height()) {
+            return Ok(());
+        }
+        *self.data.borrow_mut() = SurfaceData {
+            color: ImageData::filled(width, height, [0, 0, 0, 255])?,
+            depth: vec![1.0; width as usize * height as usize],
+        };
+        Ok(())
+    }
+
+    // Copy of the color buffer, rows top first like any other ImageData.
+    pub fn get_image(&self) -> ImageData {
+        self.data.borrow().color.clone()
+    }
+
+    pub fn clear(&self, color: Vec4, depth: f32) {
+        let mut data = self.data.borrow_mut();
+        data.color.fill(to_rgba8(color));
+        data.depth.fill(depth);
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> Vec4 {
+        let data = self.data.borrow();
+        let pixel = data.color.pixel(x, data.color.get_height() - 1 - y);
+        Vec4::new(
+            pixel[0] as f32,
+            pixel[1] as f32,
+            pixel[2] as f32,
+            pixel[3] as f32,
+        ) / 255.0
+    }
+
+    // Depth tested (less or equal) and alpha blended write, matching the OpenGL backend state.
+    pub(crate) fn blend(&self, x: u32, y: u32, depth: f32, color: Vec4) {
+        let mut data = self.data.borrow_mut();
+        let width = data.color.get_width();
+        let height = data.color.get_height();
+        let index = y as usize * width as usize + x as usize;
+        if depth > data.depth[index] {
+            return;
+        }
+        data.depth[index] = depth;
+        let row = height - 1 - y;
+        let pixel = data.color.pixel(x, row);
+        let destination = Vec4::new(
+            pixel[0] as f32,
+            pixel[1] as f32,
+            pixel[2] as f32,
+            pixel[3] as f32,
+        ) / 255.0;
+        let alpha = color.w.clamp(0.0, 1.0);
+        let blended = color * alpha + destination * (1.0 - alpha);
+        data.color.set_pixel(x, row, to_rgba8(blended));
+    }
+}
+
+fn to_rgba8(color: Vec4) -> [u8; 4] {
+    (color.clamp(Vec4::ZERO, Vec4::ONE) * 255.0)
+        .round()
+        .to_array()
+        .map(|channel| channel as u8)
+}
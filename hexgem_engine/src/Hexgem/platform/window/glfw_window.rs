@@ -6,6 +6,7 @@ use crate::{
         core::{Position, Size},
         window::Window,
     },
+    HexgemError,
     HexgemEvent::{
         Event, Key, KeyboardEvent, Modifiers, MouseButton, MouseButtonEvent, MouseMoveEvent,
         MouseScrollEvent, NoneEvent, WindowCloseEvent, WindowFocusEvent, WindowMoveEvent,
@@ -85,12 +86,10 @@ impl GlfwWindow {
 }
 
 impl Window for GlfwWindow {
-    fn create(
-        props: crate::Hexgem::window::WindowProps,
-    ) -> Box<(dyn crate::Hexgem::window::Window + 'static)> {
+    fn create(props: crate::Hexgem::window::WindowProps) -> Result<Box<dyn Window>, HexgemError> {
         let mut glfw =
             glfw::init(|err, description| error!("Error occured on glfw init - {}", description))
-                .expect("Could not init glfw!");
+                .map_err(|err| HexgemError::BackendInit(format!("glfw: {:?}", err)))?;
 
         let (mut window, events) = glfw
             .create_window(
@@ -99,7 +98,7 @@ impl Window for GlfwWindow {
                 props.title,
                 glfw::WindowMode::Windowed,
             )
-            .expect("Failed to create GLFW window.");
+            .ok_or_else(|| HexgemError::WindowCreation(String::from("glfw window")))?;
         window.make_current();
         gl::load_with(|s| glfw.get_proc_address_raw(s));
         window.set_all_polling(true);
@@ -111,7 +110,7 @@ impl Window for GlfwWindow {
         };
         os_window.set_vsync(true);
         info!("Created GLFW window");
-        Box::new(os_window)
+        Ok(Box::new(os_window))
     }
 
     fn is_vsync(&self) -> bool {
@@ -130,10 +129,6 @@ impl Window for GlfwWindow {
         self.window.swap_buffers();
         self.glfw.poll_events();
         let mut count = 0;
-        unsafe {
-            gl::ClearColor(0.13, 0.15, 0.18, 1.0);
-            gl::Clear(gl::COLOR_BUFFER_BIT);
-        }
         self.events.take().map(|events| {
             for (_, event) in glfw::flush_messages(&events) {
                 count += 1;
@@ -156,17 +151,19 @@ impl Window for GlfwWindow {
         self.vsync_on = enabled;
     }
 
-    fn get_mut(&mut self) -> Box<&mut dyn Window> {
-        Box::new(self)
+    fn is_cursor_locked(&self) -> bool {
+        self.window.get_cursor_mode() == glfw::CursorMode::Disabled
     }
-    #[cfg(not(target_os = "macos"))]
 
-    fn get_glfw(&self) -> &Glfw {
-        &self.glfw
+    fn set_cursor_locked(&mut self, locked: bool) {
+        self.window.set_cursor_mode(if locked {
+            glfw::CursorMode::Disabled
+        } else {
+            glfw::CursorMode::Normal
+        });
     }
 
-    #[cfg(not(target_os = "macos"))]
-    fn get_window(&mut self) -> &mut glfw::PWindow {
-        &mut self.window
+    fn get_mut(&mut self) -> Box<&mut dyn Window> {
+        Box::new(self)
     }
 }
@@ -0,0 +1,174 @@
+use std::collections::VecDeque;
+
+use log::info;
+
+use crate::{
+    Hexgem::{
+        core::Size,
+        error::HexgemResult,
+        platform::SoftwarePlatform::SoftwareSurface,
+        window::{Window, WindowProps},
+    },
+    HexgemEvent::{Event, NoneEvent, WindowCloseEvent, WindowResizeEvent},
+    HexgemRenderer::ImageData,
+};
+
+// Window without an OS window or GL context. Frames are rendered into its SoftwareSurface
+// and events only come from push_event, which makes it usable on machines without a GPU.
+pub struct HeadlessWindow {
+    vsync: bool,
+    cursor_locked: bool,
+    surface: SoftwareSurface,
+    events: VecDeque<Box<dyn Event>>,
+    frame: u64,
+    frame_limit: Option<u64>,
+}
+
+impl HeadlessWindow {
+    pub fn create_headless(props: WindowProps) -> HexgemResult<Self> {
+        let surface = SoftwareSurface::create(props.width, props.height)?;
+        info!(
+            "Created headless window {}x{} for {}",
+            props.width, props.height, props.title
+        );
+        Ok(Self {
+            vsync: false,
+            cursor_locked: false,
+            surface,
+            events: VecDeque::new(),
+            frame: 0,
+            frame_limit: None,
+        })
+    }
+
+    pub fn get_surface(&self) -> &SoftwareSurface {
+        &self.surface
+    }
+
+    // Requests a close after the given number of frames, so an application run ends on its own.
+    pub fn set_frame_limit(&mut self, frame_limit: Option<u64>) {
+        self.frame_limit = frame_limit;
+    }
+
+    pub fn get_frame(&self) -> u64 {
+        self.frame
+    }
+
+    // Queued events are delivered on the next on_update, in order.
+    pub fn push_event(&mut self, event: Box<dyn Event>) {
+        self.events.push_back(event);
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) -> HexgemResult<()> {
+        self.surface.resize(width, height)?;
+        self.push_event(Box::new(WindowResizeEvent::create(Size {
+            width: width as i32,
+            height: height as i32,
+        })));
+        Ok(())
+    }
+}
+
+impl Window for HeadlessWindow {
+    fn create(props: WindowProps) -> HexgemResult<Box<dyn Window>>
+    where
+        Self: Sized,
+    {
+        Ok(Box::new(Self::create_headless(props)?))
+    }
+
+    fn is_vsync(&self) -> bool {
+        self.vsync
+    }
+
+    fn get_width(&self) -> i32 {
+        self.surface.get_width() as i32
+    }
+
+    fn get_height(&self) -> i32 {
+        self.surface.get_height() as i32
+    }
+
+    fn get_mut(&mut self) -> Box<&mut dyn Window> {
+        Box::new(self)
+    }
+
+    fn on_update(&mut self, callback: &mut dyn FnMut(Box<dyn Event>, Box<&mut dyn Window>)) {
+        if self.events.is_empty() {
+            callback(Box::new(NoneEvent::create()), self.get_mut());
+        }
+        while let Some(event) = self.events.pop_front() {
+            callback(event, self.get_mut());
+        }
+        self.frame += 1;
+        if self.frame_limit == Some(self.frame) {
+            callback(Box::new(WindowCloseEvent::create()), self.get_mut());
+        }
+    }
+
+    fn set_vsync(&mut self, enabled: bool) {
+        self.vsync = enabled;
+    }
+
+    fn is_cursor_locked(&self) -> bool {
+        self.cursor_locked
+    }
+
+    fn set_cursor_locked(&mut self, locked: bool) {
+        self.cursor_locked = locked;
+    }
+
+    fn capture(&self) -> HexgemResult<ImageData> {
+        Ok(self.surface.get_image())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HexgemEvent::EventType;
+
+    fn run_frame(window: &mut HeadlessWindow) -> Vec<EventType> {
+        let mut events = vec![];
+        window.on_update(&mut |event, _| events.push(event.get_event_type()));
+        events
+    }
+
+    #[test]
+    fn pushed_events_are_delivered_in_order() {
+        let mut window = HeadlessWindow::create_headless(WindowProps::default()).unwrap();
+        assert_eq!(run_frame(&mut window), [EventType::None]);
+        window.push_event(Box::new(WindowCloseEvent::create()));
+        window.resize(64, 32).unwrap();
+        assert_eq!(
+            run_frame(&mut window),
+            [EventType::WindowClose, EventType::WindowResize]
+        );
+        assert_eq!((window.get_width(), window.get_height()), (64, 32));
+        assert_eq!(window.get_frame(), 2);
+    }
+
+    #[test]
+    fn zero_sized_resizes_keep_the_surface() {
+        let mut window = HeadlessWindow::create_headless(WindowProps::default()).unwrap();
+        window.resize(16, 8).unwrap();
+        window.resize(0, 0).unwrap();
+        assert_eq!((window.get_width(), window.get_height()), (16, 8));
+        assert_eq!(window.capture().unwrap().get_width(), 16);
+        assert_eq!(
+            run_frame(&mut window),
+            [EventType::WindowResize, EventType::WindowResize]
+        );
+    }
+
+    #[test]
+    fn frame_limit_closes_the_window() {
+        let mut window = HeadlessWindow::create_headless(WindowProps::default()).unwrap();
+        window.set_frame_limit(Some(2));
+        assert_eq!(run_frame(&mut window), [EventType::None]);
+        assert_eq!(
+            run_frame(&mut window),
+            [EventType::None, EventType::WindowClose]
+        );
+    }
+}
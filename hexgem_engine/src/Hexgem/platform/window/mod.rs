@@ -1,8 +1,10 @@
 mod glfw_window;
+mod headless_window;
 mod sdl_window;
 mod window_types;
 pub mod HexgemWindow {
 
     pub use super::glfw_window::GlfwWindow;
+    pub use super::headless_window::HeadlessWindow;
     pub use super::sdl_window::SdlWindow;
 }
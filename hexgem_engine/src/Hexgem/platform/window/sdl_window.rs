@@ -1,8 +1,8 @@
 use log::{error, info};
-use sdl2::pixels::Color;
 
 use crate::{
     Hexgem::core::{Position, Size},
+    HexgemError,
     HexgemEvent::{
         Event, Key, KeyboardEvent, Modifiers, MouseButton, MouseButtonEvent, MouseMoveEvent,
         MouseScrollEvent, NoneEvent, WindowCloseEvent, WindowFocusEvent, WindowMoveEvent,
@@ -14,12 +14,19 @@ use crate::{
 pub struct SdlWindow {
     video_subsystem: sdl2::VideoSubsystem,
     context: sdl2::Sdl,
-    canvas: sdl2::render::Canvas<sdl2::video::Window>,
+    window: sdl2::video::Window,
+    // Only held so the context lives as long as the window.
+    _gl_context: sdl2::video::GLContext,
     event_pump: Option<sdl2::EventPump>,
     vsync: bool,
 }
 
 impl SdlWindow {
+    // In pixels, which differs from the window size on high DPI displays.
+    fn output_size(&self) -> (u32, u32) {
+        self.window.drawable_size()
+    }
+
     fn get_event(event: Option<sdl2::event::Event>) -> Box<dyn Event> {
         let none = Box::new(NoneEvent::create());
         match event {
@@ -71,12 +78,18 @@ impl SdlWindow {
                     repeat,
                     Modifiers::from(keymod),
                 )),
-                sdl2::event::Event::MouseMotion { x, y, .. } => {
-                    Box::new(MouseMoveEvent::create(Position {
+                sdl2::event::Event::MouseMotion {
+                    x, y, xrel, yrel, ..
+                } => Box::new(
+                    MouseMoveEvent::create(Position {
                         x: x as f64,
                         y: y as f64,
-                    }))
-                }
+                    })
+                    .with_delta(Position {
+                        x: xrel as f64,
+                        y: yrel as f64,
+                    }),
+                ),
                 sdl2::event::Event::MouseButtonDown {
                     mouse_btn, clicks, ..
                 } => Box::new(MouseButtonEvent::create(
@@ -104,41 +117,35 @@ impl SdlWindow {
 }
 
 impl Window for SdlWindow {
-    fn create(props: crate::Hexgem::window::WindowProps) -> Box<dyn Window>
+    fn create(props: crate::Hexgem::window::WindowProps) -> Result<Box<dyn Window>, HexgemError>
     where
         Self: Sized,
     {
-        let context = sdl2::init().expect("Error occured on sdl2 init!");
+        let context = sdl2::init().map_err(HexgemError::BackendInit)?;
 
-        let video_subsystem = context.video().expect("Cannot create video subsystem");
+        let video_subsystem = context.video().map_err(HexgemError::BackendInit)?;
         let window = video_subsystem
             .window(props.title, props.width, props.height)
             .position_centered()
             .build()
-            .expect("Cannot create sdl window");
-        let gl_context = window
-            .gl_create_context()
-            .expect("Cannot create gl context");
+            .map_err(|err| HexgemError::WindowCreation(err.to_string()))?;
+        let gl_context = window.gl_create_context().map_err(HexgemError::GlContext)?;
         window
             .gl_make_current(&gl_context)
-            .expect("Cannot make glContext current");
-        let mut canvas = window.into_canvas().build().unwrap();
-        let event_pump = Some(
-            context
-                .event_pump()
-                .expect("Could not create event_pump for sdl"),
-        );
-        canvas.present();
+            .map_err(HexgemError::GlContext)?;
+        gl::load_with(|s| video_subsystem.gl_get_proc_address(s) as *const _);
+        let event_pump = Some(context.event_pump().map_err(HexgemError::BackendInit)?);
         let mut sdl_window = Self {
             context,
             video_subsystem,
-            canvas,
+            window,
+            _gl_context: gl_context,
             event_pump,
             vsync: true,
         };
         sdl_window.set_vsync(true);
         info!("Created sdl window");
-        Box::new(sdl_window)
+        Ok(Box::new(sdl_window))
     }
 
     fn is_vsync(&self) -> bool {
@@ -146,17 +153,11 @@ impl Window for SdlWindow {
     }
 
     fn get_width(&self) -> i32 {
-        match self.canvas.output_size() {
-            Ok((w, _)) => w as i32,
-            Err(_) => panic!("Could not get size of output"),
-        }
+        self.output_size().0 as i32
     }
 
     fn get_height(&self) -> i32 {
-        match self.canvas.output_size() {
-            Ok((_, h)) => h as i32,
-            Err(_) => panic!("Could not get size of output"),
-        }
+        self.output_size().1 as i32
     }
 
     fn get_mut(&mut self) -> Box<&mut dyn Window> {
@@ -164,8 +165,6 @@ impl Window for SdlWindow {
     }
 
     fn on_update(&mut self, callback: &mut dyn FnMut(Box<dyn Event>, Box<&mut dyn Window>)) {
-        self.canvas.set_draw_color(Color::RGB(35, 39, 45));
-        self.canvas.clear();
         self.event_pump.take().map(|mut event_pump| {
             let iter = event_pump.poll_iter();
             let mut count = 0;
@@ -179,7 +178,15 @@ impl Window for SdlWindow {
             self.event_pump = Some(event_pump);
         });
 
-        self.canvas.present();
+        self.window.gl_swap_window();
+    }
+
+    fn is_cursor_locked(&self) -> bool {
+        self.context.mouse().relative_mouse_mode()
+    }
+
+    fn set_cursor_locked(&mut self, locked: bool) {
+        self.context.mouse().set_relative_mouse_mode(locked);
     }
 
     fn set_vsync(&mut self, enabled: bool) {
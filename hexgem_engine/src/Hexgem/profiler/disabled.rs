@@ -0,0 +1,24 @@
+use std::borrow::Cow;
+
+// Stand-ins for builds without the profiling feature. There is never a session, they only keep
+// code that looks at the result of profile_end_session! compiling in both builds.
+pub struct ProfileResult {
+    pub name: Cow<'static, str>,
+    pub start: u128,
+    pub duration: u128,
+    pub thread_id: u64,
+}
+
+pub struct ProfileSession {
+    pub name: String,
+}
+
+impl ProfileSession {
+    pub fn results(&self) -> &[ProfileResult] {
+        &[]
+    }
+
+    pub fn to_chrome_trace(&self) -> String {
+        String::from("{\"otherData\":{},\"traceEvents\":[]}")
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    borrow::Cow,
+    fmt::Write,
+    fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Instant,
+};
+
+use log::{error, info};
+
+use crate::Hexgem::log::push_json_string;
+
+static SESSION: Mutex<Option<ProfileSession>> = Mutex::new(None);
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static THREAD_ID: u64 = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+}
+
+pub struct ProfileResult {
+    pub name: Cow<'static, str>,
+    pub start: u128,
+    pub duration: u128,
+    pub thread_id: u64,
+}
+
+pub struct ProfileSession {
+    pub name: String,
+    path: PathBuf,
+    origin: Instant,
+    results: Vec<ProfileResult>,
+}
+
+impl ProfileSession {
+    pub fn results(&self) -> &[ProfileResult] {
+        &self.results
+    }
+
+    pub fn to_chrome_trace(&self) -> String {
+        let mut json = String::from("{\"otherData\":{},\"traceEvents\":[");
+        for (i, result) in self.results.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            json.push_str("{\"cat\":\"function\",\"name\":");
+            push_json_string(&mut json, &result.name);
+            let _ = write!(
+                json,
+                ",\"ph\":\"X\",\"pid\":0,\"tid\":{},\"ts\":{},\"dur\":{}}}",
+                result.thread_id, result.start, result.duration
+            );
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+pub struct Profiler;
+
+impl Profiler {
+    pub fn begin_session<P: Into<PathBuf>>(name: &str, path: P) {
+        let mut session = match SESSION.lock() {
+            Ok(session) => session,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Some(current) = session.as_ref() {
+            error!(
+                "Profiler session {} started while {} is still running",
+                name, current.name
+            );
+        }
+        *session = Some(ProfileSession {
+            name: name.to_string(),
+            path: path.into(),
+            origin: Instant::now(),
+            results: vec![],
+        });
+    }
+
+    pub fn end_session() -> Option<ProfileSession> {
+        let session = match SESSION.lock() {
+            Ok(mut session) => session.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        }?;
+        match fs::write(&session.path, session.to_chrome_trace()) {
+            Ok(_) => info!(
+                "Profiler session {} written to {}",
+                session.name,
+                session.path.display()
+            ),
+            Err(err) => error!(
+                "Could not write profiler session to {} - {}",
+                session.path.display(),
+                err
+            ),
+        }
+        Some(session)
+    }
+
+    pub fn is_running() -> bool {
+        SESSION.lock().map_or(false, |session| session.is_some())
+    }
+
+    fn record(name: Cow<'static, str>, start: Instant, end: Instant) {
+        if let Ok(mut session) = SESSION.lock() {
+            if let Some(session) = session.as_mut() {
+                let result = ProfileResult {
+                    name,
+                    start: start.saturating_duration_since(session.origin).as_micros(),
+                    duration: end.duration_since(start).as_micros(),
+                    thread_id: THREAD_ID.with(|id| *id),
+                };
+                session.results.push(result);
+            }
+        }
+    }
+}
+
+pub struct ProfileTimer {
+    name: Option<Cow<'static, str>>,
+    start: Instant,
+}
+
+impl ProfileTimer {
+    pub fn start<N: Into<Cow<'static, str>>>(name: N) -> Self {
+        Self {
+            name: Some(name.into()),
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for ProfileTimer {
+    fn drop(&mut self) {
+        let end = Instant::now();
+        if let Some(name) = self.name.take() {
+            Profiler::record(name, self.start, end);
+        }
+    }
+}
+
+pub fn function_name<T>(_: T) -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.strip_suffix("::__hexgem_profile_marker")
+        .unwrap_or(name)
+}
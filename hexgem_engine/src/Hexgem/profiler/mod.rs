@@ -0,0 +1,98 @@
+#[cfg(feature = "profiling")]
+mod instrumentor;
+#[cfg(feature = "profiling")]
+pub use instrumentor::*;
+#[cfg(not(feature = "profiling"))]
+mod disabled;
+#[cfg(not(feature = "profiling"))]
+pub use disabled::*;
+
+#[cfg(feature = "profiling")]
+#[macro_export]
+macro_rules! profile_begin_session {
+    ($name:expr, $path:expr) => {
+        $crate::Profiler::begin_session($name, $path)
+    };
+}
+
+#[cfg(not(feature = "profiling"))]
+#[macro_export]
+macro_rules! profile_begin_session {
+    ($name:expr, $path:expr) => {};
+}
+
+#[cfg(feature = "profiling")]
+#[macro_export]
+macro_rules! profile_end_session {
+    () => {
+        $crate::Profiler::end_session()
+    };
+}
+
+#[cfg(not(feature = "profiling"))]
+#[macro_export]
+macro_rules! profile_end_session {
+    () => {
+        None::<$crate::ProfileSession>
+    };
+}
+
+#[cfg(feature = "profiling")]
+#[macro_export]
+macro_rules! profile_scope {
+    ($name:expr) => {
+        let _hexgem_profile_timer = $crate::ProfileTimer::start($name);
+    };
+}
+
+#[cfg(not(feature = "profiling"))]
+#[macro_export]
+macro_rules! profile_scope {
+    ($name:expr) => {};
+}
+
+#[cfg(feature = "profiling")]
+#[macro_export]
+macro_rules! profile_function {
+    () => {
+        fn __hexgem_profile_marker() {}
+        let name = $crate::function_name(__hexgem_profile_marker);
+        $crate::profile_scope!(name);
+    };
+}
+
+#[cfg(not(feature = "profiling"))]
+#[macro_export]
+macro_rules! profile_function {
+    () => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn end_session_has_the_same_type_in_both_builds() {
+        let path = std::env::temp_dir().join(format!("hexgem_profile_{}.json", std::process::id()));
+        crate::profile_begin_session!("test", path.clone());
+        {
+            crate::profile_scope!("scope");
+        }
+        let session: Option<ProfileSession> = crate::profile_end_session!();
+        if cfg!(feature = "profiling") {
+            let session = session.unwrap();
+            // Other tests may record into the session too, it is process wide.
+            assert!(session
+                .results()
+                .iter()
+                .any(|result| result.name == "scope"));
+            assert_eq!(
+                std::fs::read_to_string(&path).unwrap(),
+                session.to_chrome_trace()
+            );
+            std::fs::remove_file(&path).unwrap();
+        } else {
+            assert!(session.is_none());
+        }
+    }
+}
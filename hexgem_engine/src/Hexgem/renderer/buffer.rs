@@ -0,0 +1,246 @@
+use std::{mem, slice};
+
+use crate::Hexgem::core::ToAny;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShaderDataType {
+    Float,
+    Float2,
+    Float3,
+    Float4,
+    Mat3,
+    Mat4,
+    Int,
+    Int2,
+    Int3,
+    Int4,
+    Bool,
+}
+
+impl ShaderDataType {
+    pub fn size(&self) -> u32 {
+        match self {
+            ShaderDataType::Float | ShaderDataType::Int => 4,
+            ShaderDataType::Float2 | ShaderDataType::Int2 => 4 * 2,
+            ShaderDataType::Float3 | ShaderDataType::Int3 => 4 * 3,
+            ShaderDataType::Float4 | ShaderDataType::Int4 => 4 * 4,
+            ShaderDataType::Mat3 => 4 * 3 * 3,
+            ShaderDataType::Mat4 => 4 * 4 * 4,
+            ShaderDataType::Bool => 1,
+        }
+    }
+
+    pub fn component_count(&self) -> u32 {
+        match self {
+            ShaderDataType::Float | ShaderDataType::Int | ShaderDataType::Bool => 1,
+            ShaderDataType::Float2 | ShaderDataType::Int2 => 2,
+            ShaderDataType::Float3 | ShaderDataType::Int3 => 3,
+            ShaderDataType::Float4 | ShaderDataType::Int4 => 4,
+            ShaderDataType::Mat3 => 3,
+            ShaderDataType::Mat4 => 4,
+        }
+    }
+
+    // Matrices take one attribute slot per column.
+    pub fn attribute_count(&self) -> u32 {
+        match self {
+            ShaderDataType::Mat3 => 3,
+            ShaderDataType::Mat4 => 4,
+            _ => 1,
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            ShaderDataType::Int
+                | ShaderDataType::Int2
+                | ShaderDataType::Int3
+                | ShaderDataType::Int4
+                | ShaderDataType::Bool
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BufferElement {
+    pub name: String,
+    pub data_type: ShaderDataType,
+    pub size: u32,
+    pub offset: u32,
+    pub normalized: bool,
+}
+
+impl BufferElement {
+    pub fn create(data_type: ShaderDataType, name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            data_type,
+            size: data_type.size(),
+            offset: 0,
+            normalized: false,
+        }
+    }
+
+    pub fn normalized(mut self) -> Self {
+        self.normalized = true;
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VertexAttribute {
+    pub data_type: ShaderDataType,
+    pub components: u32,
+    pub normalized: bool,
+    pub stride: u32,
+    pub offset: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct BufferLayout {
+    elements: Vec<BufferElement>,
+    stride: u32,
+}
+
+impl BufferLayout {
+    pub fn create(mut elements: Vec<BufferElement>) -> Self {
+        let mut offset = 0;
+        for element in elements.iter_mut() {
+            element.offset = offset;
+            offset += element.size;
+        }
+        Self {
+            elements,
+            stride: offset,
+        }
+    }
+
+    pub fn get_elements(&self) -> &[BufferElement] {
+        &self.elements
+    }
+
+    pub fn get_stride(&self) -> u32 {
+        self.stride
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    pub fn attributes(&self) -> Vec<VertexAttribute> {
+        let mut attributes = vec![];
+        for element in self.elements.iter() {
+            let data_type = element.data_type;
+            let columns = data_type.attribute_count();
+            let column_size = data_type.size() / columns;
+            for column in 0..columns {
+                attributes.push(VertexAttribute {
+                    data_type,
+                    components: data_type.component_count(),
+                    normalized: element.normalized,
+                    stride: self.stride,
+                    offset: element.offset + column_size * column,
+                });
+            }
+        }
+        attributes
+    }
+}
+
+pub trait VertexBuffer: ToAny {
+    fn bind(&self);
+    fn unbind(&self);
+    fn set_data(&mut self, data: &[u8]);
+    fn get_layout(&self) -> &BufferLayout;
+    fn set_layout(&mut self, layout: BufferLayout);
+}
+
+pub trait IndexBuffer: ToAny {
+    fn bind(&self);
+    fn unbind(&self);
+    fn get_count(&self) -> u32;
+}
+
+/// Plain data that can be uploaded to a vertex buffer byte for byte.
+///
+/// # Safety
+///
+/// Implementors must be `#[repr(C)]` (or a primitive) with no padding bytes and no pointers,
+/// every byte of a value has to be initialized.
+pub unsafe trait VertexData: Copy + 'static {}
+
+unsafe impl VertexData for u8 {}
+unsafe impl VertexData for u16 {}
+unsafe impl VertexData for u32 {}
+unsafe impl VertexData for i32 {}
+unsafe impl VertexData for f32 {}
+unsafe impl<T: VertexData, const N: usize> VertexData for [T; N] {}
+
+pub fn as_bytes<T: VertexData>(data: &[T]) -> &[u8] {
+    // VertexData guarantees there are no uninitialized padding bytes to read.
+    unsafe { slice::from_raw_parts(data.as_ptr() as *const u8, mem::size_of_val(data)) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn as_bytes_is_little_endian_and_sized() {
+        let data = [1u32, 0x0403_0201];
+        assert_eq!(as_bytes(&data), &[1, 0, 0, 0, 1, 2, 3, 4]);
+        let arrays = [[1.0f32, 2.0], [3.0, 4.0]];
+        assert_eq!(as_bytes(&arrays).len(), 16);
+        assert!(as_bytes::<f32>(&[]).is_empty());
+    }
+
+    #[test]
+    fn data_type_sizes() {
+        assert_eq!(ShaderDataType::Bool.size(), 1);
+        assert_eq!(ShaderDataType::Int.size(), 4);
+        assert_eq!(ShaderDataType::Int3.size(), 12);
+        assert_eq!(ShaderDataType::Float4.size(), 16);
+        assert_eq!(ShaderDataType::Mat3.size(), 36);
+        assert_eq!(ShaderDataType::Mat4.size(), 64);
+        assert_eq!(ShaderDataType::Mat3.component_count(), 3);
+        assert!(ShaderDataType::Bool.is_integer());
+        assert!(!ShaderDataType::Float.is_integer());
+    }
+
+    #[test]
+    fn layout_offsets_and_stride() {
+        let layout = BufferLayout::create(vec![
+            BufferElement::create(ShaderDataType::Float3, "a_Position"),
+            BufferElement::create(ShaderDataType::Float4, "a_Color").normalized(),
+            BufferElement::create(ShaderDataType::Bool, "a_Flag"),
+            BufferElement::create(ShaderDataType::Int, "a_EntityId"),
+        ]);
+        let offsets: Vec<_> = layout.get_elements().iter().map(|e| e.offset).collect();
+        assert_eq!(offsets, [0, 12, 28, 29]);
+        assert_eq!(layout.get_stride(), 33);
+        assert!(layout.get_elements()[1].normalized);
+        assert!(!layout.is_empty());
+        assert!(BufferLayout::default().is_empty());
+        assert_eq!(BufferLayout::create(vec![]).get_stride(), 0);
+    }
+
+    #[test]
+    fn matrices_expand_to_one_attribute_per_column() {
+        let layout = BufferLayout::create(vec![
+            BufferElement::create(ShaderDataType::Float, "a_Scale"),
+            BufferElement::create(ShaderDataType::Mat4, "a_Transform"),
+            BufferElement::create(ShaderDataType::Mat3, "a_Normal"),
+            BufferElement::create(ShaderDataType::Int2, "a_Ids"),
+        ]);
+        assert_eq!(layout.get_stride(), 4 + 64 + 36 + 8);
+        let attributes = layout.attributes();
+        assert_eq!(attributes.len(), 1 + 4 + 3 + 1);
+        let offsets: Vec<_> = attributes.iter().map(|a| a.offset).collect();
+        assert_eq!(offsets, [0, 4, 20, 36, 52, 68, 80, 92, 104]);
+        let components: Vec<_> = attributes.iter().map(|a| a.components).collect();
+        assert_eq!(components, [1, 4, 4, 4, 4, 3, 3, 3, 2]);
+        assert!(attributes.iter().all(|a| a.stride == layout.get_stride()));
+        assert_eq!(attributes[1].data_type, ShaderDataType::Mat4);
+    }
+}
@@ -0,0 +1,11 @@
+use glam::Mat4;
+
+pub trait Camera {
+    fn get_view_projection(&self) -> Mat4;
+}
+
+impl Camera for Mat4 {
+    fn get_view_projection(&self) -> Mat4 {
+        *self
+    }
+}
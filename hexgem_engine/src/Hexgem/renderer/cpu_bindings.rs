@@ -0,0 +1,80 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use glam::{Vec2, Vec4};
+
+use super::{
+    cpu_framebuffer::CpuRenderTarget, image_data::ImageData, shader::UniformValue,
+    texture::TextureOptions,
+};
+
+#[derive(Clone)]
+pub(crate) struct BoundShader {
+    pub name: String,
+    pub uniforms: Rc<RefCell<HashMap<String, UniformValue>>>,
+}
+
+#[derive(Clone)]
+pub(crate) struct BoundTexture {
+    pub levels: Rc<RefCell<Vec<ImageData>>>,
+    pub options: TextureOptions,
+}
+
+impl BoundTexture {
+    pub fn sample(&self, uv: Vec2) -> Vec4 {
+        self.levels.borrow()[0].sample(
+            uv,
+            self.options.mag_filter,
+            (self.options.wrap_s, self.options.wrap_t),
+        )
+    }
+}
+
+#[derive(Default)]
+struct BindingState {
+    shader: Option<BoundShader>,
+    textures: HashMap<u32, BoundTexture>,
+    framebuffer: Option<CpuRenderTarget>,
+    viewport: (i32, i32, u32, u32),
+}
+
+// Tracks what CPU resources are bound, so a software backend can read them when drawing.
+#[derive(Clone, Default)]
+pub(crate) struct CpuBindings {
+    state: Rc<RefCell<BindingState>>,
+}
+
+impl CpuBindings {
+    pub fn bind_shader(&self, shader: BoundShader) {
+        self.state.borrow_mut().shader = Some(shader);
+    }
+
+    pub fn bind_texture(&self, slot: u32, texture: BoundTexture) {
+        self.state.borrow_mut().textures.insert(slot, texture);
+    }
+
+    pub fn get_shader(&self) -> Option<BoundShader> {
+        self.state.borrow().shader.clone()
+    }
+
+    pub fn get_texture(&self, slot: u32) -> Option<BoundTexture> {
+        self.state.borrow().textures.get(&slot).cloned()
+    }
+
+    // None draws to the default target again.
+    pub fn bind_framebuffer(&self, framebuffer: Option<CpuRenderTarget>) {
+        self.state.borrow_mut().framebuffer = framebuffer;
+    }
+
+    pub fn get_framebuffer(&self) -> Option<CpuRenderTarget> {
+        self.state.borrow().framebuffer.clone()
+    }
+
+    // Kept here rather than in the backend because binding a framebuffer resets it.
+    pub fn set_viewport(&self, viewport: (i32, i32, u32, u32)) {
+        self.state.borrow_mut().viewport = viewport;
+    }
+
+    pub fn get_viewport(&self) -> (i32, i32, u32, u32) {
+        self.state.borrow().viewport
+    }
+}
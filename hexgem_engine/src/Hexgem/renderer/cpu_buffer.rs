@@ -0,0 +1,314 @@
+use crate::{
+    toAnyImpl,
+    Hexgem::error::{HexgemError, HexgemResult},
+};
+
+use super::{
+    buffer::{BufferLayout, IndexBuffer, VertexBuffer},
+    null_renderer_api::{CallRecorder, RenderCall},
+    vertex_array::VertexArray,
+};
+
+pub struct CpuVertexBuffer {
+    id: u32,
+    data: Vec<u8>,
+    layout: BufferLayout,
+    recorder: Option<CallRecorder>,
+}
+
+impl CpuVertexBuffer {
+    pub(crate) fn create(size: usize, data: Option<&[u8]>, recorder: Option<CallRecorder>) -> Self {
+        let id = recorder.as_ref().map_or(0, |recorder| recorder.next_id());
+        let mut buffer = vec![0; size];
+        if let Some(data) = data {
+            let len = data.len().min(size);
+            buffer[..len].copy_from_slice(&data[..len]);
+        }
+        if let Some(recorder) = recorder.as_ref() {
+            recorder.record(RenderCall::CreateVertexBuffer { id, size });
+            if let Some(data) = data {
+                recorder.record(RenderCall::SetVertexBufferData {
+                    id,
+                    data: data.to_vec(),
+                });
+            }
+        }
+        Self {
+            id,
+            data: buffer,
+            layout: BufferLayout::default(),
+            recorder,
+        }
+    }
+
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+toAnyImpl!(CpuVertexBuffer);
+
+impl VertexBuffer for CpuVertexBuffer {
+    fn bind(&self) {}
+
+    fn unbind(&self) {}
+
+    fn set_data(&mut self, data: &[u8]) {
+        if data.len() > self.data.len() {
+            self.data.resize(data.len(), 0);
+        }
+        self.data[..data.len()].copy_from_slice(data);
+        if let Some(recorder) = self.recorder.as_ref() {
+            recorder.record(RenderCall::SetVertexBufferData {
+                id: self.id,
+                data: data.to_vec(),
+            });
+        }
+    }
+
+    fn get_layout(&self) -> &BufferLayout {
+        &self.layout
+    }
+
+    fn set_layout(&mut self, layout: BufferLayout) {
+        self.layout = layout;
+    }
+}
+
+pub struct CpuIndexBuffer {
+    id: u32,
+    indices: Vec<u32>,
+}
+
+impl CpuIndexBuffer {
+    pub(crate) fn create(indices: &[u32], recorder: Option<CallRecorder>) -> Self {
+        let id = recorder.as_ref().map_or(0, |recorder| recorder.next_id());
+        if let Some(recorder) = recorder.as_ref() {
+            recorder.record(RenderCall::CreateIndexBuffer {
+                id,
+                count: indices.len() as u32,
+            });
+        }
+        Self {
+            id,
+            indices: indices.to_vec(),
+        }
+    }
+
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn get_indices(&self) -> &[u32] {
+        &self.indices
+    }
+}
+
+toAnyImpl!(CpuIndexBuffer);
+
+impl IndexBuffer for CpuIndexBuffer {
+    fn bind(&self) {}
+
+    fn unbind(&self) {}
+
+    fn get_count(&self) -> u32 {
+        self.indices.len() as u32
+    }
+}
+
+pub struct CpuVertexArray {
+    id: u32,
+    attribute_index: u32,
+    vertex_buffers: Vec<Box<dyn VertexBuffer>>,
+    index_buffer: Option<Box<dyn IndexBuffer>>,
+    recorder: Option<CallRecorder>,
+}
+
+impl CpuVertexArray {
+    pub(crate) fn create(recorder: Option<CallRecorder>) -> Self {
+        let id = recorder.as_ref().map_or(0, |recorder| recorder.next_id());
+        if let Some(recorder) = recorder.as_ref() {
+            recorder.record(RenderCall::CreateVertexArray { id });
+        }
+        Self {
+            id,
+            attribute_index: 0,
+            vertex_buffers: vec![],
+            index_buffer: None,
+            recorder,
+        }
+    }
+
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+}
+
+toAnyImpl!(CpuVertexArray);
+
+impl VertexArray for CpuVertexArray {
+    fn bind(&self) {
+        if let Some(recorder) = self.recorder.as_ref() {
+            recorder.record(RenderCall::BindVertexArray { id: self.id });
+        }
+    }
+
+    fn unbind(&self) {}
+
+    fn add_vertex_buffer(&mut self, vertex_buffer: Box<dyn VertexBuffer>) -> HexgemResult<()> {
+        if vertex_buffer.get_layout().is_empty() {
+            return Err(HexgemError::Renderer(
+                "Vertex buffer has no layout".to_string(),
+            ));
+        }
+        let buffer_id = vertex_buffer
+            .as_any()
+            .downcast_ref::<CpuVertexBuffer>()
+            .map_or(0, |buffer| buffer.get_id());
+        for attribute in vertex_buffer.get_layout().attributes() {
+            if let Some(recorder) = self.recorder.as_ref() {
+                recorder.record(RenderCall::VertexAttribute {
+                    vertex_array: self.id,
+                    vertex_buffer: buffer_id,
+                    index: self.attribute_index,
+                    components: attribute.components,
+                    integer: attribute.data_type.is_integer(),
+                    normalized: attribute.normalized,
+                    stride: attribute.stride,
+                    offset: attribute.offset,
+                });
+            }
+            self.attribute_index += 1;
+        }
+        self.vertex_buffers.push(vertex_buffer);
+        Ok(())
+    }
+
+    fn set_index_buffer(&mut self, index_buffer: Box<dyn IndexBuffer>) {
+        if let Some(recorder) = self.recorder.as_ref() {
+            let index_buffer = index_buffer
+                .as_any()
+                .downcast_ref::<CpuIndexBuffer>()
+                .map_or(0, |buffer| buffer.get_id());
+            recorder.record(RenderCall::SetIndexBuffer {
+                vertex_array: self.id,
+                index_buffer,
+            });
+        }
+        self.index_buffer = Some(index_buffer);
+    }
+
+    fn get_vertex_buffers(&self) -> &[Box<dyn VertexBuffer>] {
+        &self.vertex_buffers
+    }
+
+    fn get_vertex_buffers_mut(&mut self) -> &mut [Box<dyn VertexBuffer>] {
+        &mut self.vertex_buffers
+    }
+
+    fn get_index_buffer(&self) -> Option<&dyn IndexBuffer> {
+        self.index_buffer.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hexgem::renderer::{
+        buffer::{BufferElement, ShaderDataType},
+        null_renderer_api::RecordingRendererApi,
+        renderer_api::RendererApi,
+    };
+
+    fn layout(elements: &[(ShaderDataType, &str)]) -> BufferLayout {
+        BufferLayout::create(
+            elements
+                .iter()
+                .map(|(data_type, name)| BufferElement::create(*data_type, name))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn vertex_array_records_attributes_and_index_buffer() {
+        let mut api = RecordingRendererApi::create();
+        let mut vertex_array = api.create_vertex_array();
+        let mut positions = api.create_vertex_buffer(8, Some(&[1, 2, 3]));
+        positions.set_layout(layout(&[(ShaderDataType::Float2, "a_Position")]));
+        let mut instances = api.create_vertex_buffer(68, None);
+        instances.set_layout(layout(&[
+            (ShaderDataType::Mat4, "a_Transform"),
+            (ShaderDataType::Int, "a_EntityId"),
+        ]));
+        vertex_array.add_vertex_buffer(positions).unwrap();
+        vertex_array.add_vertex_buffer(instances).unwrap();
+        let index_buffer = api.create_index_buffer(&[0, 1, 2]);
+        vertex_array.set_index_buffer(index_buffer);
+
+        let attribute = |vertex_buffer, index, components, integer, stride, offset| {
+            RenderCall::VertexAttribute {
+                vertex_array: 1,
+                vertex_buffer,
+                index,
+                components,
+                integer,
+                normalized: false,
+                stride,
+                offset,
+            }
+        };
+        assert_eq!(
+            api.calls(),
+            vec![
+                RenderCall::CreateVertexArray { id: 1 },
+                RenderCall::CreateVertexBuffer { id: 2, size: 8 },
+                RenderCall::SetVertexBufferData {
+                    id: 2,
+                    data: vec![1, 2, 3],
+                },
+                RenderCall::CreateVertexBuffer { id: 3, size: 68 },
+                attribute(2, 0, 2, false, 8, 0),
+                attribute(3, 1, 4, false, 68, 0),
+                attribute(3, 2, 4, false, 68, 16),
+                attribute(3, 3, 4, false, 68, 32),
+                attribute(3, 4, 4, false, 68, 48),
+                attribute(3, 5, 1, true, 68, 64),
+                RenderCall::CreateIndexBuffer { id: 4, count: 3 },
+                RenderCall::SetIndexBuffer {
+                    vertex_array: 1,
+                    index_buffer: 4,
+                },
+            ]
+        );
+        assert_eq!(vertex_array.get_vertex_buffers().len(), 2);
+        assert_eq!(vertex_array.get_index_buffer().unwrap().get_count(), 3);
+    }
+
+    #[test]
+    fn buffers_without_a_layout_are_rejected() {
+        let mut api = RecordingRendererApi::create();
+        let mut vertex_array = api.create_vertex_array();
+        let buffer = api.create_vertex_buffer(16, None);
+        assert!(vertex_array.add_vertex_buffer(buffer).is_err());
+        assert!(vertex_array.get_vertex_buffers().is_empty());
+        assert!(!api
+            .calls()
+            .iter()
+            .any(|call| matches!(call, RenderCall::VertexAttribute { .. })));
+    }
+
+    #[test]
+    fn vertex_buffer_data_grows_and_is_recorded() {
+        let mut buffer = CpuVertexBuffer::create(4, Some(&[9; 8]), None);
+        assert_eq!(buffer.get_data(), &[9; 4]);
+        buffer.set_data(&[1, 2, 3, 4, 5, 6]);
+        assert_eq!(buffer.get_data(), &[1, 2, 3, 4, 5, 6]);
+        buffer.set_data(&[7]);
+        assert_eq!(buffer.get_data(), &[7, 2, 3, 4, 5, 6]);
+        assert_eq!(buffer.get_id(), 0);
+    }
+}
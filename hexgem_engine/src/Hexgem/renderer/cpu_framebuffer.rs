@@ -0,0 +1,376 @@
+use std::{cell::RefCell, rc::Rc};
+
+use glam::Vec4;
+
+use crate::{toAnyImpl, Hexgem::error::HexgemResult};
+
+use super::{
+    cpu_bindings::CpuBindings,
+    framebuffer::{
+        AttachmentPixels, AttachmentValue, FramebufferHandle, FramebufferSpec,
+        FramebufferTextureFormat,
+    },
+    image_data::ImageData,
+    null_renderer_api::{CallRecorder, RenderCall},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+enum CpuAttachmentData {
+    Color(Vec<Vec4>),
+    Int(Vec<i32>),
+}
+
+impl CpuAttachmentData {
+    fn create(format: FramebufferTextureFormat, len: usize) -> Self {
+        if format.is_integer() {
+            return CpuAttachmentData::Int(vec![0; len]);
+        }
+        CpuAttachmentData::Color(vec![Vec4::ZERO; len])
+    }
+
+    fn fill(&mut self, value: AttachmentValue) {
+        match (self, value) {
+            (CpuAttachmentData::Color(data), AttachmentValue::Color(color)) => data.fill(color),
+            (CpuAttachmentData::Int(data), AttachmentValue::Int(int)) => data.fill(int),
+            _ => {}
+        }
+    }
+
+    fn set(&mut self, index: usize, value: AttachmentValue) {
+        match (self, value) {
+            (CpuAttachmentData::Color(data), AttachmentValue::Color(color)) => data[index] = color,
+            (CpuAttachmentData::Int(data), AttachmentValue::Int(int)) => data[index] = int,
+            _ => {}
+        }
+    }
+}
+
+// Sample data of pixel (x, y) lives at (y * width + x) * samples, rows bottom first.
+struct CpuAttachment {
+    format: FramebufferTextureFormat,
+    samples: CpuAttachmentData,
+    resolved: CpuAttachmentData,
+}
+
+// The storage of a bound CpuFramebuffer, shared so a software backend can draw into it.
+#[derive(Clone)]
+pub(crate) struct CpuRenderTarget {
+    width: u32,
+    height: u32,
+    samples: u32,
+    attachments: Rc<RefCell<Vec<CpuAttachment>>>,
+    depth: Rc<RefCell<Vec<f32>>>,
+}
+
+impl CpuRenderTarget {
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    // The first color attachment with samples averaged, rows top first like any other ImageData.
+    pub fn get_image(&self) -> HexgemResult<ImageData> {
+        let samples = self.samples as usize;
+        let mut image = ImageData::filled(self.width, self.height, [0, 0, 0, 0])?;
+        if let Some(CpuAttachmentData::Color(data)) = self
+            .attachments
+            .borrow()
+            .first()
+            .map(|attachment| &attachment.samples)
+        {
+            for (index, chunk) in data.chunks_exact(samples).enumerate() {
+                let color = chunk.iter().copied().sum::<Vec4>() / samples as f32;
+                let (x, y) = (index as u32 % self.width, index as u32 / self.width);
+                image.set_pixel(x, self.height - 1 - y, to_rgba8(color));
+            }
+        }
+        Ok(image)
+    }
+
+    // Clears color attachments and the depth buffer, integer attachments are left alone.
+    pub fn clear(&self, color: Vec4, depth: f32) {
+        for attachment in self.attachments.borrow_mut().iter_mut() {
+            if let CpuAttachmentData::Color(data) = &mut attachment.samples {
+                data.fill(color);
+            }
+        }
+        self.depth.borrow_mut().fill(depth);
+    }
+
+    // Same depth test and blending as SoftwareSurface::blend, written to every sample of the
+    // first color attachment.
+    pub fn blend(&self, x: u32, y: u32, depth: f32, color: Vec4) {
+        let samples = self.samples as usize;
+        let start = (y as usize * self.width as usize + x as usize) * samples;
+        let mut depths = self.depth.borrow_mut();
+        if let Some(stored) = depths.get_mut(start) {
+            if depth > *stored {
+                return;
+            }
+            depths[start..start + samples].fill(depth);
+        }
+        let mut attachments = self.attachments.borrow_mut();
+        let Some(CpuAttachmentData::Color(data)) = attachments
+            .first_mut()
+            .map(|attachment| &mut attachment.samples)
+        else {
+            return;
+        };
+        let alpha = color.w.clamp(0.0, 1.0);
+        for destination in &mut data[start..start + samples] {
+            *destination =
+                (color * alpha + *destination * (1.0 - alpha)).clamp(Vec4::ZERO, Vec4::ONE);
+        }
+    }
+}
+
+pub struct CpuFramebuffer {
+    id: u32,
+    spec: FramebufferSpec,
+    attachments: Rc<RefCell<Vec<CpuAttachment>>>,
+    depth: Rc<RefCell<Vec<f32>>>,
+    recorder: Option<CallRecorder>,
+    bindings: Option<CpuBindings>,
+}
+
+impl CpuFramebuffer {
+    pub(crate) fn create(spec: &FramebufferSpec, recorder: Option<CallRecorder>) -> Self {
+        let id = recorder.as_ref().map_or(0, |recorder| recorder.next_id());
+        if let Some(recorder) = recorder.as_ref() {
+            recorder.record(RenderCall::CreateFramebuffer {
+                id,
+                width: spec.width,
+                height: spec.height,
+                samples: spec.samples,
+            });
+        }
+        let framebuffer = Self {
+            id,
+            spec: spec.clone(),
+            attachments: Rc::new(RefCell::new(vec![])),
+            depth: Rc::new(RefCell::new(vec![])),
+            recorder,
+            bindings: None,
+        };
+        framebuffer.allocate();
+        framebuffer
+    }
+
+    // Binding makes the software renderer draw into this framebuffer instead of its surface.
+    pub(crate) fn with_bindings(mut self, bindings: CpuBindings) -> Self {
+        self.bindings = Some(bindings);
+        self
+    }
+
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn get_spec(&self) -> &FramebufferSpec {
+        &self.spec
+    }
+
+    // Writes every sample of a pixel, standing in for a draw call when testing without a GPU.
+    pub fn set_pixel(&self, attachment: usize, x: u32, y: u32, value: AttachmentValue) {
+        let samples = self.spec.samples as usize;
+        let start = self.pixel_index(x, y) * samples;
+        let mut attachments = self.attachments.borrow_mut();
+        for index in start..start + samples {
+            attachments[attachment].samples.set(index, value);
+        }
+    }
+
+    pub fn set_sample(
+        &self,
+        attachment: usize,
+        x: u32,
+        y: u32,
+        sample: u32,
+        value: AttachmentValue,
+    ) {
+        let index = self.pixel_index(x, y) * self.spec.samples as usize + sample as usize;
+        self.attachments.borrow_mut()[attachment]
+            .samples
+            .set(index, value);
+    }
+
+    pub fn get_depth(&self, x: u32, y: u32, sample: u32) -> f32 {
+        self.depth.borrow()[self.pixel_index(x, y) * self.spec.samples as usize + sample as usize]
+    }
+
+    pub fn set_depth(&self, x: u32, y: u32, sample: u32, depth: f32) {
+        let index = self.pixel_index(x, y) * self.spec.samples as usize + sample as usize;
+        self.depth.borrow_mut()[index] = depth;
+    }
+
+    pub fn clear_depth(&self, depth: f32) {
+        self.depth.borrow_mut().fill(depth);
+    }
+
+    fn pixel_index(&self, x: u32, y: u32) -> usize {
+        y as usize * self.spec.width as usize + x as usize
+    }
+
+    fn allocate(&self) {
+        let pixels = self.spec.width as usize * self.spec.height as usize;
+        let samples = pixels * self.spec.samples as usize;
+        *self.attachments.borrow_mut() = self
+            .spec
+            .color_attachments()
+            .map(|format| CpuAttachment {
+                format,
+                samples: CpuAttachmentData::create(format, samples),
+                resolved: CpuAttachmentData::create(format, pixels),
+            })
+            .collect();
+        *self.depth.borrow_mut() = match self.spec.depth_attachment() {
+            Some(_) => vec![1.0; samples],
+            None => vec![],
+        };
+    }
+
+    fn record(&self, call: RenderCall) {
+        if let Some(recorder) = self.recorder.as_ref() {
+            recorder.record(call);
+        }
+    }
+
+    fn render_target(&self) -> CpuRenderTarget {
+        CpuRenderTarget {
+            width: self.spec.width,
+            height: self.spec.height,
+            samples: self.spec.samples,
+            attachments: self.attachments.clone(),
+            depth: self.depth.clone(),
+        }
+    }
+}
+
+toAnyImpl!(CpuFramebuffer);
+
+impl FramebufferHandle for CpuFramebuffer {
+    fn bind(&self) {
+        self.record(RenderCall::BindFramebuffer { id: self.id });
+        if let Some(bindings) = self.bindings.as_ref() {
+            bindings.bind_framebuffer(Some(self.render_target()));
+            bindings.set_viewport((0, 0, self.spec.width, self.spec.height));
+        }
+    }
+
+    fn unbind(&self) {
+        self.record(RenderCall::BindFramebuffer { id: 0 });
+        if let Some(bindings) = self.bindings.as_ref() {
+            bindings.bind_framebuffer(None);
+        }
+    }
+
+    fn resize(&mut self, spec: &FramebufferSpec) -> HexgemResult<()> {
+        self.record(RenderCall::ResizeFramebuffer {
+            id: self.id,
+            width: spec.width,
+            height: spec.height,
+        });
+        self.spec = spec.clone();
+        self.allocate();
+        // Like the OpenGL backend, which leaves the default framebuffer bound after a resize.
+        if let Some(bindings) = self.bindings.as_ref() {
+            if bindings
+                .get_framebuffer()
+                .is_some_and(|target| Rc::ptr_eq(&target.attachments, &self.attachments))
+            {
+                bindings.bind_framebuffer(None);
+            }
+        }
+        Ok(())
+    }
+
+    // Color samples are averaged, integer attachments keep their first sample like a
+    // nearest blit would.
+    fn resolve(&self) {
+        self.record(RenderCall::ResolveFramebuffer { id: self.id });
+        let samples = self.spec.samples as usize;
+        for attachment in self.attachments.borrow_mut().iter_mut() {
+            match (&attachment.samples, &mut attachment.resolved) {
+                (CpuAttachmentData::Color(source), CpuAttachmentData::Color(target)) => {
+                    for (pixel, chunk) in target.iter_mut().zip(source.chunks_exact(samples)) {
+                        *pixel = chunk.iter().copied().sum::<Vec4>() / samples as f32;
+                    }
+                }
+                (CpuAttachmentData::Int(source), CpuAttachmentData::Int(target)) => {
+                    for (pixel, chunk) in target.iter_mut().zip(source.chunks_exact(samples)) {
+                        *pixel = chunk[0];
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn read_pixels(
+        &self,
+        attachment: usize,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> AttachmentPixels {
+        self.record(RenderCall::ReadFramebuffer {
+            id: self.id,
+            attachment,
+        });
+        let attachments = self.attachments.borrow();
+        let attachment = &attachments[attachment];
+        let data = match self.spec.samples {
+            1 => &attachment.samples,
+            _ => &attachment.resolved,
+        };
+        let indices = (y..y + height)
+            .flat_map(|row| (x..x + width).map(move |column| (row, column)))
+            .map(|(row, column)| self.pixel_index(column, row));
+        match data {
+            CpuAttachmentData::Int(values) => {
+                AttachmentPixels::Int(indices.map(|index| values[index]).collect())
+            }
+            CpuAttachmentData::Color(values) => {
+                let colors = indices.map(|index| values[index]);
+                match attachment.format {
+                    FramebufferTextureFormat::Rgba8 => {
+                        AttachmentPixels::Rgba8(colors.flat_map(to_rgba8).collect())
+                    }
+                    _ => {
+                        AttachmentPixels::Float(colors.flat_map(|color| color.to_array()).collect())
+                    }
+                }
+            }
+        }
+    }
+
+    fn clear_attachment(&self, attachment: usize, value: AttachmentValue) {
+        self.record(RenderCall::ClearAttachment {
+            id: self.id,
+            attachment,
+            value,
+        });
+        self.attachments.borrow_mut()[attachment]
+            .samples
+            .fill(value);
+    }
+
+    fn bind_color_attachment(&self, attachment: usize, slot: u32) {
+        self.record(RenderCall::BindFramebufferAttachment {
+            id: self.id,
+            attachment,
+            slot,
+        });
+    }
+}
+
+fn to_rgba8(color: Vec4) -> [u8; 4] {
+    (color.clamp(Vec4::ZERO, Vec4::ONE) * 255.0)
+        .round()
+        .to_array()
+        .map(|channel| channel as u8)
+}
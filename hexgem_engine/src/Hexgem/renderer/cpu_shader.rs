@@ -0,0 +1,87 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::toAnyImpl;
+
+use super::{
+    cpu_bindings::{BoundShader, CpuBindings},
+    null_renderer_api::{CallRecorder, RenderCall},
+    shader::{Shader, UniformValue},
+    shader_source::ShaderSource,
+};
+
+pub struct CpuShader {
+    id: u32,
+    source: ShaderSource,
+    uniforms: Rc<RefCell<HashMap<String, UniformValue>>>,
+    recorder: Option<CallRecorder>,
+    bindings: Option<CpuBindings>,
+}
+
+impl CpuShader {
+    pub(crate) fn create(source: &ShaderSource, recorder: Option<CallRecorder>) -> Self {
+        let id = recorder.as_ref().map_or(0, |recorder| recorder.next_id());
+        if let Some(recorder) = recorder.as_ref() {
+            recorder.record(RenderCall::CreateShader {
+                id,
+                name: source.get_name().to_string(),
+            });
+        }
+        Self {
+            id,
+            source: source.clone(),
+            uniforms: Rc::new(RefCell::new(HashMap::new())),
+            recorder,
+            bindings: None,
+        }
+    }
+
+    pub(crate) fn with_bindings(mut self, bindings: CpuBindings) -> Self {
+        self.bindings = Some(bindings);
+        self
+    }
+
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn get_source(&self) -> &ShaderSource {
+        &self.source
+    }
+
+    pub fn get_uniform(&self, name: &str) -> Option<UniformValue> {
+        self.uniforms.borrow().get(name).cloned()
+    }
+}
+
+toAnyImpl!(CpuShader);
+
+impl Shader for CpuShader {
+    fn bind(&self) {
+        if let Some(recorder) = self.recorder.as_ref() {
+            recorder.record(RenderCall::BindShader { id: self.id });
+        }
+        if let Some(bindings) = self.bindings.as_ref() {
+            bindings.bind_shader(BoundShader {
+                name: self.source.get_name().to_string(),
+                uniforms: self.uniforms.clone(),
+            });
+        }
+    }
+
+    fn unbind(&self) {}
+
+    fn get_name(&self) -> &str {
+        self.source.get_name()
+    }
+
+    fn set_uniform(&self, name: &str, value: UniformValue) {
+        if let Some(recorder) = self.recorder.as_ref() {
+            recorder.record(RenderCall::SetUniform {
+                shader: self.id,
+                name: name.to_string(),
+                value: value.clone(),
+            });
+        }
+        self.uniforms.borrow_mut().insert(name.to_string(), value);
+    }
+}
@@ -0,0 +1,109 @@
+use std::{
+    cell::{Ref, RefCell},
+    rc::Rc,
+};
+
+use glam::{Vec2, Vec4};
+
+use crate::toAnyImpl;
+
+use super::{
+    cpu_bindings::{BoundTexture, CpuBindings},
+    image_data::ImageData,
+    null_renderer_api::{CallRecorder, RenderCall},
+    texture::{TextureHandle, TextureOptions},
+};
+
+pub struct CpuTexture {
+    id: u32,
+    options: TextureOptions,
+    levels: Rc<RefCell<Vec<ImageData>>>,
+    recorder: Option<CallRecorder>,
+    bindings: Option<CpuBindings>,
+}
+
+impl CpuTexture {
+    pub(crate) fn create(
+        image: &ImageData,
+        options: &TextureOptions,
+        recorder: Option<CallRecorder>,
+    ) -> Self {
+        let id = recorder.as_ref().map_or(0, |recorder| recorder.next_id());
+        if let Some(recorder) = recorder.as_ref() {
+            recorder.record(RenderCall::CreateTexture {
+                id,
+                width: image.get_width(),
+                height: image.get_height(),
+                mipmaps: options.mipmaps,
+            });
+        }
+        Self {
+            id,
+            options: *options,
+            levels: Rc::new(RefCell::new(Self::levels(image, options))),
+            recorder,
+            bindings: None,
+        }
+    }
+
+    pub(crate) fn with_bindings(mut self, bindings: CpuBindings) -> Self {
+        self.bindings = Some(bindings);
+        self
+    }
+
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn get_image(&self) -> Ref<'_, ImageData> {
+        Ref::map(self.levels.borrow(), |levels| &levels[0])
+    }
+
+    pub fn get_level_count(&self) -> usize {
+        self.levels.borrow().len()
+    }
+
+    pub fn sample(&self, uv: Vec2) -> Vec4 {
+        self.levels.borrow()[0].sample(
+            uv,
+            self.options.mag_filter,
+            (self.options.wrap_s, self.options.wrap_t),
+        )
+    }
+
+    fn levels(image: &ImageData, options: &TextureOptions) -> Vec<ImageData> {
+        if options.mipmaps {
+            return image.mip_chain();
+        }
+        vec![image.clone()]
+    }
+}
+
+toAnyImpl!(CpuTexture);
+
+impl TextureHandle for CpuTexture {
+    fn bind(&self, slot: u32) {
+        if let Some(recorder) = self.recorder.as_ref() {
+            recorder.record(RenderCall::BindTexture { id: self.id, slot });
+        }
+        if let Some(bindings) = self.bindings.as_ref() {
+            bindings.bind_texture(
+                slot,
+                BoundTexture {
+                    levels: self.levels.clone(),
+                    options: self.options,
+                },
+            );
+        }
+    }
+
+    fn set_data(&self, image: &ImageData) {
+        if let Some(recorder) = self.recorder.as_ref() {
+            recorder.record(RenderCall::SetTextureData {
+                id: self.id,
+                size: image.get_pixels().len(),
+            });
+        }
+        *self.levels.borrow_mut() = Self::levels(image, &self.options);
+    }
+}
@@ -0,0 +1,218 @@
+use glam::Vec3;
+
+use crate::{
+    Hexgem::{input::InputState, layer::Layer, timestep::Timestep, window::Window},
+    HexgemEvent::{Event, EventDispatcher, EventType, Key, MouseButton, WindowResizeEvent},
+};
+
+use super::perspective_camera::PerspectiveCamera;
+
+pub struct FlyCameraController {
+    camera: PerspectiveCamera,
+    input: InputState,
+    speed: f32,
+    boost: f32,
+    sensitivity: f32,
+    look_button: Option<MouseButton>,
+    looking: bool,
+}
+
+impl FlyCameraController {
+    pub fn create(camera: PerspectiveCamera) -> Self {
+        Self {
+            camera,
+            input: InputState::create(),
+            speed: 5.0,
+            boost: 3.0,
+            sensitivity: 0.0025,
+            look_button: Some(MouseButton::RightButton),
+            looking: false,
+        }
+    }
+
+    pub fn get_camera(&self) -> &PerspectiveCamera {
+        &self.camera
+    }
+
+    pub fn get_camera_mut(&mut self) -> &mut PerspectiveCamera {
+        &mut self.camera
+    }
+
+    // Units per second.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    // Radians per pixel of mouse movement.
+    pub fn set_sensitivity(&mut self, sensitivity: f32) {
+        self.sensitivity = sensitivity;
+    }
+
+    // None keeps mouse-look active all the time.
+    pub fn set_look_button(&mut self, button: Option<MouseButton>) {
+        self.look_button = button;
+    }
+
+    pub fn is_looking(&self) -> bool {
+        self.looking
+    }
+
+    pub fn on_update(&mut self, timestep: Timestep) {
+        let looking = self
+            .look_button
+            .is_none_or(|button| self.input.is_mouse_button_pressed(button));
+        let delta = self.input.take_mouse_delta();
+        // Locking the cursor moves it, so the first delta after a change is dropped.
+        if looking && self.looking {
+            self.camera.set_orientation(
+                self.camera.get_yaw() + delta.x * self.sensitivity,
+                self.camera.get_pitch() - delta.y * self.sensitivity,
+            );
+        }
+        self.looking = looking;
+
+        let input = &self.input;
+        let forward = self.camera.forward();
+        let right = self.camera.right();
+        let mut direction = Vec3::ZERO;
+        if input.is_key_pressed(Key::W) {
+            direction += forward;
+        }
+        if input.is_key_pressed(Key::S) {
+            direction -= forward;
+        }
+        if input.is_key_pressed(Key::D) {
+            direction += right;
+        }
+        if input.is_key_pressed(Key::A) {
+            direction -= right;
+        }
+        if input.is_key_pressed(Key::E) || input.is_key_pressed(Key::Space) {
+            direction += Vec3::Y;
+        }
+        if input.is_key_pressed(Key::Q) || input.is_key_pressed(Key::LeftControl) {
+            direction -= Vec3::Y;
+        }
+        let mut speed = self.speed;
+        if input.is_key_pressed(Key::LeftShift) {
+            speed *= self.boost;
+        }
+        let position = self.camera.get_position()
+            + direction.normalize_or_zero() * speed * timestep.get_seconds();
+        self.camera.set_position(position);
+    }
+
+    pub fn on_event(&mut self, event: &mut Box<dyn Event>) {
+        self.input.on_event(event.as_ref());
+        let event_dispatcher = EventDispatcher::from(event);
+        event_dispatcher.dispatch::<WindowResizeEvent, _>(EventType::WindowResize, |e| {
+            if e.size.width > 0 && e.size.height > 0 {
+                self.camera
+                    .set_aspect_ratio(e.size.width as f32 / e.size.height as f32);
+            }
+            Some(false)
+        });
+    }
+
+    pub fn update_cursor(&self, window: &mut dyn Window) {
+        if window.is_cursor_locked() != self.looking {
+            window.set_cursor_locked(self.looking);
+        }
+    }
+}
+
+impl Layer for FlyCameraController {
+    fn get_name(&self) -> &'static str {
+        "FlyCameraController"
+    }
+
+    fn on_update(&mut self, window: &mut Box<dyn Window>, timestep: Timestep) {
+        FlyCameraController::on_update(self, timestep);
+        self.update_cursor(window.as_mut());
+    }
+
+    fn on_event(&mut self, event: &mut Box<dyn Event>, window: &mut dyn Window) {
+        FlyCameraController::on_event(self, event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::{
+        Hexgem::core::Size,
+        HexgemEvent::{KeyboardEvent, Modifiers},
+    };
+
+    fn controller() -> FlyCameraController {
+        FlyCameraController::create(PerspectiveCamera::create(FRAC_PI_2, 1.0, 0.1, 100.0))
+    }
+
+    fn send(controller: &mut FlyCameraController, event: impl Event + 'static) {
+        let mut event: Box<dyn Event> = Box::new(event);
+        controller.on_event(&mut event);
+    }
+
+    fn key(controller: &mut FlyCameraController, key: Key, pressed: bool) {
+        send(
+            controller,
+            KeyboardEvent::create(pressed, key, false, Modifiers::create(vec![])),
+        );
+    }
+
+    #[test]
+    fn w_moves_forward_at_the_set_speed() {
+        let mut controller = controller();
+        controller.set_speed(4.0);
+        key(&mut controller, Key::W, true);
+        controller.on_update(Timestep::create(0.5));
+        let position = controller.get_camera().get_position();
+        assert!(position.abs_diff_eq(Vec3::new(0.0, 0.0, -2.0), 1e-5));
+
+        key(&mut controller, Key::LeftShift, true);
+        controller.on_update(Timestep::create(0.25));
+        let position = controller.get_camera().get_position();
+        assert!(position.abs_diff_eq(Vec3::new(0.0, 0.0, -5.0), 1e-5));
+
+        key(&mut controller, Key::W, false);
+        key(&mut controller, Key::LeftShift, false);
+        controller.on_update(Timestep::create(1.0));
+        assert_eq!(controller.get_camera().get_position(), position);
+    }
+
+    #[test]
+    fn diagonal_movement_is_normalized() {
+        let mut controller = controller();
+        controller.set_speed(1.0);
+        key(&mut controller, Key::W, true);
+        key(&mut controller, Key::D, true);
+        controller.on_update(Timestep::create(1.0));
+        let position = controller.get_camera().get_position();
+        assert!((position.length() - 1.0).abs() < 1e-5);
+        assert!(position.x > 0.0 && position.z < 0.0);
+    }
+
+    #[test]
+    fn resize_updates_the_aspect_ratio() {
+        let mut controller = controller();
+        send(
+            &mut controller,
+            WindowResizeEvent::create(Size {
+                width: 1600,
+                height: 900,
+            }),
+        );
+        assert_eq!(controller.get_camera().get_aspect_ratio(), 1600.0 / 900.0);
+        // Minimized windows report a zero size, the last aspect ratio is kept.
+        send(
+            &mut controller,
+            WindowResizeEvent::create(Size {
+                width: 0,
+                height: 0,
+            }),
+        );
+        assert_eq!(controller.get_camera().get_aspect_ratio(), 1600.0 / 900.0);
+    }
+}
@@ -0,0 +1,538 @@
+use std::{collections::HashMap, fs, path::Path, rc::Rc};
+
+use ab_glyph::{Font as _, FontVec, PxScale, ScaleFont};
+use glam::{UVec2, Vec2};
+
+use crate::Hexgem::error::{HexgemError, HexgemResult};
+
+use super::{
+    image_data::ImageData,
+    text::{TextLayout, TextOptions},
+    texture::{SubTexture2D, Texture2D, TextureOptions},
+};
+
+const MAX_ATLAS_SIZE: u32 = 4096;
+// Empty pixels around every glyph, so linear filtering never picks up a neighbour.
+const GLYPH_PADDING: u32 = 2;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontOptions {
+    // Pixel height glyphs are rasterized at, text looks sharpest drawn at this size.
+    pub size: f32,
+    pub charset: Vec<char>,
+}
+
+impl Default for FontOptions {
+    fn default() -> Self {
+        Self {
+            size: 32.0,
+            charset: (' '..='~').collect(),
+        }
+    }
+}
+
+impl FontOptions {
+    pub fn create(size: f32) -> Self {
+        Self {
+            size,
+            ..Self::default()
+        }
+    }
+
+    pub fn charset(mut self, charset: &str) -> Self {
+        self.charset = charset.chars().collect();
+        self
+    }
+
+    // Adds characters on top of the current charset, duplicates are ignored.
+    pub fn extend_charset(mut self, characters: &str) -> Self {
+        self.charset.extend(characters.chars());
+        self
+    }
+}
+
+// Metrics are in pixels at the font size, with y pointing up from the baseline.
+#[derive(Clone)]
+pub struct Glyph {
+    pub character: char,
+    pub size: Vec2,
+    // From the pen position on the baseline to the bottom left corner of the glyph.
+    pub offset: Vec2,
+    pub advance: f32,
+    // None for glyphs without pixels, like the space.
+    pub sub_texture: Option<SubTexture2D>,
+}
+
+pub struct Font {
+    name: String,
+    size: f32,
+    ascent: f32,
+    descent: f32,
+    line_height: f32,
+    glyphs: HashMap<char, Glyph>,
+    kerning: HashMap<(char, char), f32>,
+    texture: Rc<Texture2D>,
+}
+
+impl Font {
+    // Picks the format from the extension: .fnt files are BMFont, anything else TrueType.
+    pub fn load<P: AsRef<Path>>(path: P, options: &FontOptions) -> HexgemResult<Self> {
+        let path = path.as_ref();
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("fnt") => Self::load_bmfont(path),
+            _ => Self::load_ttf(path, options),
+        }
+    }
+
+    pub fn load_ttf<P: AsRef<Path>>(path: P, options: &FontOptions) -> HexgemResult<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)
+            .map_err(|err| HexgemError::Font(format!("{}: {}", path.display(), err)))?;
+        let mut font = Self::from_ttf(&bytes, options)?;
+        font.name = file_stem(path);
+        Ok(font)
+    }
+
+    // Rasterizes every glyph of the charset into a single atlas texture.
+    pub fn from_ttf(bytes: &[u8], options: &FontOptions) -> HexgemResult<Self> {
+        if options.size <= 0.0 {
+            return Err(HexgemError::Font(format!(
+                "Invalid font size {}",
+                options.size
+            )));
+        }
+        let font = FontVec::try_from_vec(bytes.to_vec())
+            .map_err(|err| HexgemError::Font(err.to_string()))?;
+        let scaled = font.as_scaled(PxScale::from(options.size));
+        let mut charset = options.charset.clone();
+        charset.sort_unstable();
+        charset.dedup();
+
+        let mut bitmaps = vec![];
+        let mut metrics = vec![];
+        for character in charset.iter().copied() {
+            let id = font.glyph_id(character);
+            if id.0 == 0 {
+                continue;
+            }
+            let advance = scaled.h_advance(id);
+            let outline = font.outline_glyph(scaled.scaled_glyph(character));
+            let Some(outline) = outline else {
+                metrics.push((character, Vec2::ZERO, Vec2::ZERO, advance, None));
+                continue;
+            };
+            let bounds = outline.px_bounds();
+            let (width, height) = (bounds.width() as u32, bounds.height() as u32);
+            if width == 0 || height == 0 {
+                metrics.push((character, Vec2::ZERO, Vec2::ZERO, advance, None));
+                continue;
+            }
+            let mut bitmap = ImageData::filled(width, height, [255, 255, 255, 0])?;
+            outline.draw(|x, y, coverage| {
+                if x < width && y < height {
+                    let alpha = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+                    bitmap.set_pixel(x, y, [255, 255, 255, alpha]);
+                }
+            });
+            metrics.push((
+                character,
+                Vec2::new(width as f32, height as f32),
+                Vec2::new(bounds.min.x, -bounds.max.y),
+                advance,
+                Some(bitmaps.len()),
+            ));
+            bitmaps.push(bitmap);
+        }
+
+        let (atlas, positions) = pack_atlas(&bitmaps)?;
+        let texture = Rc::new(Texture2D::from_image(&atlas, TextureOptions::default())?);
+        let glyphs = metrics
+            .into_iter()
+            .map(|(character, size, offset, advance, bitmap)| {
+                let sub_texture = bitmap.map(|index| {
+                    SubTexture2D::from_pixels(texture.clone(), positions[index], size.as_uvec2())
+                });
+                let glyph = Glyph {
+                    character,
+                    size,
+                    offset,
+                    advance,
+                    sub_texture,
+                };
+                (character, glyph)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut kerning = HashMap::new();
+        for first in glyphs.keys() {
+            for second in glyphs.keys() {
+                let amount = scaled.kern(font.glyph_id(*first), font.glyph_id(*second));
+                if amount != 0.0 {
+                    kerning.insert((*first, *second), amount);
+                }
+            }
+        }
+
+        Ok(Self {
+            name: "ttf".to_string(),
+            size: options.size,
+            ascent: scaled.ascent(),
+            descent: scaled.descent(),
+            line_height: scaled.height() + scaled.line_gap(),
+            glyphs,
+            kerning,
+            texture,
+        })
+    }
+
+    // Loads a text format BMFont descriptor, its page image is looked up next to it.
+    pub fn load_bmfont<P: AsRef<Path>>(path: P) -> HexgemResult<Self> {
+        let path = path.as_ref();
+        let descriptor = fs::read_to_string(path)
+            .map_err(|err| HexgemError::Font(format!("{}: {}", path.display(), err)))?;
+        let page = Self::get_bmfont_page(&descriptor)
+            .ok_or_else(|| HexgemError::Font(format!("{}: no page file", path.display())))?;
+        let page = ImageData::load(path.with_file_name(page))?;
+        let mut font = Self::from_bmfont(&descriptor, &page)?;
+        if font.name.is_empty() {
+            font.name = file_stem(path);
+        }
+        Ok(font)
+    }
+
+    // File name of the page image, relative to the descriptor.
+    pub fn get_bmfont_page(descriptor: &str) -> Option<String> {
+        BmFontLine::parse_all(descriptor)
+            .into_iter()
+            .find(|line| line.tag == "page")
+            .and_then(|line| line.get("file").map(str::to_string))
+    }
+
+    // Only single page fonts are supported, every glyph has to live on the given image.
+    pub fn from_bmfont(descriptor: &str, page: &ImageData) -> HexgemResult<Self> {
+        let lines = BmFontLine::parse_all(descriptor);
+        let find = |tag: &str| lines.iter().find(|line| line.tag == tag);
+        let info = find("info").ok_or_else(|| missing_block("info"))?;
+        let common = find("common").ok_or_else(|| missing_block("common"))?;
+        let pages = common.get_int("pages")?;
+        if pages != 1 {
+            return Err(HexgemError::Font(format!(
+                "Only single page BMFonts are supported, got {} pages",
+                pages
+            )));
+        }
+        let line_height = common.get_int("lineHeight")? as f32;
+        let base = common.get_int("base")? as f32;
+        // A negative size means the font was generated to match character heights.
+        let size = match info.get_int("size")?.abs() {
+            0 => line_height,
+            size => size as f32,
+        };
+
+        let texture = Rc::new(Texture2D::from_image(page, TextureOptions::default())?);
+        let mut glyphs = HashMap::new();
+        for line in lines.iter().filter(|line| line.tag == "char") {
+            let id = line.get_int("id")?;
+            let Some(character) = u32::try_from(id).ok().and_then(char::from_u32) else {
+                continue;
+            };
+            let position = UVec2::new(line.get_uint("x")?, line.get_uint("y")?);
+            let size = UVec2::new(line.get_uint("width")?, line.get_uint("height")?);
+            let outside = |start: u32, length: u32, limit: u32| {
+                start
+                    .checked_add(length)
+                    .filter(|end| *end <= limit)
+                    .is_none()
+            };
+            if outside(position.x, size.x, page.get_width())
+                || outside(position.y, size.y, page.get_height())
+            {
+                return Err(line.error(format!(
+                    "places glyph {} outside of the {}x{} page",
+                    id,
+                    page.get_width(),
+                    page.get_height()
+                )));
+            }
+            let x_offset = line.get_int("xoffset")? as f32;
+            let y_offset = line.get_int("yoffset")? as f32;
+            let sub_texture = (size.x > 0 && size.y > 0)
+                .then(|| SubTexture2D::from_pixels(texture.clone(), position, size));
+            glyphs.insert(
+                character,
+                Glyph {
+                    character,
+                    size: size.as_vec2(),
+                    offset: Vec2::new(x_offset, base - y_offset - size.y as f32),
+                    advance: line.get_int("xadvance")? as f32,
+                    sub_texture,
+                },
+            );
+        }
+
+        let mut kerning = HashMap::new();
+        for line in lines.iter().filter(|line| line.tag == "kerning") {
+            let first = char::from_u32(line.get_uint("first")?);
+            let second = char::from_u32(line.get_uint("second")?);
+            if let (Some(first), Some(second)) = (first, second) {
+                kerning.insert((first, second), line.get_int("amount")? as f32);
+            }
+        }
+
+        Ok(Self {
+            name: info.get("face").unwrap_or_default().to_string(),
+            size,
+            ascent: base,
+            descent: base - line_height,
+            line_height,
+            glyphs,
+            kerning,
+            texture,
+        })
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    pub fn get_size(&self) -> f32 {
+        self.size
+    }
+
+    pub fn get_ascent(&self) -> f32 {
+        self.ascent
+    }
+
+    // Negative, measured downwards from the baseline.
+    pub fn get_descent(&self) -> f32 {
+        self.descent
+    }
+
+    // Distance between the baselines of two lines.
+    pub fn get_line_height(&self) -> f32 {
+        self.line_height
+    }
+
+    pub fn get_texture(&self) -> &Rc<Texture2D> {
+        &self.texture
+    }
+
+    // Characters missing from the font fall back to '?' when the font has one.
+    pub fn get_glyph(&self, character: char) -> Option<&Glyph> {
+        self.glyphs
+            .get(&character)
+            .or_else(|| self.glyphs.get(&'?'))
+    }
+
+    pub fn has_glyph(&self, character: char) -> bool {
+        self.glyphs.contains_key(&character)
+    }
+
+    pub fn get_kerning(&self, first: char, second: char) -> f32 {
+        self.kerning.get(&(first, second)).copied().unwrap_or(0.0)
+    }
+
+    pub fn layout(&self, text: &str, options: &TextOptions) -> TextLayout {
+        TextLayout::create(self, text, options)
+    }
+
+    // Size of the text in em, multiply by the drawn font size to get world units.
+    pub fn measure(&self, text: &str) -> Vec2 {
+        self.layout(text, &TextOptions::default()).get_size()
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map_or(String::new(), |stem| stem.to_string_lossy().to_string())
+}
+
+fn missing_block(tag: &str) -> HexgemError {
+    HexgemError::Font(format!("BMFont descriptor has no {} block", tag))
+}
+
+// Packs bitmaps row by row into the smallest square power of two atlas they fit in.
+fn pack_atlas(bitmaps: &[ImageData]) -> HexgemResult<(ImageData, Vec<UVec2>)> {
+    let mut size = 64;
+    loop {
+        if let Some(positions) = pack_rows(bitmaps, size) {
+            let mut atlas = ImageData::filled(size, size, [255, 255, 255, 0])?;
+            for (bitmap, position) in bitmaps.iter().zip(positions.iter()) {
+                for y in 0..bitmap.get_height() {
+                    for x in 0..bitmap.get_width() {
+                        atlas.set_pixel(position.x + x, position.y + y, bitmap.pixel(x, y));
+                    }
+                }
+            }
+            return Ok((atlas, positions));
+        }
+        if size >= MAX_ATLAS_SIZE {
+            return Err(HexgemError::Font(format!(
+                "Glyphs do not fit into a {}x{} atlas, use a smaller size or charset",
+                MAX_ATLAS_SIZE, MAX_ATLAS_SIZE
+            )));
+        }
+        size *= 2;
+    }
+}
+
+fn pack_rows(bitmaps: &[ImageData], size: u32) -> Option<Vec<UVec2>> {
+    let mut positions = Vec::with_capacity(bitmaps.len());
+    let mut cursor = UVec2::splat(GLYPH_PADDING);
+    let mut row_height = 0;
+    for bitmap in bitmaps {
+        let (width, height) = (bitmap.get_width(), bitmap.get_height());
+        if cursor.x + width + GLYPH_PADDING > size {
+            cursor = UVec2::new(GLYPH_PADDING, cursor.y + row_height + GLYPH_PADDING);
+            row_height = 0;
+        }
+        if cursor.x + width + GLYPH_PADDING > size || cursor.y + height + GLYPH_PADDING > size {
+            return None;
+        }
+        positions.push(cursor);
+        cursor.x += width + GLYPH_PADDING;
+        row_height = row_height.max(height);
+    }
+    Some(positions)
+}
+
+// One line of a text BMFont descriptor: a tag followed by key=value pairs.
+struct BmFontLine {
+    tag: String,
+    values: HashMap<String, String>,
+    // One based, for error messages.
+    number: usize,
+}
+
+impl BmFontLine {
+    fn parse_all(descriptor: &str) -> Vec<Self> {
+        descriptor
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| Self::parse(line, index + 1))
+            .collect()
+    }
+
+    fn parse(line: &str, number: usize) -> Option<Self> {
+        let line = line.trim();
+        let (tag, mut rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        if tag.is_empty() {
+            return None;
+        }
+        let mut values = HashMap::new();
+        while let Some((key, value)) = rest.trim_start().split_once('=') {
+            let (value, remaining) = match value.strip_prefix('"') {
+                Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+                None => value.split_once(char::is_whitespace).unwrap_or((value, "")),
+            };
+            values.insert(key.trim().to_string(), value.to_string());
+            rest = remaining;
+        }
+        Some(Self {
+            tag: tag.to_string(),
+            values,
+            number,
+        })
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    fn get_int(&self, key: &str) -> HexgemResult<i32> {
+        let value = self
+            .get(key)
+            .ok_or_else(|| self.error(format!("is missing {}", key)))?;
+        value
+            .parse()
+            .map_err(|_| self.error(format!("has an invalid {}: {}", key, value)))
+    }
+
+    fn get_uint(&self, key: &str) -> HexgemResult<u32> {
+        let value = self.get_int(key)?;
+        u32::try_from(value).map_err(|_| self.error(format!("has a negative {}: {}", key, value)))
+    }
+
+    fn error(&self, message: String) -> HexgemError {
+        HexgemError::Font(format!(
+            "BMFont {} block on line {} {}",
+            self.tag, self.number, message
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hexgem::renderer::HexgemRenderer::{NullRendererApi, RenderCommand};
+
+    fn descriptor(glyph: &str) -> String {
+        format!(
+            "info face=\"Test\" size=10\n\
+             common lineHeight=12 base=9 pages=1\n\
+             page id=0 file=\"test.png\"\n\
+             {}\n",
+            glyph
+        )
+    }
+
+    fn load(glyph: &str) -> HexgemResult<Font> {
+        RenderCommand::init(NullRendererApi);
+        let page = ImageData::filled(16, 16, [255, 255, 255, 255]).unwrap();
+        Font::from_bmfont(&descriptor(glyph), &page)
+    }
+
+    fn error_message(glyph: &str) -> String {
+        match load(glyph) {
+            Err(HexgemError::Font(message)) => message,
+            Err(err) => panic!("Expected a font error, got {}", err),
+            Ok(_) => panic!("Expected {} to be rejected", glyph),
+        }
+    }
+
+    #[test]
+    fn loads_glyphs_and_kerning() {
+        let font = load(
+            "char id=65 x=0 y=0 width=4 height=6 xoffset=0 yoffset=3 xadvance=5\n\
+             kerning first=65 second=65 amount=-1",
+        )
+        .unwrap();
+        assert_eq!(font.get_name(), "Test");
+        let glyph = font.get_glyph('A').unwrap();
+        assert_eq!(glyph.size, Vec2::new(4.0, 6.0));
+        assert_eq!(glyph.offset, Vec2::new(0.0, 0.0));
+        assert_eq!(font.get_kerning('A', 'A'), -1.0);
+    }
+
+    #[test]
+    fn negative_glyph_fields_are_rejected_with_their_line() {
+        let message =
+            error_message("char id=65 x=-2 y=0 width=4 height=6 xoffset=0 yoffset=0 xadvance=5");
+        assert!(message.contains("line 4"), "{}", message);
+        assert!(message.contains("negative x: -2"), "{}", message);
+        let message =
+            error_message("char id=65 x=0 y=0 width=4 height=-6 xoffset=0 yoffset=0 xadvance=5");
+        assert!(message.contains("negative height: -6"), "{}", message);
+    }
+
+    #[test]
+    fn glyphs_outside_of_the_page_are_rejected() {
+        let message =
+            error_message("char id=65 x=14 y=0 width=4 height=6 xoffset=0 yoffset=0 xadvance=5");
+        assert!(message.contains("line 4"), "{}", message);
+        assert!(message.contains("outside of the 16x16 page"), "{}", message);
+        error_message(
+            "char id=65 x=0 y=2147483647 width=4 height=2147483647 xoffset=0 yoffset=0 xadvance=5",
+        );
+    }
+
+    #[test]
+    fn missing_fields_name_the_block_and_line() {
+        let message = error_message("char id=65 x=0 y=0 width=4 xoffset=0 yoffset=0 xadvance=5");
+        assert_eq!(message, "BMFont char block on line 4 is missing height");
+    }
+}
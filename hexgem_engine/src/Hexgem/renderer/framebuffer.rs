@@ -0,0 +1,343 @@
+use glam::{UVec2, Vec4};
+use log::error;
+
+use crate::{
+    Hexgem::{
+        core::ToAny,
+        error::{HexgemError, HexgemResult},
+    },
+    HexgemEvent::{Event, EventDispatcher, EventType, WindowResizeEvent},
+};
+
+use super::{image_data::ImageData, render_command::RenderCommand};
+
+pub const MAX_FRAMEBUFFER_SIZE: u32 = 8192;
+pub const MAX_COLOR_ATTACHMENTS: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FramebufferTextureFormat {
+    Rgba8,
+    Rgba16F,
+    RedInteger,
+    Depth24Stencil8,
+    Depth32F,
+}
+
+impl FramebufferTextureFormat {
+    pub fn is_depth(&self) -> bool {
+        matches!(
+            self,
+            FramebufferTextureFormat::Depth24Stencil8 | FramebufferTextureFormat::Depth32F
+        )
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(self, FramebufferTextureFormat::RedInteger)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FramebufferSpec {
+    pub width: u32,
+    pub height: u32,
+    pub samples: u32,
+    pub attachments: Vec<FramebufferTextureFormat>,
+}
+
+impl FramebufferSpec {
+    pub fn create(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            samples: 1,
+            attachments: vec![
+                FramebufferTextureFormat::Rgba8,
+                FramebufferTextureFormat::Depth24Stencil8,
+            ],
+        }
+    }
+
+    pub fn samples(mut self, samples: u32) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    pub fn attachments(mut self, attachments: &[FramebufferTextureFormat]) -> Self {
+        self.attachments = attachments.to_vec();
+        self
+    }
+
+    // Color attachments are indexed in the order they appear, skipping the depth attachment.
+    pub fn color_attachments(&self) -> impl Iterator<Item = FramebufferTextureFormat> + '_ {
+        self.attachments
+            .iter()
+            .copied()
+            .filter(|format| !format.is_depth())
+    }
+
+    pub fn color_attachment(&self, index: usize) -> Option<FramebufferTextureFormat> {
+        self.color_attachments().nth(index)
+    }
+
+    pub fn depth_attachment(&self) -> Option<FramebufferTextureFormat> {
+        self.attachments
+            .iter()
+            .copied()
+            .find(|format| format.is_depth())
+    }
+
+    pub fn validate(&self) -> HexgemResult<()> {
+        if self.width == 0 || self.height == 0 {
+            return Err(HexgemError::Framebuffer(format!(
+                "Invalid size {}x{}",
+                self.width, self.height
+            )));
+        }
+        if self.width > MAX_FRAMEBUFFER_SIZE || self.height > MAX_FRAMEBUFFER_SIZE {
+            return Err(HexgemError::Framebuffer(format!(
+                "Size {}x{} exceeds the maximum of {}",
+                self.width, self.height, MAX_FRAMEBUFFER_SIZE
+            )));
+        }
+        if !matches!(self.samples, 1 | 2 | 4 | 8 | 16) {
+            return Err(HexgemError::Framebuffer(format!(
+                "Unsupported sample count {}",
+                self.samples
+            )));
+        }
+        if self.attachments.is_empty() {
+            return Err(HexgemError::Framebuffer(
+                "At least one attachment is required".to_string(),
+            ));
+        }
+        let depth_count = self.attachments.iter().filter(|f| f.is_depth()).count();
+        if depth_count > 1 {
+            return Err(HexgemError::Framebuffer(format!(
+                "Only one depth attachment is allowed, got {}",
+                depth_count
+            )));
+        }
+        let color_count = self.attachments.len() - depth_count;
+        if color_count > MAX_COLOR_ATTACHMENTS {
+            return Err(HexgemError::Framebuffer(format!(
+                "At most {} color attachments are allowed, got {}",
+                MAX_COLOR_ATTACHMENTS, color_count
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AttachmentValue {
+    Color(Vec4),
+    Int(i32),
+}
+
+// Pixel rows are ordered bottom first, the way glReadPixels returns them.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttachmentPixels {
+    Rgba8(Vec<u8>),
+    Float(Vec<f32>),
+    Int(Vec<i32>),
+}
+
+pub trait FramebufferHandle: ToAny {
+    fn bind(&self);
+    fn unbind(&self);
+    fn resize(&mut self, spec: &FramebufferSpec) -> HexgemResult<()>;
+    fn resolve(&self);
+    fn read_pixels(
+        &self,
+        attachment: usize,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> AttachmentPixels;
+    fn clear_attachment(&self, attachment: usize, value: AttachmentValue);
+    fn bind_color_attachment(&self, attachment: usize, slot: u32);
+}
+
+pub struct Framebuffer {
+    spec: FramebufferSpec,
+    handle: Box<dyn FramebufferHandle>,
+}
+
+impl Framebuffer {
+    pub fn create(spec: FramebufferSpec) -> HexgemResult<Self> {
+        spec.validate()?;
+        let handle = RenderCommand::create_framebuffer(&spec)?;
+        Ok(Self { spec, handle })
+    }
+
+    pub fn get_spec(&self) -> &FramebufferSpec {
+        &self.spec
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.spec.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.spec.height
+    }
+
+    pub fn get_size(&self) -> UVec2 {
+        UVec2::new(self.spec.width, self.spec.height)
+    }
+
+    pub fn get_handle(&self) -> &dyn FramebufferHandle {
+        self.handle.as_ref()
+    }
+
+    // Binding also sets the viewport to cover the whole framebuffer.
+    pub fn bind(&self) {
+        self.handle.bind();
+    }
+
+    pub fn unbind(&self) {
+        self.handle.unbind();
+    }
+
+    // A zero sized request comes from a minimized window and keeps the current attachments.
+    pub fn resize(&mut self, width: u32, height: u32) -> HexgemResult<()> {
+        if width == 0 || height == 0 || (width == self.spec.width && height == self.spec.height) {
+            return Ok(());
+        }
+        let mut spec = self.spec.clone();
+        spec.width = width;
+        spec.height = height;
+        spec.validate()?;
+        self.handle.resize(&spec)?;
+        self.spec = spec;
+        Ok(())
+    }
+
+    pub fn on_event(&mut self, event: &mut Box<dyn Event>) {
+        let event_dispatcher = EventDispatcher::from(event);
+        event_dispatcher.dispatch::<WindowResizeEvent, _>(EventType::WindowResize, |e| {
+            let width = e.size.width.max(0) as u32;
+            let height = e.size.height.max(0) as u32;
+            if let Err(err) = self.resize(width, height) {
+                error!("{}", err);
+            }
+            Some(false)
+        });
+    }
+
+    pub fn is_multisampled(&self) -> bool {
+        self.spec.samples > 1
+    }
+
+    // Copies multisampled color attachments into their single sample textures.
+    pub fn resolve(&self) {
+        if self.is_multisampled() {
+            self.handle.resolve();
+        }
+    }
+
+    pub fn clear_attachment(&self, attachment: usize, value: AttachmentValue) -> HexgemResult<()> {
+        let format = self.color_format(attachment)?;
+        match (format.is_integer(), value) {
+            (true, AttachmentValue::Int(_)) | (false, AttachmentValue::Color(_)) => {
+                self.handle.clear_attachment(attachment, value);
+                Ok(())
+            }
+            _ => Err(HexgemError::Framebuffer(format!(
+                "Cannot clear {:?} attachment {} with {:?}",
+                format, attachment, value
+            ))),
+        }
+    }
+
+    pub fn bind_color_attachment(&self, attachment: usize, slot: u32) -> HexgemResult<()> {
+        self.color_format(attachment)?;
+        self.handle.bind_color_attachment(attachment, slot);
+        Ok(())
+    }
+
+    // Region is measured in pixels from the bottom left corner, like the viewport.
+    pub fn read_pixels(
+        &self,
+        attachment: usize,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> HexgemResult<AttachmentPixels> {
+        self.color_format(attachment)?;
+        let outside = |start: u32, length: u32, limit: u32| {
+            start
+                .checked_add(length)
+                .filter(|end| *end <= limit)
+                .is_none()
+        };
+        if width == 0
+            || height == 0
+            || outside(x, width, self.spec.width)
+            || outside(y, height, self.spec.height)
+        {
+            return Err(HexgemError::Framebuffer(format!(
+                "Region {}x{} at ({}, {}) is outside of the {}x{} framebuffer",
+                width, height, x, y, self.spec.width, self.spec.height
+            )));
+        }
+        self.resolve();
+        Ok(self.handle.read_pixels(attachment, x, y, width, height))
+    }
+
+    // Reads a single value of an integer attachment, typically an entity id for picking.
+    pub fn read_pixel_int(&self, attachment: usize, x: u32, y: u32) -> HexgemResult<i32> {
+        match self.read_pixels(attachment, x, y, 1, 1)? {
+            AttachmentPixels::Int(values) => Ok(values[0]),
+            _ => Err(HexgemError::Framebuffer(format!(
+                "Attachment {} is not an integer attachment",
+                attachment
+            ))),
+        }
+    }
+
+    pub fn read_image(&self, attachment: usize) -> HexgemResult<ImageData> {
+        let pixels = match self.read_pixels(attachment, 0, 0, self.spec.width, self.spec.height)? {
+            AttachmentPixels::Rgba8(pixels) => pixels,
+            AttachmentPixels::Float(values) => values
+                .iter()
+                .map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8)
+                .collect(),
+            AttachmentPixels::Int(_) => {
+                return Err(HexgemError::Framebuffer(format!(
+                    "Cannot read integer attachment {} as an image",
+                    attachment
+                )))
+            }
+        };
+        let image = ImageData::from_rgba(self.spec.width, self.spec.height, pixels)?;
+        ImageData::from_rgba(self.spec.width, self.spec.height, image.flipped_rows())
+    }
+
+    fn color_format(&self, attachment: usize) -> HexgemResult<FramebufferTextureFormat> {
+        self.spec.color_attachment(attachment).ok_or_else(|| {
+            HexgemError::Framebuffer(format!("No color attachment at index {}", attachment))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hexgem::renderer::HexgemRenderer::NullRendererApi;
+
+    #[test]
+    fn read_pixels_rejects_regions_outside_the_framebuffer() {
+        RenderCommand::init(NullRendererApi);
+        let framebuffer = Framebuffer::create(FramebufferSpec::create(4, 4)).unwrap();
+        assert!(framebuffer.read_pixels(0, 0, 0, 4, 4).is_ok());
+        assert!(framebuffer.read_pixels(0, 3, 3, 1, 1).is_ok());
+        assert!(framebuffer.read_pixels(0, 0, 0, 0, 1).is_err());
+        assert!(framebuffer.read_pixels(0, 2, 0, 3, 1).is_err());
+        assert!(framebuffer.read_pixels(0, 0, 2, 1, 3).is_err());
+        assert!(framebuffer.read_pixels(0, u32::MAX, 0, 2, 1).is_err());
+        assert!(framebuffer.read_pixels(0, 0, 1, 1, u32::MAX).is_err());
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use log::info;
+
+use crate::Hexgem::error::{HexgemError, HexgemResult};
+
+use super::image_data::ImageData;
+
+// Set to rewrite golden images with the actual output instead of comparing against them.
+pub const UPDATE_GOLDEN_ENV: &str = "HEXGEM_UPDATE_GOLDEN";
+
+#[derive(Clone, Debug)]
+pub struct ImageComparison {
+    pub mismatched_pixels: usize,
+    pub max_difference: u8,
+    // Mismatching pixels in red over a dimmed grayscale copy of the expected image.
+    pub diff: ImageData,
+}
+
+impl ImageComparison {
+    pub fn is_match(&self) -> bool {
+        self.mismatched_pixels == 0
+    }
+}
+
+// A pixel mismatches when any channel differs by more than the tolerance.
+pub fn compare_images(
+    actual: &ImageData,
+    expected: &ImageData,
+    tolerance: u8,
+) -> HexgemResult<ImageComparison> {
+    if actual.get_width() != expected.get_width() || actual.get_height() != expected.get_height() {
+        return Err(HexgemError::ImageComparison(format!(
+            "Size {}x{} does not match the expected {}x{}",
+            actual.get_width(),
+            actual.get_height(),
+            expected.get_width(),
+            expected.get_height()
+        )));
+    }
+    let mut mismatched_pixels = 0;
+    let mut max_difference = 0;
+    let mut diff = Vec::with_capacity(expected.get_pixels().len());
+    for (actual, expected) in actual
+        .get_pixels()
+        .chunks_exact(4)
+        .zip(expected.get_pixels().chunks_exact(4))
+    {
+        let difference = actual
+            .iter()
+            .zip(expected)
+            .map(|(a, e)| a.abs_diff(*e))
+            .max()
+            .unwrap_or(0);
+        max_difference = max_difference.max(difference);
+        if difference > tolerance {
+            mismatched_pixels += 1;
+            diff.extend([255, 0, 0, 255]);
+        } else {
+            let gray = (expected[0] as u32 * 3 + expected[1] as u32 * 6 + expected[2] as u32) / 10;
+            let dimmed = (gray / 3) as u8;
+            diff.extend([dimmed, dimmed, dimmed, 255]);
+        }
+    }
+    Ok(ImageComparison {
+        mismatched_pixels,
+        max_difference,
+        diff: ImageData::from_rgba(expected.get_width(), expected.get_height(), diff)?,
+    })
+}
+
+// Compares rendered frames against a PNG on disk. A missing golden image is written from the
+// actual output, on a mismatch the actual and diff images are saved next to the golden one.
+pub struct GoldenImage {
+    path: PathBuf,
+    tolerance: u8,
+    allowed_mismatches: usize,
+}
+
+impl GoldenImage {
+    pub fn create<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            tolerance: 0,
+            allowed_mismatches: 0,
+        }
+    }
+
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn allowed_mismatches(mut self, allowed_mismatches: usize) -> Self {
+        self.allowed_mismatches = allowed_mismatches;
+        self
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get_actual_path(&self) -> PathBuf {
+        self.sibling("actual")
+    }
+
+    pub fn get_diff_path(&self) -> PathBuf {
+        self.sibling("diff")
+    }
+
+    pub fn check(&self, actual: &ImageData) -> HexgemResult<ImageComparison> {
+        if !self.path.exists() || env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+            self.write(&self.path, actual)?;
+            info!("Wrote golden image {}", self.path.display());
+            return compare_images(actual, actual, self.tolerance);
+        }
+        let expected = ImageData::load(&self.path)?;
+        let comparison = compare_images(actual, &expected, self.tolerance)?;
+        if comparison.mismatched_pixels <= self.allowed_mismatches {
+            return Ok(comparison);
+        }
+        self.write(&self.get_actual_path(), actual)?;
+        self.write(&self.get_diff_path(), &comparison.diff)?;
+        Err(HexgemError::ImageComparison(format!(
+            "{} pixels of {} differ by up to {} (tolerance {}), see {}",
+            comparison.mismatched_pixels,
+            self.path.display(),
+            comparison.max_difference,
+            self.tolerance,
+            self.get_diff_path().display()
+        )))
+    }
+
+    fn sibling(&self, suffix: &str) -> PathBuf {
+        let stem = self
+            .path
+            .file_stem()
+            .map_or("golden".into(), |stem| stem.to_string_lossy());
+        self.path.with_file_name(format!("{}.{}.png", stem, suffix))
+    }
+
+    fn write(&self, path: &Path, image: &ImageData) -> HexgemResult<()> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).map_err(|err| {
+                HexgemError::ImageComparison(format!("{}: {}", parent.display(), err))
+            })?;
+        }
+        image.save(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(pixels: &[[u8; 4]]) -> ImageData {
+        ImageData::from_rgba(pixels.len() as u32, 1, pixels.concat()).unwrap()
+    }
+
+    #[test]
+    fn differences_up_to_the_tolerance_match() {
+        let expected = image(&[[100, 200, 50, 255], [0, 0, 0, 255], [10, 10, 10, 255]]);
+        let actual = image(&[[102, 198, 50, 255], [0, 0, 3, 255], [10, 10, 10, 255]]);
+        let comparison = compare_images(&actual, &expected, 2).unwrap();
+        assert_eq!(comparison.mismatched_pixels, 1);
+        assert_eq!(comparison.max_difference, 3);
+        assert!(!comparison.is_match());
+        assert!(compare_images(&actual, &expected, 3).unwrap().is_match());
+        assert!(compare_images(&expected, &expected, 0).unwrap().is_match());
+    }
+
+    #[test]
+    fn diff_marks_mismatches_in_red() {
+        let expected = image(&[[100, 200, 50, 255], [255, 255, 255, 255]]);
+        let actual = image(&[[100, 200, 50, 255], [255, 255, 255, 0]]);
+        let diff = compare_images(&actual, &expected, 0).unwrap().diff;
+        assert_eq!((diff.get_width(), diff.get_height()), (2, 1));
+        // (100 * 3 + 200 * 6 + 50) / 10 = 155, dimmed to a third.
+        assert_eq!(diff.pixel(0, 0), [51, 51, 51, 255]);
+        assert_eq!(diff.pixel(1, 0), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn size_mismatches_are_errors() {
+        let expected = image(&[[0; 4], [0; 4]]);
+        let actual = image(&[[0; 4]]);
+        assert!(matches!(
+            compare_images(&actual, &expected, 255),
+            Err(HexgemError::ImageComparison(_))
+        ));
+    }
+
+    #[test]
+    fn failed_checks_write_actual_and_diff_images() {
+        let dir = env::temp_dir().join(format!("hexgem_golden_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let golden = GoldenImage::create(dir.join("frame.png"));
+        let expected = image(&[[0, 0, 0, 255], [0, 0, 0, 255]]);
+        let actual = image(&[[0, 0, 0, 255], [9, 0, 0, 255]]);
+        // A missing golden image is written from the actual output.
+        assert!(golden.check(&expected).unwrap().is_match());
+        assert_eq!(ImageData::load(golden.get_path()).unwrap(), expected);
+
+        assert!(golden.check(&actual).is_err());
+        assert_eq!(ImageData::load(golden.get_actual_path()).unwrap(), actual);
+        let diff = ImageData::load(golden.get_diff_path()).unwrap();
+        assert_eq!(diff.pixel(1, 0), [255, 0, 0, 255]);
+        assert!(golden.get_diff_path().ends_with("frame.diff.png"));
+
+        let lenient = GoldenImage::create(dir.join("frame.png")).allowed_mismatches(1);
+        assert_eq!(lenient.check(&actual).unwrap().mismatched_pixels, 1);
+        let tolerant = GoldenImage::create(dir.join("frame.png")).tolerance(9);
+        assert!(tolerant.check(&actual).unwrap().is_match());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
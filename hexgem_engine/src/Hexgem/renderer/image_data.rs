@@ -0,0 +1,343 @@
+use std::{fs::File, path::Path, time::Duration};
+
+use glam::{Vec2, Vec4};
+use image::{
+    codecs::{
+        gif::{GifEncoder, Repeat},
+        png::PngEncoder,
+    },
+    ColorType, Delay, Frame, ImageEncoder, RgbaImage,
+};
+
+use crate::Hexgem::error::{HexgemError, HexgemResult};
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum TextureFilter {
+    Nearest,
+    #[default]
+    Linear,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum TextureWrap {
+    #[default]
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+}
+
+impl TextureWrap {
+    fn apply(&self, coord: i64, size: u32) -> u32 {
+        if size == 0 {
+            return 0;
+        }
+        let size = size as i64;
+        let coord = match self {
+            TextureWrap::Repeat => coord.rem_euclid(size),
+            TextureWrap::MirroredRepeat => {
+                let period = coord.rem_euclid(size * 2);
+                if period < size {
+                    period
+                } else {
+                    size * 2 - 1 - period
+                }
+            }
+            TextureWrap::ClampToEdge => coord.clamp(0, size - 1),
+        };
+        coord as u32
+    }
+}
+
+// RGBA8 pixels stored row by row starting with the top row of the image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageData {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl ImageData {
+    pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>) -> HexgemResult<Self> {
+        if width == 0 || height == 0 {
+            return Err(HexgemError::Texture(format!(
+                "Invalid image size {}x{}",
+                width, height
+            )));
+        }
+        let expected = width as usize * height as usize * 4;
+        if pixels.len() != expected {
+            return Err(HexgemError::Texture(format!(
+                "Expected {} bytes for a {}x{} RGBA image, got {}",
+                expected,
+                width,
+                height,
+                pixels.len()
+            )));
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    pub fn filled(width: u32, height: u32, color: [u8; 4]) -> HexgemResult<Self> {
+        let pixels = color
+            .iter()
+            .copied()
+            .cycle()
+            .take(width as usize * height as usize * 4)
+            .collect();
+        Self::from_rgba(width, height, pixels)
+    }
+
+    pub fn decode(bytes: &[u8]) -> HexgemResult<Self> {
+        let decoded = image::load_from_memory(bytes)
+            .map_err(|err| HexgemError::Texture(err.to_string()))?
+            .to_rgba8();
+        let (width, height) = decoded.dimensions();
+        Self::from_rgba(width, height, decoded.into_raw())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> HexgemResult<Self> {
+        let path = path.as_ref();
+        let decoded = image::open(path)
+            .map_err(|err| HexgemError::Texture(format!("{}: {}", path.display(), err)))?
+            .to_rgba8();
+        let (width, height) = decoded.dimensions();
+        Self::from_rgba(width, height, decoded.into_raw())
+    }
+
+    // The format is picked from the file extension.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> HexgemResult<()> {
+        let path = path.as_ref();
+        image::save_buffer(
+            path,
+            &self.pixels,
+            self.width,
+            self.height,
+            ColorType::Rgba8,
+        )
+        .map_err(|err| HexgemError::Texture(format!("{}: {}", path.display(), err)))
+    }
+
+    pub fn encode_png(&self) -> HexgemResult<Vec<u8>> {
+        let mut bytes = vec![];
+        PngEncoder::new(&mut bytes)
+            .write_image(&self.pixels, self.width, self.height, ColorType::Rgba8)
+            .map_err(|err| HexgemError::Texture(err.to_string()))?;
+        Ok(bytes)
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get_pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn set_pixels(&mut self, pixels: &[u8]) -> HexgemResult<()> {
+        if pixels.len() != self.pixels.len() {
+            return Err(HexgemError::Texture(format!(
+                "Expected {} bytes of pixel data, got {}",
+                self.pixels.len(),
+                pixels.len()
+            )));
+        }
+        self.pixels.copy_from_slice(pixels);
+        Ok(())
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        [
+            self.pixels[index],
+            self.pixels[index + 1],
+            self.pixels[index + 2],
+            self.pixels[index + 3],
+        ]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[index..index + 4].copy_from_slice(&color);
+    }
+
+    pub fn fill(&mut self, color: [u8; 4]) {
+        for pixel in self.pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&color);
+        }
+    }
+
+    // Rows bottom first, the order OpenGL expects for uploads.
+    pub fn flipped_rows(&self) -> Vec<u8> {
+        let row = self.width as usize * 4;
+        self.pixels
+            .chunks_exact(row)
+            .rev()
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    pub fn mip_level_count(&self) -> u32 {
+        32 - self.width.max(self.height).leading_zeros()
+    }
+
+    // Box filtered half size image, used to build mip chains.
+    pub fn downsample(&self) -> ImageData {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = [0u32; 4];
+                let mut count = 0;
+                for (sx, sy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let px = (x * 2 + sx).min(self.width - 1);
+                    let py = (y * 2 + sy).min(self.height - 1);
+                    let pixel = self.pixel(px, py);
+                    for channel in 0..4 {
+                        sum[channel] += pixel[channel] as u32;
+                    }
+                    count += 1;
+                }
+                pixels.extend(sum.map(|channel| (channel / count) as u8));
+            }
+        }
+        ImageData {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn mip_chain(&self) -> Vec<ImageData> {
+        let mut chain = vec![self.clone()];
+        for _ in 1..self.mip_level_count() {
+            let next = chain[chain.len() - 1].downsample();
+            chain.push(next);
+        }
+        chain
+    }
+
+    // Samples with OpenGL conventions: v = 0 is the bottom row of the image.
+    pub fn sample(
+        &self,
+        uv: Vec2,
+        filter: TextureFilter,
+        wrap: (TextureWrap, TextureWrap),
+    ) -> Vec4 {
+        let x = uv.x * self.width as f32;
+        let y = (1.0 - uv.y) * self.height as f32;
+        match filter {
+            TextureFilter::Nearest => self.texel(x.floor() as i64, y.floor() as i64, wrap),
+            TextureFilter::Linear => {
+                let x = x - 0.5;
+                let y = y - 0.5;
+                let x0 = x.floor();
+                let y0 = y.floor();
+                let tx = x - x0;
+                let ty = y - y0;
+                let (x0, y0) = (x0 as i64, y0 as i64);
+                let top = self
+                    .texel(x0, y0, wrap)
+                    .lerp(self.texel(x0 + 1, y0, wrap), tx);
+                let bottom = self
+                    .texel(x0, y0 + 1, wrap)
+                    .lerp(self.texel(x0 + 1, y0 + 1, wrap), tx);
+                top.lerp(bottom, ty)
+            }
+        }
+    }
+
+    fn texel(&self, x: i64, y: i64, (wrap_s, wrap_t): (TextureWrap, TextureWrap)) -> Vec4 {
+        let pixel = self.pixel(wrap_s.apply(x, self.width), wrap_t.apply(y, self.height));
+        Vec4::new(
+            pixel[0] as f32,
+            pixel[1] as f32,
+            pixel[2] as f32,
+            pixel[3] as f32,
+        ) / 255.0
+    }
+}
+
+// Writes a looping animation, every frame is shown for frame_delay.
+pub fn save_gif<P: AsRef<Path>>(
+    frames: &[ImageData],
+    frame_delay: Duration,
+    path: P,
+) -> HexgemResult<()> {
+    let path = path.as_ref();
+    let error = |err: String| HexgemError::Texture(format!("{}: {}", path.display(), err));
+    let file = File::create(path).map_err(|err| error(err.to_string()))?;
+    let mut encoder = GifEncoder::new(file);
+    encoder
+        .set_repeat(Repeat::Infinite)
+        .map_err(|err| error(err.to_string()))?;
+    let frames = frames.iter().filter_map(|frame| {
+        RgbaImage::from_raw(frame.width, frame.height, frame.pixels.clone()).map(|buffer| {
+            Frame::from_parts(buffer, 0, 0, Delay::from_saturating_duration(frame_delay))
+        })
+    });
+    encoder
+        .encode_frames(frames)
+        .map_err(|err| error(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_modes() {
+        assert_eq!(TextureWrap::Repeat.apply(-1, 4), 3);
+        assert_eq!(TextureWrap::Repeat.apply(9, 4), 1);
+        assert_eq!(TextureWrap::MirroredRepeat.apply(4, 4), 3);
+        assert_eq!(TextureWrap::MirroredRepeat.apply(-1, 4), 0);
+        assert_eq!(TextureWrap::ClampToEdge.apply(-5, 4), 0);
+        assert_eq!(TextureWrap::ClampToEdge.apply(7, 4), 3);
+        for wrap in [
+            TextureWrap::Repeat,
+            TextureWrap::MirroredRepeat,
+            TextureWrap::ClampToEdge,
+        ] {
+            assert_eq!(wrap.apply(3, 0), 0);
+        }
+    }
+
+    #[test]
+    fn from_rgba_validates_size() {
+        assert!(ImageData::from_rgba(0, 1, vec![]).is_err());
+        assert!(ImageData::from_rgba(2, 1, vec![0; 4]).is_err());
+        assert!(ImageData::from_rgba(2, 1, vec![0; 8]).is_ok());
+    }
+
+    #[test]
+    fn filled_rejects_empty_images() {
+        assert!(ImageData::filled(0, 4, [255; 4]).is_err());
+        assert!(ImageData::filled(4, 0, [255; 4]).is_err());
+        let mut image = ImageData::filled(3, 2, [1, 2, 3, 4]).unwrap();
+        assert_eq!(image.get_pixels().len(), 24);
+        assert_eq!(image.pixel(2, 1), [1, 2, 3, 4]);
+        image.fill([9; 4]);
+        assert!(image.get_pixels().iter().all(|channel| *channel == 9));
+    }
+
+    #[test]
+    fn mip_chain_ends_at_one_pixel() {
+        let image = ImageData::filled(5, 1, [200, 100, 50, 255]).unwrap();
+        let chain = image.mip_chain();
+        let sizes: Vec<_> = chain
+            .iter()
+            .map(|level| (level.get_width(), level.get_height()))
+            .collect();
+        assert_eq!(sizes, [(5, 1), (2, 1), (1, 1)]);
+        assert_eq!(chain[2].pixel(0, 0), [200, 100, 50, 255]);
+    }
+}
@@ -0,0 +1,78 @@
+mod buffer;
+mod camera;
+mod cpu_bindings;
+mod cpu_buffer;
+mod cpu_framebuffer;
+mod cpu_shader;
+mod cpu_texture;
+mod fly_camera_controller;
+mod font;
+mod framebuffer;
+mod golden_image;
+mod image_data;
+mod null_renderer_api;
+mod orbit_camera_controller;
+mod orthographic_camera;
+mod orthographic_camera_controller;
+mod perspective_camera;
+mod render_command;
+mod renderer_2d;
+mod renderer_api;
+mod shader;
+mod shader_source;
+mod text;
+mod texture;
+mod vertex_array;
+
+pub(crate) use cpu_bindings::CpuBindings;
+pub(crate) use cpu_framebuffer::CpuRenderTarget;
+
+pub mod HexgemRenderer {
+    pub use super::buffer::{
+        as_bytes, BufferElement, BufferLayout, IndexBuffer, ShaderDataType, VertexAttribute,
+        VertexBuffer, VertexData,
+    };
+    pub use super::camera::Camera;
+    pub use super::cpu_buffer::{CpuIndexBuffer, CpuVertexArray, CpuVertexBuffer};
+    pub use super::cpu_framebuffer::CpuFramebuffer;
+    pub use super::cpu_shader::CpuShader;
+    pub use super::cpu_texture::CpuTexture;
+    pub use super::fly_camera_controller::FlyCameraController;
+    pub use super::font::{Font, FontOptions, Glyph};
+    pub use super::framebuffer::{
+        AttachmentPixels, AttachmentValue, Framebuffer, FramebufferHandle, FramebufferSpec,
+        FramebufferTextureFormat, MAX_COLOR_ATTACHMENTS, MAX_FRAMEBUFFER_SIZE,
+    };
+    pub use super::golden_image::{
+        compare_images, GoldenImage, ImageComparison, UPDATE_GOLDEN_ENV,
+    };
+    pub use super::image_data::{save_gif, ImageData, TextureFilter, TextureWrap};
+    pub use super::null_renderer_api::{NullRendererApi, RecordingRendererApi, RenderCall};
+    pub use super::orbit_camera_controller::OrbitCameraController;
+    pub use super::orthographic_camera::OrthographicCamera;
+    pub use super::orthographic_camera_controller::OrthographicCameraController;
+    pub use super::perspective_camera::PerspectiveCamera;
+    pub use super::render_command::RenderCommand;
+    pub use super::renderer_2d::{
+        CircleVertex, LineVertex, Quad, QuadVertex, Renderer2D, Renderer2DConfig, Renderer2DStats,
+    };
+    pub use super::renderer_api::{GraphicsApi, RendererApi};
+    pub use super::shader::{Shader, ShaderLibrary, UniformValue};
+    pub use super::shader_source::{
+        parse_log_line, ShaderSource, ShaderStage, ShaderStageSource, SourceLine,
+    };
+    pub use super::text::{PositionedGlyph, TextAlign, TextLayout, TextOptions};
+    pub use super::texture::{
+        pixel_rect_to_uv, rect_tex_coords, SubTexture2D, Texture2D, TextureAtlas, TextureHandle,
+        TextureOptions,
+    };
+    pub use super::vertex_array::VertexArray;
+    pub use crate::Hexgem::platform::OpenGlPlatform::{
+        OpenGlFramebuffer, OpenGlIndexBuffer, OpenGlRendererApi, OpenGlShader, OpenGlTexture,
+        OpenGlVertexArray, OpenGlVertexBuffer,
+    };
+    pub use crate::Hexgem::platform::SoftwarePlatform::{
+        get_mat4, CircleProgram, FlatColorProgram, FragmentContext, LineProgram, QuadProgram,
+        ShadedVertex, SoftwareProgram, SoftwareRendererApi, SoftwareSurface, Uniforms, VertexInput,
+    };
+}
@@ -0,0 +1,344 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use glam::Vec4;
+use log::trace;
+
+use super::{
+    buffer::{IndexBuffer, VertexBuffer},
+    cpu_buffer::{CpuIndexBuffer, CpuVertexArray, CpuVertexBuffer},
+    cpu_framebuffer::CpuFramebuffer,
+    cpu_shader::CpuShader,
+    cpu_texture::CpuTexture,
+    framebuffer::{AttachmentValue, FramebufferHandle, FramebufferSpec},
+    image_data::ImageData,
+    renderer_api::{GraphicsApi, RendererApi},
+    shader::{Shader, UniformValue},
+    shader_source::ShaderSource,
+    texture::{TextureHandle, TextureOptions},
+    vertex_array::VertexArray,
+};
+use crate::Hexgem::error::HexgemResult;
+
+pub struct NullRendererApi;
+
+impl RendererApi for NullRendererApi {
+    fn get_api(&self) -> GraphicsApi {
+        GraphicsApi::None
+    }
+
+    fn set_clear_color(&mut self, color: Vec4) {}
+
+    fn clear(&mut self) {}
+
+    fn set_viewport(&mut self, x: i32, y: i32, width: u32, height: u32) {}
+
+    fn draw_indexed(&mut self, vertex_array: &dyn VertexArray, index_count: Option<u32>) {}
+
+    fn draw_lines(&mut self, vertex_array: &dyn VertexArray, vertex_count: u32) {}
+
+    fn set_line_width(&mut self, width: f32) {}
+
+    fn create_vertex_buffer(&mut self, size: usize, data: Option<&[u8]>) -> Box<dyn VertexBuffer> {
+        Box::new(CpuVertexBuffer::create(size, data, None))
+    }
+
+    fn create_index_buffer(&mut self, indices: &[u32]) -> Box<dyn IndexBuffer> {
+        Box::new(CpuIndexBuffer::create(indices, None))
+    }
+
+    fn create_vertex_array(&mut self) -> Box<dyn VertexArray> {
+        Box::new(CpuVertexArray::create(None))
+    }
+
+    fn create_shader(&mut self, source: &ShaderSource) -> HexgemResult<Box<dyn Shader>> {
+        Ok(Box::new(CpuShader::create(source, None)))
+    }
+
+    fn create_texture(
+        &mut self,
+        image: &ImageData,
+        options: &TextureOptions,
+    ) -> HexgemResult<Box<dyn TextureHandle>> {
+        Ok(Box::new(CpuTexture::create(image, options, None)))
+    }
+
+    fn create_framebuffer(
+        &mut self,
+        spec: &FramebufferSpec,
+    ) -> HexgemResult<Box<dyn FramebufferHandle>> {
+        Ok(Box::new(CpuFramebuffer::create(spec, None)))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RenderCall {
+    SetClearColor(Vec4),
+    Clear,
+    SetViewport {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    },
+    CreateVertexBuffer {
+        id: u32,
+        size: usize,
+    },
+    SetVertexBufferData {
+        id: u32,
+        data: Vec<u8>,
+    },
+    CreateIndexBuffer {
+        id: u32,
+        count: u32,
+    },
+    CreateVertexArray {
+        id: u32,
+    },
+    BindVertexArray {
+        id: u32,
+    },
+    VertexAttribute {
+        vertex_array: u32,
+        vertex_buffer: u32,
+        index: u32,
+        components: u32,
+        integer: bool,
+        normalized: bool,
+        stride: u32,
+        offset: u32,
+    },
+    SetIndexBuffer {
+        vertex_array: u32,
+        index_buffer: u32,
+    },
+    CreateShader {
+        id: u32,
+        name: String,
+    },
+    BindShader {
+        id: u32,
+    },
+    SetUniform {
+        shader: u32,
+        name: String,
+        value: UniformValue,
+    },
+    CreateTexture {
+        id: u32,
+        width: u32,
+        height: u32,
+        mipmaps: bool,
+    },
+    BindTexture {
+        id: u32,
+        slot: u32,
+    },
+    SetTextureData {
+        id: u32,
+        size: usize,
+    },
+    DrawIndexed {
+        vertex_array: u32,
+        index_count: u32,
+    },
+    DrawLines {
+        vertex_array: u32,
+        vertex_count: u32,
+    },
+    SetLineWidth(f32),
+    ReadPixels {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    },
+    CreateFramebuffer {
+        id: u32,
+        width: u32,
+        height: u32,
+        samples: u32,
+    },
+    BindFramebuffer {
+        id: u32,
+    },
+    ResizeFramebuffer {
+        id: u32,
+        width: u32,
+        height: u32,
+    },
+    ResolveFramebuffer {
+        id: u32,
+    },
+    ReadFramebuffer {
+        id: u32,
+        attachment: usize,
+    },
+    ClearAttachment {
+        id: u32,
+        attachment: usize,
+        value: AttachmentValue,
+    },
+    BindFramebufferAttachment {
+        id: u32,
+        attachment: usize,
+        slot: u32,
+    },
+}
+
+#[derive(Clone, Default)]
+pub struct CallRecorder {
+    calls: Rc<RefCell<Vec<RenderCall>>>,
+    next_id: Rc<Cell<u32>>,
+}
+
+impl CallRecorder {
+    pub fn record(&self, call: RenderCall) {
+        trace!("{:?}", call);
+        self.calls.borrow_mut().push(call);
+    }
+
+    pub fn next_id(&self) -> u32 {
+        let id = self.next_id.get() + 1;
+        self.next_id.set(id);
+        id
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct RecordingRendererApi {
+    recorder: CallRecorder,
+    clear_color: Vec4,
+}
+
+impl RecordingRendererApi {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn calls(&self) -> Vec<RenderCall> {
+        self.recorder.calls.borrow().clone()
+    }
+
+    pub fn clear_calls(&self) {
+        self.recorder.calls.borrow_mut().clear();
+    }
+}
+
+impl RendererApi for RecordingRendererApi {
+    fn get_api(&self) -> GraphicsApi {
+        GraphicsApi::None
+    }
+
+    fn set_clear_color(&mut self, color: Vec4) {
+        self.clear_color = color;
+        self.recorder.record(RenderCall::SetClearColor(color));
+    }
+
+    fn clear(&mut self) {
+        self.recorder.record(RenderCall::Clear);
+    }
+
+    fn set_viewport(&mut self, x: i32, y: i32, width: u32, height: u32) {
+        self.recorder.record(RenderCall::SetViewport {
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+
+    fn draw_indexed(&mut self, vertex_array: &dyn VertexArray, index_count: Option<u32>) {
+        let id = vertex_array_id(vertex_array);
+        let count = vertex_array
+            .get_index_buffer()
+            .map_or(0, |ib| ib.get_count());
+        vertex_array.bind();
+        self.recorder.record(RenderCall::DrawIndexed {
+            vertex_array: id,
+            index_count: index_count.unwrap_or(count),
+        });
+    }
+
+    fn draw_lines(&mut self, vertex_array: &dyn VertexArray, vertex_count: u32) {
+        vertex_array.bind();
+        self.recorder.record(RenderCall::DrawLines {
+            vertex_array: vertex_array_id(vertex_array),
+            vertex_count,
+        });
+    }
+
+    fn set_line_width(&mut self, width: f32) {
+        self.recorder.record(RenderCall::SetLineWidth(width));
+    }
+
+    fn create_vertex_buffer(&mut self, size: usize, data: Option<&[u8]>) -> Box<dyn VertexBuffer> {
+        Box::new(CpuVertexBuffer::create(
+            size,
+            data,
+            Some(self.recorder.clone()),
+        ))
+    }
+
+    fn create_index_buffer(&mut self, indices: &[u32]) -> Box<dyn IndexBuffer> {
+        Box::new(CpuIndexBuffer::create(indices, Some(self.recorder.clone())))
+    }
+
+    fn create_vertex_array(&mut self) -> Box<dyn VertexArray> {
+        Box::new(CpuVertexArray::create(Some(self.recorder.clone())))
+    }
+
+    fn create_shader(&mut self, source: &ShaderSource) -> HexgemResult<Box<dyn Shader>> {
+        Ok(Box::new(CpuShader::create(
+            source,
+            Some(self.recorder.clone()),
+        )))
+    }
+
+    fn create_texture(
+        &mut self,
+        image: &ImageData,
+        options: &TextureOptions,
+    ) -> HexgemResult<Box<dyn TextureHandle>> {
+        Ok(Box::new(CpuTexture::create(
+            image,
+            options,
+            Some(self.recorder.clone()),
+        )))
+    }
+
+    fn create_framebuffer(
+        &mut self,
+        spec: &FramebufferSpec,
+    ) -> HexgemResult<Box<dyn FramebufferHandle>> {
+        Ok(Box::new(CpuFramebuffer::create(
+            spec,
+            Some(self.recorder.clone()),
+        )))
+    }
+
+    // Nothing is rasterized, so the read back frame is the clear color.
+    fn read_pixels(&mut self, x: i32, y: i32, width: u32, height: u32) -> HexgemResult<ImageData> {
+        self.recorder.record(RenderCall::ReadPixels {
+            x,
+            y,
+            width,
+            height,
+        });
+        let color = (self.clear_color.clamp(Vec4::ZERO, Vec4::ONE) * 255.0)
+            .round()
+            .to_array()
+            .map(|channel| channel as u8);
+        ImageData::filled(width, height, color)
+    }
+}
+
+fn vertex_array_id(vertex_array: &dyn VertexArray) -> u32 {
+    vertex_array
+        .as_any()
+        .downcast_ref::<CpuVertexArray>()
+        .map_or(0, |vertex_array| vertex_array.get_id())
+}
@@ -0,0 +1,225 @@
+use glam::Vec3;
+
+use crate::{
+    Hexgem::{input::InputState, layer::Layer, timestep::Timestep, window::Window},
+    HexgemEvent::{Event, EventDispatcher, EventType, Key, MouseButton, WindowResizeEvent},
+};
+
+use super::perspective_camera::PerspectiveCamera;
+
+const MAX_PITCH: f32 = 89.0 * std::f32::consts::PI / 180.0;
+
+pub struct OrbitCameraController {
+    camera: PerspectiveCamera,
+    input: InputState,
+    target: Vec3,
+    distance: f32,
+    yaw: f32,
+    pitch: f32,
+    sensitivity: f32,
+    key_speed: f32,
+    zoom_speed: f32,
+    min_distance: f32,
+    max_distance: f32,
+}
+
+impl OrbitCameraController {
+    pub fn create(camera: PerspectiveCamera, target: Vec3, distance: f32) -> Self {
+        let mut controller = Self {
+            camera,
+            input: InputState::create(),
+            target,
+            distance,
+            yaw: 0.0,
+            pitch: 0.0,
+            sensitivity: 0.005,
+            key_speed: std::f32::consts::FRAC_PI_2,
+            zoom_speed: 0.1,
+            min_distance: 0.1,
+            max_distance: 1000.0,
+        };
+        controller.apply();
+        controller
+    }
+
+    pub fn get_camera(&self) -> &PerspectiveCamera {
+        &self.camera
+    }
+
+    pub fn get_target(&self) -> Vec3 {
+        self.target
+    }
+
+    pub fn set_target(&mut self, target: Vec3) {
+        self.target = target;
+        self.apply();
+    }
+
+    pub fn get_distance(&self) -> f32 {
+        self.distance
+    }
+
+    pub fn set_distance(&mut self, distance: f32) {
+        self.distance = distance.clamp(self.min_distance, self.max_distance);
+        self.apply();
+    }
+
+    pub fn set_distance_limits(&mut self, min_distance: f32, max_distance: f32) {
+        self.min_distance = min_distance;
+        self.max_distance = max_distance;
+        self.set_distance(self.distance);
+    }
+
+    pub fn set_orbit(&mut self, yaw: f32, pitch: f32) {
+        self.yaw = yaw;
+        self.pitch = pitch.clamp(-MAX_PITCH, MAX_PITCH);
+        self.apply();
+    }
+
+    pub fn on_update(&mut self, timestep: Timestep) {
+        let delta = self.input.take_mouse_delta();
+        let scroll = self.input.take_scroll_delta();
+        let mut yaw = self.yaw;
+        let mut pitch = self.pitch;
+
+        if self.input.is_mouse_button_pressed(MouseButton::LeftButton) {
+            yaw -= delta.x * self.sensitivity;
+            pitch += delta.y * self.sensitivity;
+        }
+        if self
+            .input
+            .is_mouse_button_pressed(MouseButton::MiddleButton)
+        {
+            let scale = self.distance * self.sensitivity * 0.2;
+            self.target += (-self.camera.right() * delta.x + self.camera.up() * delta.y) * scale;
+        }
+
+        let turn = self.key_speed * timestep.get_seconds();
+        if self.input.is_key_pressed(Key::Left) {
+            yaw += turn;
+        }
+        if self.input.is_key_pressed(Key::Right) {
+            yaw -= turn;
+        }
+        if self.input.is_key_pressed(Key::Up) {
+            pitch += turn;
+        }
+        if self.input.is_key_pressed(Key::Down) {
+            pitch -= turn;
+        }
+
+        // Each scroll step dollies a fixed fraction of the distance.
+        self.distance = (self.distance * (1.0 - self.zoom_speed).powf(scroll.y))
+            .clamp(self.min_distance, self.max_distance);
+        self.set_orbit(yaw, pitch);
+    }
+
+    pub fn on_event(&mut self, event: &mut Box<dyn Event>) {
+        self.input.on_event(event.as_ref());
+        let event_dispatcher = EventDispatcher::from(event);
+        event_dispatcher.dispatch::<WindowResizeEvent, _>(EventType::WindowResize, |e| {
+            if e.size.width > 0 && e.size.height > 0 {
+                self.camera
+                    .set_aspect_ratio(e.size.width as f32 / e.size.height as f32);
+            }
+            Some(false)
+        });
+    }
+
+    fn apply(&mut self) {
+        let offset = Vec3::new(
+            self.pitch.cos() * self.yaw.sin(),
+            self.pitch.sin(),
+            self.pitch.cos() * self.yaw.cos(),
+        ) * self.distance;
+        self.camera.set_position(self.target + offset);
+        self.camera.look_at(self.target);
+    }
+}
+
+impl Layer for OrbitCameraController {
+    fn get_name(&self) -> &'static str {
+        "OrbitCameraController"
+    }
+
+    fn on_update(&mut self, window: &mut Box<dyn Window>, timestep: Timestep) {
+        OrbitCameraController::on_update(self, timestep);
+    }
+
+    fn on_event(&mut self, event: &mut Box<dyn Event>, window: &mut dyn Window) {
+        OrbitCameraController::on_event(self, event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::{Hexgem::core::Size, HexgemEvent::MouseScrollEvent};
+
+    fn controller(distance: f32) -> OrbitCameraController {
+        let camera = PerspectiveCamera::create(FRAC_PI_2, 1.0, 0.1, 100.0);
+        OrbitCameraController::create(camera, Vec3::ZERO, distance)
+    }
+
+    fn send(controller: &mut OrbitCameraController, event: impl Event + 'static) {
+        let mut event: Box<dyn Event> = Box::new(event);
+        controller.on_event(&mut event);
+    }
+
+    #[test]
+    fn camera_orbits_the_target() {
+        let mut controller = controller(5.0);
+        assert!(controller
+            .get_camera()
+            .get_position()
+            .abs_diff_eq(Vec3::new(0.0, 0.0, 5.0), 1e-5));
+        controller.set_target(Vec3::new(1.0, 2.0, 3.0));
+        controller.set_orbit(FRAC_PI_2, 0.0);
+        let camera = controller.get_camera();
+        assert!(camera
+            .get_position()
+            .abs_diff_eq(Vec3::new(6.0, 2.0, 3.0), 1e-5));
+        assert!(camera.forward().abs_diff_eq(Vec3::NEG_X, 1e-5));
+    }
+
+    #[test]
+    fn scrolling_dollies_within_limits() {
+        let mut controller = controller(10.0);
+        send(&mut controller, MouseScrollEvent::create(0.0, 1.0));
+        controller.on_update(Timestep::create(0.016));
+        assert!((controller.get_distance() - 9.0).abs() < 1e-5);
+
+        controller.set_distance_limits(2.0, 20.0);
+        send(&mut controller, MouseScrollEvent::create(0.0, 100.0));
+        controller.on_update(Timestep::create(0.016));
+        assert_eq!(controller.get_distance(), 2.0);
+        send(&mut controller, MouseScrollEvent::create(0.0, -100.0));
+        controller.on_update(Timestep::create(0.016));
+        assert_eq!(controller.get_distance(), 20.0);
+        let position = controller.get_camera().get_position();
+        assert!((position.length() - 20.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn resize_updates_the_aspect_ratio() {
+        let mut controller = controller(5.0);
+        send(
+            &mut controller,
+            WindowResizeEvent::create(Size {
+                width: 800,
+                height: 600,
+            }),
+        );
+        assert_eq!(controller.get_camera().get_aspect_ratio(), 800.0 / 600.0);
+        send(
+            &mut controller,
+            WindowResizeEvent::create(Size {
+                width: 800,
+                height: 0,
+            }),
+        );
+        assert_eq!(controller.get_camera().get_aspect_ratio(), 800.0 / 600.0);
+    }
+}
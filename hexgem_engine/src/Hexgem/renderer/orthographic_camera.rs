@@ -0,0 +1,147 @@
+use glam::{Mat4, Vec2, Vec3};
+
+use crate::Hexgem::math::HexgemMath::orthographic;
+
+use super::camera::Camera;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrthographicCamera {
+    projection: Mat4,
+    view: Mat4,
+    view_projection: Mat4,
+    position: Vec3,
+    rotation: f32,
+}
+
+impl OrthographicCamera {
+    pub fn create(left: f32, right: f32, bottom: f32, top: f32) -> Self {
+        let projection = orthographic(left, right, bottom, top, -1.0, 1.0);
+        Self {
+            projection,
+            view: Mat4::IDENTITY,
+            view_projection: projection,
+            position: Vec3::ZERO,
+            rotation: 0.0,
+        }
+    }
+
+    pub fn set_projection(&mut self, left: f32, right: f32, bottom: f32, top: f32) {
+        self.projection = orthographic(left, right, bottom, top, -1.0, 1.0);
+        self.view_projection = self.projection * self.view;
+    }
+
+    pub fn get_position(&self) -> Vec3 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Vec3) {
+        self.position = position;
+        self.recalculate_view();
+    }
+
+    // Radians around the z axis.
+    pub fn get_rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    pub fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation;
+        self.recalculate_view();
+    }
+
+    pub fn get_projection(&self) -> Mat4 {
+        self.projection
+    }
+
+    pub fn get_view(&self) -> Mat4 {
+        self.view
+    }
+
+    // Maps a point in window pixels (origin top left) into world space.
+    pub fn screen_to_world(&self, screen: Vec2, viewport: Vec2) -> Vec2 {
+        let ndc = Vec2::new(
+            screen.x / viewport.x * 2.0 - 1.0,
+            1.0 - screen.y / viewport.y * 2.0,
+        );
+        self.view_projection
+            .inverse()
+            .project_point3(ndc.extend(0.0))
+            .truncate()
+    }
+
+    fn recalculate_view(&mut self) {
+        let transform =
+            Mat4::from_translation(self.position) * Mat4::from_rotation_z(self.rotation);
+        self.view = transform.inverse();
+        self.view_projection = self.projection * self.view;
+    }
+}
+
+impl Camera for OrthographicCamera {
+    fn get_view_projection(&self) -> Mat4 {
+        self.view_projection
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use glam::Vec4Swizzles;
+
+    use super::*;
+
+    fn to_clip(camera: &OrthographicCamera, point: Vec3) -> Vec2 {
+        (camera.get_view_projection() * point.extend(1.0)).xy()
+    }
+
+    #[test]
+    fn starts_as_the_projection() {
+        let camera = OrthographicCamera::create(-2.0, 2.0, -1.0, 1.0);
+        assert_eq!(camera.get_view(), Mat4::IDENTITY);
+        assert_eq!(camera.get_view_projection(), camera.get_projection());
+        assert!(to_clip(&camera, Vec3::new(2.0, 1.0, 0.0)).abs_diff_eq(Vec2::ONE, 1e-6));
+    }
+
+    #[test]
+    fn position_moves_the_view() {
+        let mut camera = OrthographicCamera::create(-2.0, 2.0, -1.0, 1.0);
+        camera.set_position(Vec3::new(1.0, 0.5, 0.0));
+        assert_eq!(camera.get_position(), Vec3::new(1.0, 0.5, 0.0));
+        assert!(to_clip(&camera, Vec3::new(1.0, 0.5, 0.0)).abs_diff_eq(Vec2::ZERO, 1e-6));
+        assert!(to_clip(&camera, Vec3::new(3.0, 1.5, 0.0)).abs_diff_eq(Vec2::ONE, 1e-6));
+        assert!(camera
+            .get_view_projection()
+            .abs_diff_eq(camera.get_projection() * camera.get_view(), 1e-6));
+    }
+
+    #[test]
+    fn rotation_turns_the_view() {
+        let mut camera = OrthographicCamera::create(-1.0, 1.0, -1.0, 1.0);
+        camera.set_rotation(FRAC_PI_2);
+        assert_eq!(camera.get_rotation(), FRAC_PI_2);
+        // A camera turned a quarter counter clockwise sees +y on its right.
+        assert!(to_clip(&camera, Vec3::Y).abs_diff_eq(Vec2::X, 1e-6));
+        camera.set_position(Vec3::new(0.0, 1.0, 0.0));
+        assert!(to_clip(&camera, Vec3::new(0.0, 2.0, 0.0)).abs_diff_eq(Vec2::X, 1e-6));
+    }
+
+    #[test]
+    fn set_projection_keeps_the_view() {
+        let mut camera = OrthographicCamera::create(-1.0, 1.0, -1.0, 1.0);
+        camera.set_position(Vec3::new(1.0, 0.0, 0.0));
+        camera.set_projection(-4.0, 4.0, -2.0, 2.0);
+        assert!(to_clip(&camera, Vec3::new(5.0, 2.0, 0.0)).abs_diff_eq(Vec2::ONE, 1e-6));
+    }
+
+    #[test]
+    fn screen_to_world_inverts_the_camera() {
+        let mut camera = OrthographicCamera::create(-2.0, 2.0, -1.0, 1.0);
+        camera.set_position(Vec3::new(10.0, 0.0, 0.0));
+        let viewport = Vec2::new(400.0, 200.0);
+        let center = camera.screen_to_world(Vec2::new(200.0, 100.0), viewport);
+        assert!(center.abs_diff_eq(Vec2::new(10.0, 0.0), 1e-5));
+        let top_left = camera.screen_to_world(Vec2::ZERO, viewport);
+        assert!(top_left.abs_diff_eq(Vec2::new(8.0, 1.0), 1e-5));
+    }
+}
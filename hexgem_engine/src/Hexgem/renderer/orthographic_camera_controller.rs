@@ -0,0 +1,246 @@
+use glam::{Vec2, Vec3};
+
+use crate::{
+    Hexgem::{
+        input::InputState, layer::Layer, math::HexgemMath::Rect, timestep::Timestep, window::Window,
+    },
+    HexgemEvent::{Event, EventDispatcher, EventType, Key, MouseScrollEvent, WindowResizeEvent},
+};
+
+use super::orthographic_camera::OrthographicCamera;
+
+const ZOOM_STEP: f32 = 0.25;
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 100.0;
+
+pub struct OrthographicCameraController {
+    aspect_ratio: f32,
+    zoom_level: f32,
+    camera: OrthographicCamera,
+    rotation_enabled: bool,
+    position: Vec3,
+    rotation: f32,
+    rotation_speed: f32,
+    input: InputState,
+}
+
+impl OrthographicCameraController {
+    pub fn create(aspect_ratio: f32, rotation_enabled: bool) -> Self {
+        let zoom_level = 1.0;
+        Self {
+            aspect_ratio,
+            zoom_level,
+            camera: OrthographicCamera::create(
+                -aspect_ratio * zoom_level,
+                aspect_ratio * zoom_level,
+                -zoom_level,
+                zoom_level,
+            ),
+            rotation_enabled,
+            position: Vec3::ZERO,
+            rotation: 0.0,
+            rotation_speed: std::f32::consts::PI,
+            input: InputState::create(),
+        }
+    }
+
+    pub fn get_camera(&self) -> &OrthographicCamera {
+        &self.camera
+    }
+
+    pub fn get_zoom_level(&self) -> f32 {
+        self.zoom_level
+    }
+
+    pub fn set_zoom_level(&mut self, zoom_level: f32) {
+        self.zoom_level = zoom_level.clamp(MIN_ZOOM, MAX_ZOOM);
+        self.update_projection();
+    }
+
+    pub fn get_aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
+
+    pub fn set_position(&mut self, position: Vec3) {
+        self.position = position;
+        self.camera.set_position(position);
+    }
+
+    // Visible world area centred on the camera position.
+    pub fn get_bounds(&self) -> Rect {
+        Rect::from_center_size(
+            self.position.truncate(),
+            Vec2::new(self.aspect_ratio, 1.0) * self.zoom_level * 2.0,
+        )
+    }
+
+    // Pans faster when zoomed out so the screen space speed stays the same.
+    pub fn get_translation_speed(&self) -> f32 {
+        self.zoom_level
+    }
+
+    pub fn on_update(&mut self, timestep: Timestep) {
+        let distance = self.get_translation_speed() * timestep.get_seconds();
+        let right = Vec3::new(self.rotation.cos(), self.rotation.sin(), 0.0);
+        let up = Vec3::new(-self.rotation.sin(), self.rotation.cos(), 0.0);
+        let input = &self.input;
+        let held = |keys: [Key; 2]| keys.iter().any(|key| input.is_key_pressed(*key));
+
+        let mut direction = Vec3::ZERO;
+        if held([Key::A, Key::Left]) {
+            direction -= right;
+        }
+        if held([Key::D, Key::Right]) {
+            direction += right;
+        }
+        if held([Key::W, Key::Up]) {
+            direction += up;
+        }
+        if held([Key::S, Key::Down]) {
+            direction -= up;
+        }
+        self.position += direction * distance;
+
+        if self.rotation_enabled {
+            let mut turn = 0.0;
+            if input.is_key_pressed(Key::Q) {
+                turn += 1.0;
+            }
+            if input.is_key_pressed(Key::E) {
+                turn -= 1.0;
+            }
+            self.rotation += turn * self.rotation_speed * timestep.get_seconds();
+            self.camera.set_rotation(self.rotation);
+        }
+        self.camera.set_position(self.position);
+    }
+
+    pub fn on_event(&mut self, event: &mut Box<dyn Event>) {
+        self.input.on_event(event.as_ref());
+        let event_dispatcher = EventDispatcher::from(event);
+        event_dispatcher.dispatch::<MouseScrollEvent, _>(EventType::MouseScrolled, |e| {
+            self.set_zoom_level(self.zoom_level - e.dy as f32 * ZOOM_STEP);
+            Some(false)
+        });
+        event_dispatcher.dispatch::<WindowResizeEvent, _>(EventType::WindowResize, |e| {
+            self.on_resize(e.size.width as f32, e.size.height as f32);
+            Some(false)
+        });
+    }
+
+    pub fn on_resize(&mut self, width: f32, height: f32) {
+        if width <= 0.0 || height <= 0.0 {
+            return;
+        }
+        self.aspect_ratio = width / height;
+        self.update_projection();
+    }
+
+    fn update_projection(&mut self) {
+        self.camera.set_projection(
+            -self.aspect_ratio * self.zoom_level,
+            self.aspect_ratio * self.zoom_level,
+            -self.zoom_level,
+            self.zoom_level,
+        );
+    }
+}
+
+impl Layer for OrthographicCameraController {
+    fn get_name(&self) -> &'static str {
+        "OrthographicCameraController"
+    }
+
+    fn on_update(&mut self, window: &mut Box<dyn Window>, timestep: Timestep) {
+        OrthographicCameraController::on_update(self, timestep);
+    }
+
+    fn on_event(&mut self, event: &mut Box<dyn Event>, window: &mut dyn Window) {
+        OrthographicCameraController::on_event(self, event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Hexgem::core::Size,
+        HexgemEvent::{KeyboardEvent, Modifiers},
+    };
+
+    fn send(controller: &mut OrthographicCameraController, event: impl Event + 'static) {
+        let mut event: Box<dyn Event> = Box::new(event);
+        controller.on_event(&mut event);
+    }
+
+    #[test]
+    fn zoom_is_clamped() {
+        let mut controller = OrthographicCameraController::create(2.0, false);
+        controller.set_zoom_level(0.0);
+        assert_eq!(controller.get_zoom_level(), MIN_ZOOM);
+        controller.set_zoom_level(1000.0);
+        assert_eq!(controller.get_zoom_level(), MAX_ZOOM);
+        controller.set_zoom_level(3.0);
+        assert_eq!(controller.get_zoom_level(), 3.0);
+    }
+
+    #[test]
+    fn scrolling_zooms_within_limits() {
+        let mut controller = OrthographicCameraController::create(1.0, false);
+        send(&mut controller, MouseScrollEvent::create(0.0, 2.0));
+        assert_eq!(controller.get_zoom_level(), 1.0 - 2.0 * ZOOM_STEP);
+        for _ in 0..20 {
+            send(&mut controller, MouseScrollEvent::create(0.0, 1.0));
+        }
+        assert_eq!(controller.get_zoom_level(), MIN_ZOOM);
+        send(&mut controller, MouseScrollEvent::create(0.0, -10000.0));
+        assert_eq!(controller.get_zoom_level(), MAX_ZOOM);
+    }
+
+    #[test]
+    fn zoom_updates_the_projection() {
+        let mut controller = OrthographicCameraController::create(2.0, false);
+        controller.set_zoom_level(2.0);
+        let expected = Rect::from_min_max(Vec2::new(-4.0, -2.0), Vec2::new(4.0, 2.0));
+        assert_eq!(controller.get_bounds(), expected);
+        let corner = controller
+            .get_camera()
+            .get_projection()
+            .project_point3(Vec3::new(4.0, 2.0, 0.0));
+        assert!(corner.truncate().abs_diff_eq(Vec2::ONE, 1e-6));
+    }
+
+    #[test]
+    fn resize_updates_the_aspect_ratio() {
+        let mut controller = OrthographicCameraController::create(1.0, false);
+        send(
+            &mut controller,
+            WindowResizeEvent::create(Size {
+                width: 1280,
+                height: 720,
+            }),
+        );
+        assert_eq!(controller.get_aspect_ratio(), 1280.0 / 720.0);
+        let corner = controller
+            .get_camera()
+            .get_projection()
+            .project_point3(Vec3::new(1280.0 / 720.0, 1.0, 0.0));
+        assert!(corner.truncate().abs_diff_eq(Vec2::ONE, 1e-6));
+        // Minimized windows report a zero size, the last aspect ratio is kept.
+        controller.on_resize(0.0, 0.0);
+        assert_eq!(controller.get_aspect_ratio(), 1280.0 / 720.0);
+    }
+
+    #[test]
+    fn keys_pan_the_camera() {
+        let mut controller = OrthographicCameraController::create(1.0, false);
+        controller.set_zoom_level(2.0);
+        send(
+            &mut controller,
+            KeyboardEvent::create(true, Key::D, false, Modifiers::create(vec![])),
+        );
+        controller.on_update(Timestep::from(std::time::Duration::from_millis(500)));
+        let position = controller.get_camera().get_position();
+        assert!(position.abs_diff_eq(Vec3::new(1.0, 0.0, 0.0), 1e-5));
+    }
+}
@@ -0,0 +1,200 @@
+use glam::{Mat4, Vec3};
+
+use crate::Hexgem::math::HexgemMath::perspective;
+
+use super::camera::Camera;
+
+const MAX_PITCH: f32 = 89.0 * std::f32::consts::PI / 180.0;
+
+// Yaw and pitch are radians; yaw 0 and pitch 0 look down -Z with +Y up.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PerspectiveCamera {
+    fov_y: f32,
+    aspect_ratio: f32,
+    near: f32,
+    far: f32,
+    position: Vec3,
+    yaw: f32,
+    pitch: f32,
+    projection: Mat4,
+    view: Mat4,
+    view_projection: Mat4,
+}
+
+impl PerspectiveCamera {
+    pub fn create(fov_y: f32, aspect_ratio: f32, near: f32, far: f32) -> Self {
+        let mut camera = Self {
+            fov_y,
+            aspect_ratio,
+            near,
+            far,
+            position: Vec3::ZERO,
+            yaw: 0.0,
+            pitch: 0.0,
+            projection: Mat4::IDENTITY,
+            view: Mat4::IDENTITY,
+            view_projection: Mat4::IDENTITY,
+        };
+        camera.recalculate_projection();
+        camera
+    }
+
+    pub fn get_fov(&self) -> f32 {
+        self.fov_y
+    }
+
+    pub fn set_fov(&mut self, fov_y: f32) {
+        self.fov_y = fov_y;
+        self.recalculate_projection();
+    }
+
+    pub fn get_aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
+
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
+        self.aspect_ratio = aspect_ratio;
+        self.recalculate_projection();
+    }
+
+    pub fn get_near(&self) -> f32 {
+        self.near
+    }
+
+    pub fn get_far(&self) -> f32 {
+        self.far
+    }
+
+    pub fn set_clip(&mut self, near: f32, far: f32) {
+        self.near = near;
+        self.far = far;
+        self.recalculate_projection();
+    }
+
+    pub fn get_position(&self) -> Vec3 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Vec3) {
+        self.position = position;
+        self.recalculate_view();
+    }
+
+    pub fn get_yaw(&self) -> f32 {
+        self.yaw
+    }
+
+    pub fn get_pitch(&self) -> f32 {
+        self.pitch
+    }
+
+    pub fn set_orientation(&mut self, yaw: f32, pitch: f32) {
+        self.yaw = yaw;
+        self.pitch = pitch.clamp(-MAX_PITCH, MAX_PITCH);
+        self.recalculate_view();
+    }
+
+    pub fn look_at(&mut self, target: Vec3) {
+        let direction = (target - self.position).normalize_or_zero();
+        if direction == Vec3::ZERO {
+            return;
+        }
+        self.set_orientation(
+            direction.x.atan2(-direction.z),
+            direction.y.clamp(-1.0, 1.0).asin(),
+        );
+    }
+
+    pub fn forward(&self) -> Vec3 {
+        Vec3::new(
+            self.pitch.cos() * self.yaw.sin(),
+            self.pitch.sin(),
+            -self.pitch.cos() * self.yaw.cos(),
+        )
+    }
+
+    pub fn right(&self) -> Vec3 {
+        self.forward().cross(Vec3::Y).normalize()
+    }
+
+    pub fn up(&self) -> Vec3 {
+        self.right().cross(self.forward())
+    }
+
+    pub fn get_projection(&self) -> Mat4 {
+        self.projection
+    }
+
+    pub fn get_view(&self) -> Mat4 {
+        self.view
+    }
+
+    fn recalculate_projection(&mut self) {
+        self.projection = perspective(self.fov_y, self.aspect_ratio, self.near, self.far);
+        self.view_projection = self.projection * self.view;
+    }
+
+    fn recalculate_view(&mut self) {
+        self.view = Mat4::look_to_rh(self.position, self.forward(), Vec3::Y);
+        self.view_projection = self.projection * self.view;
+    }
+}
+
+impl Camera for PerspectiveCamera {
+    fn get_view_projection(&self) -> Mat4 {
+        self.view_projection
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use glam::Vec2;
+
+    use super::*;
+
+    fn project(camera: &PerspectiveCamera, point: Vec3) -> Vec2 {
+        camera
+            .get_view_projection()
+            .project_point3(point)
+            .truncate()
+    }
+
+    #[test]
+    fn projection_follows_the_aspect_ratio() {
+        let mut camera = PerspectiveCamera::create(FRAC_PI_2, 2.0, 0.1, 100.0);
+        assert!(project(&camera, Vec3::new(2.0, 1.0, -1.0)).abs_diff_eq(Vec2::ONE, 1e-5));
+        assert!(project(&camera, Vec3::new(-4.0, -2.0, -2.0)).abs_diff_eq(-Vec2::ONE, 1e-5));
+
+        camera.set_aspect_ratio(1.0);
+        assert_eq!(camera.get_aspect_ratio(), 1.0);
+        assert!(project(&camera, Vec3::new(1.0, 1.0, -1.0)).abs_diff_eq(Vec2::ONE, 1e-5));
+        assert!(project(&camera, Vec3::new(2.0, 1.0, -1.0)).abs_diff_eq(Vec2::new(2.0, 1.0), 1e-5));
+    }
+
+    #[test]
+    fn depth_spans_the_clip_planes() {
+        let camera = PerspectiveCamera::create(FRAC_PI_2, 1.0, 0.5, 20.0);
+        let depth = |z: f32| {
+            camera
+                .get_view_projection()
+                .project_point3(Vec3::new(0.0, 0.0, z))
+                .z
+        };
+        assert!((depth(-0.5) + 1.0).abs() < 1e-5);
+        assert!((depth(-20.0) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn look_at_orients_the_view() {
+        let mut camera = PerspectiveCamera::create(FRAC_PI_2, 1.0, 0.1, 100.0);
+        assert!(camera.forward().abs_diff_eq(Vec3::NEG_Z, 1e-6));
+        camera.set_position(Vec3::new(5.0, 0.0, 0.0));
+        camera.look_at(Vec3::ZERO);
+        assert!(camera.forward().abs_diff_eq(Vec3::NEG_X, 1e-6));
+        assert!(project(&camera, Vec3::ZERO).abs_diff_eq(Vec2::ZERO, 1e-6));
+        camera.set_orientation(0.0, 10.0);
+        assert_eq!(camera.get_pitch(), MAX_PITCH);
+    }
+}
@@ -0,0 +1,99 @@
+use std::cell::RefCell;
+
+use glam::Vec4;
+
+use super::{
+    buffer::{IndexBuffer, VertexBuffer},
+    framebuffer::{FramebufferHandle, FramebufferSpec},
+    image_data::ImageData,
+    null_renderer_api::NullRendererApi,
+    renderer_api::{GraphicsApi, RendererApi},
+    shader::Shader,
+    shader_source::ShaderSource,
+    texture::{TextureHandle, TextureOptions},
+    vertex_array::VertexArray,
+};
+use crate::Hexgem::error::HexgemResult;
+
+thread_local! {
+    static RENDERER_API: RefCell<Box<dyn RendererApi>> = RefCell::new(Box::new(NullRendererApi));
+}
+
+pub struct RenderCommand;
+
+impl RenderCommand {
+    pub fn init<A>(api: A)
+    where
+        A: RendererApi + 'static,
+    {
+        let mut api: Box<dyn RendererApi> = Box::new(api);
+        api.init();
+        RENDERER_API.with(|renderer| *renderer.borrow_mut() = api);
+    }
+
+    pub fn with<F, R>(callback: F) -> R
+    where
+        F: FnOnce(&mut dyn RendererApi) -> R,
+    {
+        RENDERER_API.with(|renderer| callback(renderer.borrow_mut().as_mut()))
+    }
+
+    pub fn get_api() -> GraphicsApi {
+        Self::with(|api| api.get_api())
+    }
+
+    pub fn set_clear_color(color: Vec4) {
+        Self::with(|api| api.set_clear_color(color));
+    }
+
+    pub fn clear() {
+        Self::with(|api| api.clear());
+    }
+
+    pub fn set_viewport(x: i32, y: i32, width: u32, height: u32) {
+        Self::with(|api| api.set_viewport(x, y, width, height));
+    }
+
+    pub fn draw_indexed(vertex_array: &dyn VertexArray, index_count: Option<u32>) {
+        Self::with(|api| api.draw_indexed(vertex_array, index_count));
+    }
+
+    pub fn draw_lines(vertex_array: &dyn VertexArray, vertex_count: u32) {
+        Self::with(|api| api.draw_lines(vertex_array, vertex_count));
+    }
+
+    pub fn set_line_width(width: f32) {
+        Self::with(|api| api.set_line_width(width));
+    }
+
+    pub fn create_vertex_buffer(size: usize, data: Option<&[u8]>) -> Box<dyn VertexBuffer> {
+        Self::with(|api| api.create_vertex_buffer(size, data))
+    }
+
+    pub fn create_index_buffer(indices: &[u32]) -> Box<dyn IndexBuffer> {
+        Self::with(|api| api.create_index_buffer(indices))
+    }
+
+    pub fn create_vertex_array() -> Box<dyn VertexArray> {
+        Self::with(|api| api.create_vertex_array())
+    }
+
+    pub fn create_shader(source: &ShaderSource) -> HexgemResult<Box<dyn Shader>> {
+        Self::with(|api| api.create_shader(source))
+    }
+
+    pub fn create_texture(
+        image: &ImageData,
+        options: &TextureOptions,
+    ) -> HexgemResult<Box<dyn TextureHandle>> {
+        Self::with(|api| api.create_texture(image, options))
+    }
+
+    pub fn read_pixels(x: i32, y: i32, width: u32, height: u32) -> HexgemResult<ImageData> {
+        Self::with(|api| api.read_pixels(x, y, width, height))
+    }
+
+    pub fn create_framebuffer(spec: &FramebufferSpec) -> HexgemResult<Box<dyn FramebufferHandle>> {
+        Self::with(|api| api.create_framebuffer(spec))
+    }
+}
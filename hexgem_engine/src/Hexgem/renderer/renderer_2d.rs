@@ -0,0 +1,771 @@
+use std::{cell::RefCell, rc::Rc};
+
+use glam::{Mat4, Quat, Vec2, Vec3, Vec4};
+use log::warn;
+
+use crate::Hexgem::error::{HexgemError, HexgemResult};
+
+use super::{
+    buffer::{as_bytes, BufferElement, BufferLayout, ShaderDataType, VertexData},
+    camera::Camera,
+    font::Font,
+    render_command::RenderCommand,
+    shader::Shader,
+    shader_source::ShaderSource,
+    text::{TextLayout, TextOptions},
+    texture::{SubTexture2D, Texture2D},
+    vertex_array::VertexArray,
+};
+
+const QUAD_POSITIONS: [Vec4; 4] = [
+    Vec4::new(-0.5, -0.5, 0.0, 1.0),
+    Vec4::new(0.5, -0.5, 0.0, 1.0),
+    Vec4::new(0.5, 0.5, 0.0, 1.0),
+    Vec4::new(-0.5, 0.5, 0.0, 1.0),
+];
+const QUAD_TEX_COORDS: [Vec2; 4] = [
+    Vec2::new(0.0, 0.0),
+    Vec2::new(1.0, 0.0),
+    Vec2::new(1.0, 1.0),
+    Vec2::new(0.0, 1.0),
+];
+const MAX_TEXTURE_SLOTS: u32 = 16;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuadVertex {
+    pub position: [f32; 3],
+    pub color: [f32; 4],
+    pub tex_coord: [f32; 2],
+    pub tex_index: f32,
+    pub tiling_factor: f32,
+}
+
+// The vertex types only hold f32s, so they have no padding.
+unsafe impl VertexData for QuadVertex {}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CircleVertex {
+    pub world_position: [f32; 3],
+    pub local_position: [f32; 3],
+    pub color: [f32; 4],
+    pub thickness: f32,
+    pub fade: f32,
+}
+
+unsafe impl VertexData for CircleVertex {}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LineVertex {
+    pub position: [f32; 3],
+    pub color: [f32; 4],
+}
+
+unsafe impl VertexData for LineVertex {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Renderer2DConfig {
+    pub max_quads: u32,
+    pub max_texture_slots: u32,
+}
+
+impl Default for Renderer2DConfig {
+    fn default() -> Self {
+        Self {
+            max_quads: 10000,
+            max_texture_slots: MAX_TEXTURE_SLOTS,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct Renderer2DStats {
+    pub draw_calls: u32,
+    pub quad_count: u32,
+    pub circle_count: u32,
+    pub line_count: u32,
+}
+
+impl Renderer2DStats {
+    pub fn vertex_count(&self) -> u32 {
+        (self.quad_count + self.circle_count) * 4 + self.line_count * 2
+    }
+
+    pub fn index_count(&self) -> u32 {
+        (self.quad_count + self.circle_count) * 6
+    }
+}
+
+#[derive(Clone)]
+pub struct Quad {
+    pub position: Vec3,
+    pub size: Vec2,
+    pub rotation: f32,
+    pub color: Vec4,
+    pub texture: Option<SubTexture2D>,
+    pub tiling: f32,
+}
+
+impl Quad {
+    pub fn create(position: Vec3, size: Vec2) -> Self {
+        Self {
+            position,
+            size,
+            rotation: 0.0,
+            color: Vec4::ONE,
+            texture: None,
+            tiling: 1.0,
+        }
+    }
+
+    pub fn color(mut self, color: Vec4) -> Self {
+        self.color = color;
+        self
+    }
+
+    // Same as color, reads better for textured quads.
+    pub fn tint(self, tint: Vec4) -> Self {
+        self.color(tint)
+    }
+
+    pub fn rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn texture(mut self, texture: Rc<Texture2D>) -> Self {
+        self.texture = Some(SubTexture2D::whole(texture));
+        self
+    }
+
+    pub fn sub_texture(mut self, sub_texture: SubTexture2D) -> Self {
+        self.texture = Some(sub_texture);
+        self
+    }
+
+    pub fn tiling(mut self, tiling: f32) -> Self {
+        self.tiling = tiling;
+        self
+    }
+
+    pub fn transform(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(
+            self.size.extend(1.0),
+            Quat::from_rotation_z(self.rotation),
+            self.position,
+        )
+    }
+}
+
+struct Batch<V> {
+    vertex_array: Box<dyn VertexArray>,
+    shader: Box<dyn Shader>,
+    vertices: Vec<V>,
+}
+
+impl<V: VertexData> Batch<V> {
+    fn create(
+        name: &str,
+        source: &str,
+        layout: BufferLayout,
+        max_vertices: u32,
+        indices: Option<&[u32]>,
+    ) -> HexgemResult<Self> {
+        let shader = RenderCommand::create_shader(&ShaderSource::parse(name, source)?)?;
+        let mut vertex_array = RenderCommand::create_vertex_array();
+        let size = max_vertices as usize * layout.get_stride() as usize;
+        let mut vertex_buffer = RenderCommand::create_vertex_buffer(size, None);
+        vertex_buffer.set_layout(layout);
+        vertex_array.add_vertex_buffer(vertex_buffer)?;
+        if let Some(indices) = indices {
+            vertex_array.set_index_buffer(RenderCommand::create_index_buffer(indices));
+        }
+        Ok(Self {
+            vertex_array,
+            shader,
+            vertices: Vec::with_capacity(max_vertices as usize),
+        })
+    }
+
+    fn upload(&mut self) {
+        if let Some(vertex_buffer) = self.vertex_array.get_vertex_buffers_mut().first_mut() {
+            vertex_buffer.set_data(as_bytes(&self.vertices));
+        }
+        self.shader.bind();
+    }
+}
+
+struct Renderer2DData {
+    config: Renderer2DConfig,
+    quads: Batch<QuadVertex>,
+    circles: Batch<CircleVertex>,
+    lines: Batch<LineVertex>,
+    white_texture: Rc<Texture2D>,
+    texture_slots: Vec<Rc<Texture2D>>,
+    line_width: f32,
+    stats: Renderer2DStats,
+}
+
+impl Renderer2DData {
+    fn create(config: Renderer2DConfig) -> HexgemResult<Self> {
+        // Vertex indices are u32, so every quad's four vertices have to be addressable.
+        if config.max_quads == 0 || config.max_quads > u32::MAX / 4 {
+            return Err(HexgemError::Renderer(format!(
+                "max_quads must be between 1 and {}, got {}",
+                u32::MAX / 4,
+                config.max_quads
+            )));
+        }
+        let max_vertices = config.max_quads * 4;
+        let indices: Vec<u32> = (0..config.max_quads)
+            .flat_map(|quad| {
+                let offset = quad * 4;
+                [
+                    offset,
+                    offset + 1,
+                    offset + 2,
+                    offset + 2,
+                    offset + 3,
+                    offset,
+                ]
+            })
+            .collect();
+
+        let quads = Batch::create(
+            "Renderer2D_Quad",
+            include_str!("shaders/renderer2d_quad.glsl"),
+            BufferLayout::create(vec![
+                BufferElement::create(ShaderDataType::Float3, "a_Position"),
+                BufferElement::create(ShaderDataType::Float4, "a_Color"),
+                BufferElement::create(ShaderDataType::Float2, "a_TexCoord"),
+                BufferElement::create(ShaderDataType::Float, "a_TexIndex"),
+                BufferElement::create(ShaderDataType::Float, "a_TilingFactor"),
+            ]),
+            max_vertices,
+            Some(&indices),
+        )?;
+        let circles = Batch::create(
+            "Renderer2D_Circle",
+            include_str!("shaders/renderer2d_circle.glsl"),
+            BufferLayout::create(vec![
+                BufferElement::create(ShaderDataType::Float3, "a_WorldPosition"),
+                BufferElement::create(ShaderDataType::Float3, "a_LocalPosition"),
+                BufferElement::create(ShaderDataType::Float4, "a_Color"),
+                BufferElement::create(ShaderDataType::Float, "a_Thickness"),
+                BufferElement::create(ShaderDataType::Float, "a_Fade"),
+            ]),
+            max_vertices,
+            Some(&indices),
+        )?;
+        let lines = Batch::create(
+            "Renderer2D_Line",
+            include_str!("shaders/renderer2d_line.glsl"),
+            BufferLayout::create(vec![
+                BufferElement::create(ShaderDataType::Float3, "a_Position"),
+                BufferElement::create(ShaderDataType::Float4, "a_Color"),
+            ]),
+            max_vertices,
+            None,
+        )?;
+
+        let config = Renderer2DConfig {
+            max_texture_slots: config.max_texture_slots.clamp(2, MAX_TEXTURE_SLOTS),
+            ..config
+        };
+        let samplers: Vec<i32> = (0..MAX_TEXTURE_SLOTS as i32).collect();
+        quads.shader.bind();
+        quads.shader.set_int_array("u_Textures", &samplers);
+
+        let white_texture = Rc::new(Texture2D::white()?);
+        Ok(Self {
+            config,
+            quads,
+            circles,
+            lines,
+            texture_slots: vec![white_texture.clone()],
+            white_texture,
+            line_width: 2.0,
+            stats: Renderer2DStats::default(),
+        })
+    }
+
+    fn start_batch(&mut self) {
+        self.quads.vertices.clear();
+        self.circles.vertices.clear();
+        self.lines.vertices.clear();
+        self.texture_slots.truncate(1);
+    }
+
+    fn flush(&mut self) {
+        if !self.quads.vertices.is_empty() {
+            let index_count = self.quads.vertices.len() as u32 / 4 * 6;
+            self.quads.upload();
+            for (slot, texture) in self.texture_slots.iter().enumerate() {
+                texture.bind(slot as u32);
+            }
+            RenderCommand::draw_indexed(self.quads.vertex_array.as_ref(), Some(index_count));
+            self.stats.draw_calls += 1;
+        }
+        if !self.circles.vertices.is_empty() {
+            let index_count = self.circles.vertices.len() as u32 / 4 * 6;
+            self.circles.upload();
+            RenderCommand::draw_indexed(self.circles.vertex_array.as_ref(), Some(index_count));
+            self.stats.draw_calls += 1;
+        }
+        if !self.lines.vertices.is_empty() {
+            let vertex_count = self.lines.vertices.len() as u32;
+            self.lines.upload();
+            RenderCommand::set_line_width(self.line_width);
+            RenderCommand::draw_lines(self.lines.vertex_array.as_ref(), vertex_count);
+            self.stats.draw_calls += 1;
+        }
+    }
+
+    fn next_batch(&mut self) {
+        self.flush();
+        self.start_batch();
+    }
+
+    fn texture_index(&mut self, texture: &Rc<Texture2D>) -> f32 {
+        if let Some(index) = self
+            .texture_slots
+            .iter()
+            .position(|slot| Rc::ptr_eq(slot, texture))
+        {
+            return index as f32;
+        }
+        if self.texture_slots.len() as u32 >= self.config.max_texture_slots {
+            self.next_batch();
+        }
+        self.texture_slots.push(texture.clone());
+        (self.texture_slots.len() - 1) as f32
+    }
+
+    fn draw_quad(
+        &mut self,
+        transform: Mat4,
+        color: Vec4,
+        texture: Option<&SubTexture2D>,
+        tiling: f32,
+    ) {
+        if self.quads.vertices.len() as u32 >= self.config.max_quads * 4 {
+            self.next_batch();
+        }
+        let (tex_index, tex_coords) = match texture {
+            Some(sub_texture) => (
+                self.texture_index(sub_texture.get_texture()),
+                *sub_texture.get_tex_coords(),
+            ),
+            None => (0.0, QUAD_TEX_COORDS),
+        };
+        for (position, tex_coord) in QUAD_POSITIONS.iter().zip(tex_coords) {
+            self.quads.vertices.push(QuadVertex {
+                position: (transform * *position).truncate().to_array(),
+                color: color.to_array(),
+                tex_coord: tex_coord.to_array(),
+                tex_index,
+                tiling_factor: tiling,
+            });
+        }
+        self.stats.quad_count += 1;
+    }
+
+    fn draw_circle(&mut self, transform: Mat4, color: Vec4, thickness: f32, fade: f32) {
+        if self.circles.vertices.len() as u32 >= self.config.max_quads * 4 {
+            self.next_batch();
+        }
+        for position in QUAD_POSITIONS.iter() {
+            self.circles.vertices.push(CircleVertex {
+                world_position: (transform * *position).truncate().to_array(),
+                local_position: (position.truncate() * 2.0).to_array(),
+                color: color.to_array(),
+                thickness,
+                fade,
+            });
+        }
+        self.stats.circle_count += 1;
+    }
+
+    fn draw_line(&mut self, start: Vec3, end: Vec3, color: Vec4) {
+        if self.lines.vertices.len() as u32 >= self.config.max_quads * 4 {
+            self.next_batch();
+        }
+        for position in [start, end] {
+            self.lines.vertices.push(LineVertex {
+                position: position.to_array(),
+                color: color.to_array(),
+            });
+        }
+        self.stats.line_count += 1;
+    }
+}
+
+thread_local! {
+    static RENDERER_2D: RefCell<Option<Renderer2DData>> = const { RefCell::new(None) };
+}
+
+pub struct Renderer2D;
+
+impl Renderer2D {
+    pub fn init() -> HexgemResult<()> {
+        Self::init_with(Renderer2DConfig::default())
+    }
+
+    pub fn init_with(config: Renderer2DConfig) -> HexgemResult<()> {
+        let data = Renderer2DData::create(config)?;
+        RENDERER_2D.with(|renderer| *renderer.borrow_mut() = Some(data));
+        Ok(())
+    }
+
+    pub fn shutdown() {
+        RENDERER_2D.with(|renderer| renderer.borrow_mut().take());
+    }
+
+    pub fn is_initialized() -> bool {
+        RENDERER_2D.with(|renderer| renderer.borrow().is_some())
+    }
+
+    fn with<F, R>(callback: F) -> Option<R>
+    where
+        F: FnOnce(&mut Renderer2DData) -> R,
+    {
+        RENDERER_2D.with(|renderer| match renderer.borrow_mut().as_mut() {
+            Some(data) => Some(callback(data)),
+            None => {
+                warn!("Renderer2D used before Renderer2D::init");
+                None
+            }
+        })
+    }
+
+    pub fn begin_scene(camera: &dyn Camera) {
+        let view_projection = camera.get_view_projection();
+        Self::with(|data| {
+            for shader in [
+                data.quads.shader.as_ref(),
+                data.circles.shader.as_ref(),
+                data.lines.shader.as_ref(),
+            ] {
+                shader.bind();
+                shader.set_mat4("u_ViewProjection", view_projection);
+            }
+            data.start_batch();
+        });
+    }
+
+    pub fn end_scene() {
+        Self::flush();
+    }
+
+    pub fn flush() {
+        Self::with(|data| data.next_batch());
+    }
+
+    pub fn draw_quad(quad: &Quad) {
+        Self::with(|data| {
+            data.draw_quad(
+                quad.transform(),
+                quad.color,
+                quad.texture.as_ref(),
+                quad.tiling,
+            )
+        });
+    }
+
+    pub fn draw_quad_transform(
+        transform: Mat4,
+        color: Vec4,
+        texture: Option<&SubTexture2D>,
+        tiling: f32,
+    ) {
+        Self::with(|data| data.draw_quad(transform, color, texture, tiling));
+    }
+
+    pub fn draw_circle(position: Vec3, radius: f32, color: Vec4, thickness: f32) {
+        let transform = Mat4::from_translation(position)
+            * Mat4::from_scale(Vec3::new(radius * 2.0, radius * 2.0, 1.0));
+        Self::draw_circle_transform(transform, color, thickness, 0.005);
+    }
+
+    pub fn draw_circle_transform(transform: Mat4, color: Vec4, thickness: f32, fade: f32) {
+        Self::with(|data| data.draw_circle(transform, color, thickness, fade));
+    }
+
+    pub fn draw_line(start: Vec3, end: Vec3, color: Vec4) {
+        Self::with(|data| data.draw_line(start, end, color));
+    }
+
+    pub fn draw_rect(position: Vec3, size: Vec2, color: Vec4) {
+        let transform = Mat4::from_translation(position) * Mat4::from_scale(size.extend(1.0));
+        Self::draw_rect_transform(transform, color);
+    }
+
+    pub fn draw_rect_transform(transform: Mat4, color: Vec4) {
+        let corners = QUAD_POSITIONS.map(|position| (transform * position).truncate());
+        Self::with(|data| {
+            for (start, end) in corners.iter().zip(corners.iter().cycle().skip(1)) {
+                data.draw_line(*start, *end, color);
+            }
+        });
+    }
+
+    // Text is laid out in em units, scale the transform by the font size in world units.
+    pub fn draw_text(font: &Font, text: &str, transform: Mat4, color: Vec4) {
+        Self::draw_text_with(font, text, transform, color, &TextOptions::default());
+    }
+
+    pub fn draw_text_with(
+        font: &Font,
+        text: &str,
+        transform: Mat4,
+        color: Vec4,
+        options: &TextOptions,
+    ) {
+        Self::draw_text_layout(&font.layout(text, options), transform, color);
+    }
+
+    // Draws text laid out earlier, so static labels skip the layout every frame.
+    pub fn draw_text_layout(layout: &TextLayout, transform: Mat4, color: Vec4) {
+        Self::with(|data| {
+            for glyph in layout.get_glyphs() {
+                let center = glyph.position + glyph.size / 2.0;
+                let glyph_transform = transform
+                    * Mat4::from_translation(center.extend(0.0))
+                    * Mat4::from_scale(glyph.size.extend(1.0));
+                data.draw_quad(glyph_transform, color, Some(&glyph.sub_texture), 1.0);
+            }
+        });
+    }
+
+    pub fn set_line_width(width: f32) {
+        Self::with(|data| data.line_width = width);
+    }
+
+    pub fn get_line_width() -> f32 {
+        Self::with(|data| data.line_width).unwrap_or(1.0)
+    }
+
+    pub fn get_white_texture() -> Option<Rc<Texture2D>> {
+        Self::with(|data| data.white_texture.clone())
+    }
+
+    pub fn get_stats() -> Renderer2DStats {
+        RENDERER_2D.with(|renderer| {
+            renderer
+                .borrow()
+                .as_ref()
+                .map(|data| data.stats)
+                .unwrap_or_default()
+        })
+    }
+
+    pub fn reset_stats() {
+        RENDERER_2D.with(|renderer| {
+            if let Some(data) = renderer.borrow_mut().as_mut() {
+                data.stats = Renderer2DStats::default();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::size_of;
+
+    use super::*;
+    use crate::Hexgem::renderer::HexgemRenderer::{RecordingRendererApi, RenderCall};
+
+    fn setup(config: Renderer2DConfig) -> RecordingRendererApi {
+        let api = RecordingRendererApi::create();
+        RenderCommand::init(api.clone());
+        Renderer2D::init_with(config).unwrap();
+        api.clear_calls();
+        api
+    }
+
+    fn draw_index_counts(api: &RecordingRendererApi) -> Vec<u32> {
+        api.calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                RenderCall::DrawIndexed { index_count, .. } => Some(index_count),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn uploaded_quads(api: &RecordingRendererApi) -> Vec<Vec<f32>> {
+        api.calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                RenderCall::SetVertexBufferData { data, .. } => Some(data),
+                _ => None,
+            })
+            .map(|data| {
+                data.chunks_exact(4)
+                    .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn quad(x: f32) -> Quad {
+        Quad::create(Vec3::new(x, 0.0, 0.0), Vec2::ONE)
+    }
+
+    fn texture() -> Rc<Texture2D> {
+        Rc::new(Texture2D::white().unwrap())
+    }
+
+    #[test]
+    fn quads_share_one_draw_call() {
+        let api = setup(Renderer2DConfig::default());
+        Renderer2D::begin_scene(&Mat4::IDENTITY);
+        for x in 0..3 {
+            Renderer2D::draw_quad(&quad(x as f32));
+        }
+        Renderer2D::end_scene();
+
+        assert_eq!(draw_index_counts(&api), [18]);
+        let uploads = uploaded_quads(&api);
+        assert_eq!(uploads.len(), 1);
+        assert_eq!(uploads[0].len() * 4, 12 * size_of::<QuadVertex>());
+        let stats = Renderer2D::get_stats();
+        assert_eq!(stats.draw_calls, 1);
+        assert_eq!(stats.quad_count, 3);
+        assert_eq!(stats.vertex_count(), 12);
+        assert_eq!(stats.index_count(), 18);
+    }
+
+    #[test]
+    fn full_batches_are_flushed() {
+        let api = setup(Renderer2DConfig {
+            max_quads: 2,
+            ..Renderer2DConfig::default()
+        });
+        Renderer2D::begin_scene(&Mat4::IDENTITY);
+        for x in 0..5 {
+            Renderer2D::draw_quad(&quad(x as f32));
+        }
+        Renderer2D::end_scene();
+
+        assert_eq!(draw_index_counts(&api), [12, 12, 6]);
+        assert_eq!(Renderer2D::get_stats().draw_calls, 3);
+        assert_eq!(Renderer2D::get_stats().quad_count, 5);
+    }
+
+    #[test]
+    fn max_quads_is_validated() {
+        RenderCommand::init(RecordingRendererApi::create());
+        for max_quads in [0, u32::MAX / 4 + 1, u32::MAX] {
+            let config = Renderer2DConfig {
+                max_quads,
+                ..Renderer2DConfig::default()
+            };
+            assert!(Renderer2D::init_with(config).is_err());
+        }
+        let config = Renderer2DConfig {
+            max_quads: 1,
+            ..Renderer2DConfig::default()
+        };
+        assert!(Renderer2D::init_with(config).is_ok());
+    }
+
+    #[test]
+    fn texture_slot_overflow_starts_a_new_batch() {
+        let api = setup(Renderer2DConfig::default());
+        let textures: Vec<_> = (0..20).map(|_| texture()).collect();
+        Renderer2D::begin_scene(&Mat4::IDENTITY);
+        for texture in textures.iter() {
+            Renderer2D::draw_quad(&quad(0.0).texture(texture.clone()));
+        }
+        Renderer2D::end_scene();
+
+        // Slot 0 is the white texture, so 15 textures fit in the first batch.
+        assert_eq!(draw_index_counts(&api), [15 * 6, 5 * 6]);
+        let binds = api
+            .calls()
+            .into_iter()
+            .filter(|call| matches!(call, RenderCall::BindTexture { .. }))
+            .count();
+        assert_eq!(binds, 16 + 6);
+        let uploads = uploaded_quads(&api);
+        let floats = size_of::<QuadVertex>() / 4;
+        // tex_index of the first vertex of the first quad in the second batch.
+        assert_eq!(uploads[1][9], 1.0);
+        assert_eq!(uploads[0][(14 * 4) * floats + 9], 15.0);
+    }
+
+    #[test]
+    fn same_texture_reuses_its_slot() {
+        let api = setup(Renderer2DConfig::default());
+        let shared = texture();
+        Renderer2D::begin_scene(&Mat4::IDENTITY);
+        for _ in 0..40 {
+            Renderer2D::draw_quad(&quad(0.0).texture(shared.clone()));
+        }
+        Renderer2D::end_scene();
+        assert_eq!(draw_index_counts(&api), [40 * 6]);
+    }
+
+    #[test]
+    fn begin_scene_resets_the_batch() {
+        let api = setup(Renderer2DConfig::default());
+        let (first, second) = (texture(), texture());
+        Renderer2D::begin_scene(&Mat4::IDENTITY);
+        Renderer2D::draw_quad(&quad(0.0).texture(first));
+        Renderer2D::end_scene();
+        Renderer2D::begin_scene(&Mat4::IDENTITY);
+        Renderer2D::draw_quad(&quad(0.0).texture(second));
+        Renderer2D::draw_quad(&quad(1.0));
+        Renderer2D::end_scene();
+
+        assert_eq!(draw_index_counts(&api), [6, 12]);
+        let uploads = uploaded_quads(&api);
+        let floats = size_of::<QuadVertex>() / 4;
+        assert_eq!(uploads[1].len(), 8 * floats);
+        // The second scene's texture gets slot 1 again instead of slot 2.
+        assert_eq!(uploads[1][9], 1.0);
+
+        let stats = Renderer2D::get_stats();
+        assert_eq!((stats.draw_calls, stats.quad_count), (2, 3));
+        Renderer2D::reset_stats();
+        assert_eq!(Renderer2D::get_stats(), Renderer2DStats::default());
+    }
+
+    #[test]
+    fn empty_scene_draws_nothing() {
+        let api = setup(Renderer2DConfig::default());
+        Renderer2D::begin_scene(&Mat4::IDENTITY);
+        Renderer2D::end_scene();
+        assert!(draw_index_counts(&api).is_empty());
+        assert_eq!(Renderer2D::get_stats().draw_calls, 0);
+    }
+
+    #[test]
+    fn circles_and_lines_use_their_own_batches() {
+        let api = setup(Renderer2DConfig::default());
+        Renderer2D::begin_scene(&Mat4::IDENTITY);
+        Renderer2D::draw_quad(&quad(0.0));
+        Renderer2D::draw_circle(Vec3::ZERO, 1.0, Vec4::ONE, 1.0);
+        Renderer2D::draw_line(Vec3::ZERO, Vec3::X, Vec4::ONE);
+        Renderer2D::draw_rect(Vec3::ZERO, Vec2::ONE, Vec4::ONE);
+        Renderer2D::end_scene();
+
+        assert_eq!(draw_index_counts(&api), [6, 6]);
+        let lines: Vec<u32> = api
+            .calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                RenderCall::DrawLines { vertex_count, .. } => Some(vertex_count),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(lines, [10]);
+        let stats = Renderer2D::get_stats();
+        assert_eq!(stats.draw_calls, 3);
+        assert_eq!((stats.circle_count, stats.line_count), (1, 5));
+        assert_eq!(stats.vertex_count(), 8 + 10);
+    }
+}
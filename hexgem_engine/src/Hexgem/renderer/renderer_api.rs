@@ -0,0 +1,55 @@
+use glam::Vec4;
+
+use crate::Hexgem::error::{HexgemError, HexgemResult};
+
+use super::{
+    buffer::{IndexBuffer, VertexBuffer},
+    framebuffer::{FramebufferHandle, FramebufferSpec},
+    image_data::ImageData,
+    shader::Shader,
+    shader_source::ShaderSource,
+    texture::{TextureHandle, TextureOptions},
+    vertex_array::VertexArray,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GraphicsApi {
+    None,
+    OpenGl,
+    Software,
+}
+
+pub trait RendererApi {
+    fn get_api(&self) -> GraphicsApi;
+    fn init(&mut self) {}
+    fn set_clear_color(&mut self, color: Vec4);
+    fn clear(&mut self);
+    fn set_viewport(&mut self, x: i32, y: i32, width: u32, height: u32);
+    fn draw_indexed(&mut self, vertex_array: &dyn VertexArray, index_count: Option<u32>);
+    fn draw_lines(&mut self, vertex_array: &dyn VertexArray, vertex_count: u32);
+    fn set_line_width(&mut self, width: f32);
+    fn create_vertex_buffer(&mut self, size: usize, data: Option<&[u8]>) -> Box<dyn VertexBuffer>;
+    fn create_index_buffer(&mut self, indices: &[u32]) -> Box<dyn IndexBuffer>;
+    fn create_vertex_array(&mut self) -> Box<dyn VertexArray>;
+    fn create_shader(&mut self, source: &ShaderSource) -> HexgemResult<Box<dyn Shader>>;
+    fn create_texture(
+        &mut self,
+        image: &ImageData,
+        options: &TextureOptions,
+    ) -> HexgemResult<Box<dyn TextureHandle>>;
+    fn create_framebuffer(
+        &mut self,
+        spec: &FramebufferSpec,
+    ) -> HexgemResult<Box<dyn FramebufferHandle>>;
+    // Reads the currently bound render target, rows are returned top first.
+    fn read_pixels(&mut self, x: i32, y: i32, width: u32, height: u32) -> HexgemResult<ImageData> {
+        Err(HexgemError::Renderer(format!(
+            "{:?} renderer cannot read back {}x{} pixels at ({}, {})",
+            self.get_api(),
+            width,
+            height,
+            x,
+            y
+        )))
+    }
+}
@@ -0,0 +1,137 @@
+use std::{collections::HashMap, path::Path};
+
+use glam::{Mat3, Mat4, Vec2, Vec3, Vec4};
+
+use crate::Hexgem::{
+    core::ToAny,
+    error::{HexgemError, HexgemResult},
+};
+
+use super::{render_command::RenderCommand, shader_source::ShaderSource};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UniformValue {
+    Int(i32),
+    IntArray(Vec<i32>),
+    Float(f32),
+    FloatArray(Vec<f32>),
+    Float2(Vec2),
+    Float3(Vec3),
+    Float4(Vec4),
+    Mat3(Mat3),
+    Mat4(Mat4),
+    Mat4Array(Vec<Mat4>),
+}
+
+pub trait Shader: ToAny {
+    fn bind(&self);
+    fn unbind(&self);
+    fn get_name(&self) -> &str;
+    fn set_uniform(&self, name: &str, value: UniformValue);
+
+    fn set_int(&self, name: &str, value: i32) {
+        self.set_uniform(name, UniformValue::Int(value));
+    }
+
+    fn set_int_array(&self, name: &str, values: &[i32]) {
+        self.set_uniform(name, UniformValue::IntArray(values.to_vec()));
+    }
+
+    fn set_float(&self, name: &str, value: f32) {
+        self.set_uniform(name, UniformValue::Float(value));
+    }
+
+    fn set_float_array(&self, name: &str, values: &[f32]) {
+        self.set_uniform(name, UniformValue::FloatArray(values.to_vec()));
+    }
+
+    fn set_float2(&self, name: &str, value: Vec2) {
+        self.set_uniform(name, UniformValue::Float2(value));
+    }
+
+    fn set_float3(&self, name: &str, value: Vec3) {
+        self.set_uniform(name, UniformValue::Float3(value));
+    }
+
+    fn set_float4(&self, name: &str, value: Vec4) {
+        self.set_uniform(name, UniformValue::Float4(value));
+    }
+
+    fn set_mat3(&self, name: &str, value: Mat3) {
+        self.set_uniform(name, UniformValue::Mat3(value));
+    }
+
+    fn set_mat4(&self, name: &str, value: Mat4) {
+        self.set_uniform(name, UniformValue::Mat4(value));
+    }
+
+    fn set_mat4_array(&self, name: &str, values: &[Mat4]) {
+        self.set_uniform(name, UniformValue::Mat4Array(values.to_vec()));
+    }
+}
+
+#[derive(Default)]
+pub struct ShaderLibrary {
+    shaders: HashMap<String, Box<dyn Shader>>,
+}
+
+impl ShaderLibrary {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, shader: Box<dyn Shader>) -> HexgemResult<()> {
+        let name = shader.get_name().to_string();
+        self.add_named(&name, shader)
+    }
+
+    pub fn add_named(&mut self, name: &str, shader: Box<dyn Shader>) -> HexgemResult<()> {
+        if self.exists(name) {
+            return Err(HexgemError::Renderer(format!(
+                "Shader {} already exists",
+                name
+            )));
+        }
+        self.shaders.insert(name.to_string(), shader);
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> HexgemResult<&dyn Shader> {
+        let source = ShaderSource::from_file(path)?;
+        self.load_source(source)
+    }
+
+    pub fn load_named<P: AsRef<Path>>(&mut self, name: &str, path: P) -> HexgemResult<&dyn Shader> {
+        let source = ShaderSource::from_file_named(name, path)?;
+        self.load_source(source)
+    }
+
+    pub fn load_source(&mut self, source: ShaderSource) -> HexgemResult<&dyn Shader> {
+        let name = source.get_name().to_string();
+        if self.exists(&name) {
+            return Err(HexgemError::Renderer(format!(
+                "Shader {} already exists",
+                name
+            )));
+        }
+        let shader = RenderCommand::create_shader(&source)?;
+        self.add_named(&name, shader)?;
+        Ok(self.shaders[&name].as_ref())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Shader> {
+        self.shaders.get(name).map(|shader| shader.as_ref())
+    }
+
+    pub fn exists(&self, name: &str) -> bool {
+        self.shaders.contains_key(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Box<dyn Shader>> {
+        self.shaders.remove(name)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.shaders.keys().map(|name| name.as_str()).collect()
+    }
+}
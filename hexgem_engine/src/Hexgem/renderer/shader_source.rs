@@ -0,0 +1,508 @@
+use std::{fs, path::Path};
+
+use crate::Hexgem::error::{HexgemError, HexgemResult};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShaderStage {
+    Vertex,
+    Fragment,
+    Geometry,
+    Compute,
+}
+
+impl ShaderStage {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "vertex" => Some(ShaderStage::Vertex),
+            "fragment" | "pixel" => Some(ShaderStage::Fragment),
+            "geometry" => Some(ShaderStage::Geometry),
+            "compute" => Some(ShaderStage::Compute),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShaderStage::Vertex => "vertex",
+            ShaderStage::Fragment => "fragment",
+            ShaderStage::Geometry => "geometry",
+            ShaderStage::Compute => "compute",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourceLine {
+    pub file: String,
+    pub line: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderStageSource {
+    pub stage: ShaderStage,
+    pub source: String,
+    lines: Vec<SourceLine>,
+}
+
+impl ShaderStageSource {
+    // Maps a 1-based line of the preprocessed stage back to the file it came from.
+    pub fn locate(&self, line: u32) -> Option<&SourceLine> {
+        if line == 0 {
+            return None;
+        }
+        self.lines.get(line as usize - 1)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderSource {
+    name: String,
+    file: String,
+    stages: Vec<ShaderStageSource>,
+}
+
+impl ShaderSource {
+    pub fn parse(name: &str, source: &str) -> HexgemResult<Self> {
+        Self::parse_with_includes(name, source, |_, _| None)
+    }
+
+    // The resolver receives the including file and the include path and returns
+    // the resolved file name together with its contents.
+    pub fn parse_with_includes<F>(name: &str, source: &str, resolver: F) -> HexgemResult<Self>
+    where
+        F: Fn(&str, &str) -> Option<(String, String)>,
+    {
+        Self::parse_file_with_includes(name, name, source, resolver)
+    }
+
+    // Like parse_with_includes, but the root file is reported and resolved as file instead of
+    // the shader name.
+    pub fn parse_file_with_includes<F>(
+        name: &str,
+        file: &str,
+        source: &str,
+        resolver: F,
+    ) -> HexgemResult<Self>
+    where
+        F: Fn(&str, &str) -> Option<(String, String)>,
+    {
+        let mut preprocessor = Preprocessor {
+            name,
+            resolver: &resolver,
+            stack: vec![],
+            lines: vec![],
+        };
+        preprocessor.expand(file, source)?;
+        let stages = split_stages(name, preprocessor.lines)?;
+        Ok(Self {
+            name: name.to_string(),
+            file: file.to_string(),
+            stages,
+        })
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> HexgemResult<Self> {
+        let path = path.as_ref();
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+        Self::from_file_named(&name, path)
+    }
+
+    pub fn from_file_named<P: AsRef<Path>>(name: &str, path: P) -> HexgemResult<Self> {
+        let path = path.as_ref();
+        let file = path.display().to_string();
+        let source = fs::read_to_string(path).map_err(|err| HexgemError::Shader {
+            name: name.to_string(),
+            file: file.clone(),
+            line: None,
+            message: err.to_string(),
+        })?;
+        Self::parse_file_with_includes(name, &file, &source, read_include)
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_file(&self) -> &str {
+        &self.file
+    }
+
+    pub fn get_stages(&self) -> &[ShaderStageSource] {
+        &self.stages
+    }
+
+    pub fn get_stage(&self, stage: ShaderStage) -> Option<&ShaderStageSource> {
+        self.stages.iter().find(|source| source.stage == stage)
+    }
+
+    // Builds the error for a failed stage compile, pointing at the original file and line
+    // when the driver log contains one.
+    pub fn compile_error(&self, stage: ShaderStage, log: &str) -> HexgemError {
+        let location = parse_log_line(log)
+            .and_then(|line| self.get_stage(stage).and_then(|source| source.locate(line)));
+        let message = format!("{} stage failed to compile: {}", stage.name(), log.trim());
+        match location {
+            Some(location) => HexgemError::Shader {
+                name: self.name.clone(),
+                file: location.file.clone(),
+                line: Some(location.line),
+                message,
+            },
+            None => HexgemError::Shader {
+                name: self.name.clone(),
+                file: self.file.clone(),
+                line: None,
+                message,
+            },
+        }
+    }
+
+    pub fn link_error(&self, log: &str) -> HexgemError {
+        HexgemError::Shader {
+            name: self.name.clone(),
+            file: self.file.clone(),
+            line: None,
+            message: format!("failed to link: {}", log.trim()),
+        }
+    }
+}
+
+// Driver logs look like "0(12) : error", "0:12(5): error" or "ERROR: 0:12: ...".
+pub fn parse_log_line(log: &str) -> Option<u32> {
+    for line in log.lines() {
+        let line = line.trim();
+        let line = line.strip_prefix("ERROR:").unwrap_or(line).trim_start();
+        let rest = line.trim_start_matches(|c: char| c.is_ascii_digit());
+        if rest.len() == line.len() {
+            continue;
+        }
+        let rest = match rest.strip_prefix(':').or_else(|| rest.strip_prefix('(')) {
+            Some(rest) => rest,
+            None => continue,
+        };
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        if let Ok(number) = digits.parse() {
+            return Some(number);
+        }
+    }
+    None
+}
+
+fn read_include(from: &str, path: &str) -> Option<(String, String)> {
+    let file = match Path::new(from).parent() {
+        Some(parent) => parent.join(path),
+        None => Path::new(path).to_path_buf(),
+    };
+    let source = fs::read_to_string(&file).ok()?;
+    Some((file.display().to_string(), source))
+}
+
+type IncludeResolver<'a> = dyn Fn(&str, &str) -> Option<(String, String)> + 'a;
+
+struct Preprocessor<'a> {
+    name: &'a str,
+    resolver: &'a IncludeResolver<'a>,
+    stack: Vec<String>,
+    lines: Vec<(String, SourceLine)>,
+}
+
+impl<'a> Preprocessor<'a> {
+    fn expand(&mut self, file: &str, source: &str) -> HexgemResult<()> {
+        self.stack.push(file.to_string());
+        for (index, text) in source.lines().enumerate() {
+            let location = SourceLine {
+                file: file.to_string(),
+                line: index as u32 + 1,
+            };
+            let include = match text.trim_start().strip_prefix("#include") {
+                Some(include) => include.trim(),
+                None => {
+                    self.lines.push((text.to_string(), location));
+                    continue;
+                }
+            };
+            let path = include
+                .strip_prefix('"')
+                .and_then(|include| include.strip_suffix('"'))
+                .or_else(|| {
+                    include
+                        .strip_prefix('<')
+                        .and_then(|include| include.strip_suffix('>'))
+                })
+                .ok_or_else(|| self.error(&location, format!("malformed include {}", include)))?;
+            let (resolved, contents) = (self.resolver)(file, path).ok_or_else(|| {
+                self.error(&location, format!("could not resolve include {}", path))
+            })?;
+            if self.stack.contains(&resolved) {
+                return Err(self.error(&location, format!("recursive include of {}", resolved)));
+            }
+            self.expand(&resolved, &contents)?;
+        }
+        self.stack.pop();
+        Ok(())
+    }
+
+    fn error(&self, location: &SourceLine, message: String) -> HexgemError {
+        HexgemError::Shader {
+            name: self.name.to_string(),
+            file: location.file.clone(),
+            line: Some(location.line),
+            message,
+        }
+    }
+}
+
+fn split_stages(
+    name: &str,
+    lines: Vec<(String, SourceLine)>,
+) -> HexgemResult<Vec<ShaderStageSource>> {
+    let mut stages: Vec<ShaderStageSource> = vec![];
+    let mut in_comment = false;
+    for (text, location) in lines {
+        if let Some(stage_name) = text.trim_start().strip_prefix("#type") {
+            let stage_name = stage_name.trim();
+            let error = |message: String| HexgemError::Shader {
+                name: name.to_string(),
+                file: location.file.clone(),
+                line: Some(location.line),
+                message,
+            };
+            let stage = ShaderStage::from_name(stage_name)
+                .ok_or_else(|| error(format!("unknown shader stage {}", stage_name)))?;
+            if stages.iter().any(|source| source.stage == stage) {
+                return Err(error(format!("duplicate {} stage", stage.name())));
+            }
+            stages.push(ShaderStageSource {
+                stage,
+                source: String::new(),
+                lines: vec![],
+            });
+            continue;
+        }
+        match stages.last_mut() {
+            Some(current) => {
+                current.source.push_str(&text);
+                current.source.push('\n');
+                current.lines.push(location);
+            }
+            None if is_comment(&text, &mut in_comment) => {}
+            None => {
+                return Err(HexgemError::Shader {
+                    name: name.to_string(),
+                    file: location.file,
+                    line: Some(location.line),
+                    message: "source outside of a #type section".to_string(),
+                })
+            }
+        }
+    }
+    if stages.is_empty() {
+        return Err(HexgemError::Shader {
+            name: name.to_string(),
+            file: name.to_string(),
+            line: None,
+            message: "no #type sections found".to_string(),
+        });
+    }
+    Ok(stages)
+}
+
+// Blank lines and comments are allowed before the first #type, e.g. a license header.
+fn is_comment(text: &str, in_comment: &mut bool) -> bool {
+    let mut text = text.trim();
+    loop {
+        if *in_comment {
+            match text.find("*/") {
+                Some(end) => {
+                    *in_comment = false;
+                    text = text[end + 2..].trim_start();
+                }
+                None => return true,
+            }
+        } else if text.is_empty() || text.starts_with("//") {
+            return true;
+        } else if let Some(rest) = text.strip_prefix("/*") {
+            *in_comment = true;
+            text = rest;
+        } else {
+            return false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "// Sprite shader\n\
+                          /* shared by the 2D renderer\n\
+                             and the editor */\n\
+                          \n\
+                          #type vertex\n\
+                          void main() {}\n\
+                          #type pixel\n\
+                          out vec4 color;\n\
+                          void main() {}\n";
+
+    fn includes(
+        files: &'static [(&'static str, &'static str)],
+    ) -> impl Fn(&str, &str) -> Option<(String, String)> {
+        move |_, path| {
+            files
+                .iter()
+                .find(|(name, _)| *name == path)
+                .map(|(name, source)| (name.to_string(), source.to_string()))
+        }
+    }
+
+    fn error_location(error: HexgemError) -> (String, Option<u32>, String) {
+        match error {
+            HexgemError::Shader {
+                file,
+                line,
+                message,
+                ..
+            } => (file, line, message),
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+
+    #[test]
+    fn splits_type_sections_after_leading_comments() {
+        let source = ShaderSource::parse("sprite", SOURCE).unwrap();
+        let stages = source.get_stages();
+        assert_eq!(stages.len(), 2);
+        assert_eq!(stages[0].stage, ShaderStage::Vertex);
+        assert_eq!(stages[0].source, "void main() {}\n");
+        assert_eq!(stages[1].stage, ShaderStage::Fragment);
+        assert_eq!(stages[1].source, "out vec4 color;\nvoid main() {}\n");
+        assert_eq!(
+            stages[1].locate(2),
+            Some(&SourceLine {
+                file: "sprite".to_string(),
+                line: 9,
+            })
+        );
+        assert_eq!(stages[1].locate(0), None);
+        assert_eq!(stages[1].locate(3), None);
+    }
+
+    #[test]
+    fn rejects_invalid_sections() {
+        let (_, line, message) =
+            error_location(ShaderSource::parse("s", "// header\nvoid main() {}\n").unwrap_err());
+        assert_eq!(line, Some(2));
+        assert!(message.contains("outside of a #type"));
+        let (_, line, message) = error_location(
+            ShaderSource::parse("s", "/* x */ float y;\n#type vertex\n").unwrap_err(),
+        );
+        assert_eq!(line, Some(1));
+        assert!(message.contains("outside of a #type"));
+        let (_, line, message) = error_location(
+            ShaderSource::parse("s", "#type vertex\n#type tessellation\n").unwrap_err(),
+        );
+        assert_eq!(line, Some(2));
+        assert!(message.contains("unknown shader stage"));
+        let (_, _, message) =
+            error_location(ShaderSource::parse("s", "#type vertex\n#type vertex\n").unwrap_err());
+        assert!(message.contains("duplicate vertex stage"));
+        let (_, line, _) =
+            error_location(ShaderSource::parse("s", "// only a comment\n").unwrap_err());
+        assert_eq!(line, None);
+    }
+
+    #[test]
+    fn resolves_includes_and_locates_their_lines() {
+        let resolver = includes(&[
+            (
+                "common.glsl",
+                "#include <math.glsl>\nuniform mat4 u_ViewProjection;",
+            ),
+            ("math.glsl", "float square(float x) { return x * x; }"),
+        ]);
+        let source = ShaderSource::parse_file_with_includes(
+            "lit",
+            "lit.glsl",
+            "#type vertex\n#include \"common.glsl\"\nvoid main() {}\n",
+            resolver,
+        )
+        .unwrap();
+        assert_eq!(source.get_file(), "lit.glsl");
+        let vertex = source.get_stage(ShaderStage::Vertex).unwrap();
+        assert_eq!(
+            vertex.source,
+            "float square(float x) { return x * x; }\nuniform mat4 u_ViewProjection;\nvoid main() {}\n"
+        );
+        let locations: Vec<_> = (1..=3)
+            .map(|line| {
+                let location = vertex.locate(line).unwrap();
+                (location.file.as_str(), location.line)
+            })
+            .collect();
+        assert_eq!(
+            locations,
+            [("math.glsl", 1), ("common.glsl", 2), ("lit.glsl", 3)]
+        );
+    }
+
+    #[test]
+    fn rejects_include_cycles_and_missing_includes() {
+        let resolver = includes(&[
+            ("a.glsl", "#include \"b.glsl\""),
+            ("b.glsl", "float b;\n#include \"a.glsl\""),
+        ]);
+        let error =
+            ShaderSource::parse_with_includes("s", "#type vertex\n#include \"a.glsl\"\n", resolver)
+                .unwrap_err();
+        let (file, line, message) = error_location(error);
+        assert_eq!((file.as_str(), line), ("b.glsl", Some(2)));
+        assert!(message.contains("recursive include of a.glsl"));
+
+        let error =
+            ShaderSource::parse("s", "#type vertex\n#include \"missing.glsl\"\n").unwrap_err();
+        let (_, line, message) = error_location(error);
+        assert_eq!(line, Some(2));
+        assert!(message.contains("could not resolve include missing.glsl"));
+        let error = ShaderSource::parse("s", "#type vertex\n#include missing.glsl\n").unwrap_err();
+        assert!(error_location(error).2.contains("malformed include"));
+    }
+
+    #[test]
+    fn parses_driver_log_lines() {
+        assert_eq!(
+            parse_log_line("0(12) : error C0000: syntax error"),
+            Some(12)
+        );
+        assert_eq!(parse_log_line("0:7(5): error: `x' undeclared"), Some(7));
+        assert_eq!(
+            parse_log_line("ERROR: 0:33: 'foo' : undeclared identifier"),
+            Some(33)
+        );
+        assert_eq!(parse_log_line("warning\n  0(4) : error"), Some(4));
+        assert_eq!(parse_log_line("error: no line here"), None);
+        assert_eq!(parse_log_line(""), None);
+    }
+
+    #[test]
+    fn compile_errors_point_at_the_original_file() {
+        let resolver = includes(&[("common.glsl", "float a;\nfloat b")]);
+        let source = ShaderSource::parse_file_with_includes(
+            "lit",
+            "lit.glsl",
+            "#type fragment\n#include \"common.glsl\"\nvoid main() {}\n",
+            resolver,
+        )
+        .unwrap();
+        let (file, line, message) = error_location(
+            source.compile_error(ShaderStage::Fragment, "0(2) : error C1035: missing ;"),
+        );
+        assert_eq!((file.as_str(), line), ("common.glsl", Some(2)));
+        assert!(message.starts_with("fragment stage failed to compile"));
+        let (file, line, _) =
+            error_location(source.compile_error(ShaderStage::Fragment, "0(40) : error"));
+        assert_eq!((file.as_str(), line), ("lit.glsl", None));
+    }
+}
@@ -0,0 +1,60 @@
+#type vertex
+#version 330 core
+
+layout(location = 0) in vec3 a_Position;
+layout(location = 1) in vec4 a_Color;
+layout(location = 2) in vec2 a_TexCoord;
+layout(location = 3) in float a_TexIndex;
+layout(location = 4) in float a_TilingFactor;
+
+uniform mat4 u_ViewProjection;
+
+out vec4 v_Color;
+out vec2 v_TexCoord;
+flat out float v_TexIndex;
+
+void main()
+{
+	v_Color = a_Color;
+	v_TexCoord = a_TexCoord * a_TilingFactor;
+	v_TexIndex = a_TexIndex;
+	gl_Position = u_ViewProjection * vec4(a_Position, 1.0);
+}
+
+#type fragment
+#version 330 core
+
+layout(location = 0) out vec4 o_Color;
+
+in vec4 v_Color;
+in vec2 v_TexCoord;
+flat in float v_TexIndex;
+
+uniform sampler2D u_Textures[16];
+
+void main()
+{
+	vec4 color = v_Color;
+	switch (int(v_TexIndex))
+	{
+		case  0: color *= texture(u_Textures[ 0], v_TexCoord); break;
+		case  1: color *= texture(u_Textures[ 1], v_TexCoord); break;
+		case  2: color *= texture(u_Textures[ 2], v_TexCoord); break;
+		case  3: color *= texture(u_Textures[ 3], v_TexCoord); break;
+		case  4: color *= texture(u_Textures[ 4], v_TexCoord); break;
+		case  5: color *= texture(u_Textures[ 5], v_TexCoord); break;
+		case  6: color *= texture(u_Textures[ 6], v_TexCoord); break;
+		case  7: color *= texture(u_Textures[ 7], v_TexCoord); break;
+		case  8: color *= texture(u_Textures[ 8], v_TexCoord); break;
+		case  9: color *= texture(u_Textures[ 9], v_TexCoord); break;
+		case 10: color *= texture(u_Textures[10], v_TexCoord); break;
+		case 11: color *= texture(u_Textures[11], v_TexCoord); break;
+		case 12: color *= texture(u_Textures[12], v_TexCoord); break;
+		case 13: color *= texture(u_Textures[13], v_TexCoord); break;
+		case 14: color *= texture(u_Textures[14], v_TexCoord); break;
+		case 15: color *= texture(u_Textures[15], v_TexCoord); break;
+	}
+	if (color.a == 0.0)
+		discard;
+	o_Color = color;
+}
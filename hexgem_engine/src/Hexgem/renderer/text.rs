@@ -0,0 +1,261 @@
+use glam::Vec2;
+
+use crate::Hexgem::math::HexgemMath::Rect;
+
+use super::{font::Font, texture::SubTexture2D};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextOptions {
+    pub align: TextAlign,
+    // Multiplier for the font's line height.
+    pub line_spacing: f32,
+    // Extra space between characters, in em.
+    pub letter_spacing: f32,
+    pub kerning: bool,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            align: TextAlign::Left,
+            line_spacing: 1.0,
+            letter_spacing: 0.0,
+            kerning: true,
+        }
+    }
+}
+
+impl TextOptions {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn line_spacing(mut self, line_spacing: f32) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    pub fn letter_spacing(mut self, letter_spacing: f32) -> Self {
+        self.letter_spacing = letter_spacing;
+        self
+    }
+
+    pub fn kerning(mut self, kerning: bool) -> Self {
+        self.kerning = kerning;
+        self
+    }
+}
+
+#[derive(Clone)]
+pub struct PositionedGlyph {
+    pub character: char,
+    // Bottom left corner and size of the glyph quad.
+    pub position: Vec2,
+    pub size: Vec2,
+    pub sub_texture: SubTexture2D,
+}
+
+// Text laid out in em units, one em being the font size. The origin is on the baseline of
+// the first line, at its left edge, center or right edge depending on the alignment, and
+// further lines go down.
+#[derive(Clone)]
+pub struct TextLayout {
+    glyphs: Vec<PositionedGlyph>,
+    line_widths: Vec<f32>,
+    line_advance: f32,
+    ascent: f32,
+    descent: f32,
+}
+
+impl TextLayout {
+    pub fn create(font: &Font, text: &str, options: &TextOptions) -> Self {
+        let scale = 1.0 / font.get_size();
+        let line_advance = font.get_line_height() * scale * options.line_spacing;
+        let mut glyphs = vec![];
+        let mut line_widths = vec![];
+        for (line_index, line) in text.split('\n').enumerate() {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let line_start = glyphs.len();
+            let mut pen = 0.0;
+            let mut previous: Option<char> = None;
+            for character in line.chars() {
+                let Some(glyph) = font.get_glyph(character) else {
+                    continue;
+                };
+                if let Some(previous) = previous.filter(|_| options.kerning) {
+                    pen += font.get_kerning(previous, glyph.character) * scale;
+                }
+                if let Some(sub_texture) = glyph.sub_texture.as_ref() {
+                    glyphs.push(PositionedGlyph {
+                        character,
+                        position: Vec2::new(pen, -(line_index as f32) * line_advance)
+                            + glyph.offset * scale,
+                        size: glyph.size * scale,
+                        sub_texture: sub_texture.clone(),
+                    });
+                }
+                pen += glyph.advance * scale + options.letter_spacing;
+                previous = Some(glyph.character);
+            }
+            if previous.is_some() {
+                pen -= options.letter_spacing;
+            }
+            let shift = match options.align {
+                TextAlign::Left => 0.0,
+                TextAlign::Center => -pen / 2.0,
+                TextAlign::Right => -pen,
+            };
+            for glyph in glyphs[line_start..].iter_mut() {
+                glyph.position.x += shift;
+            }
+            line_widths.push(pen);
+        }
+        Self {
+            glyphs,
+            line_widths,
+            line_advance,
+            ascent: font.get_ascent() * scale,
+            descent: font.get_descent() * scale,
+        }
+    }
+
+    pub fn get_glyphs(&self) -> &[PositionedGlyph] {
+        &self.glyphs
+    }
+
+    pub fn get_line_count(&self) -> usize {
+        self.line_widths.len()
+    }
+
+    pub fn get_line_widths(&self) -> &[f32] {
+        &self.line_widths
+    }
+
+    // Advance width of the longest line and the height from the first ascent to the last descent.
+    pub fn get_size(&self) -> Vec2 {
+        let width = self.line_widths.iter().copied().fold(0.0, f32::max);
+        let lines = self.line_widths.len().saturating_sub(1) as f32;
+        Vec2::new(
+            width,
+            self.ascent - self.descent + lines * self.line_advance,
+        )
+    }
+
+    // Box around the glyph quads, the ink of the text rather than its advance.
+    pub fn get_bounds(&self) -> Option<Rect> {
+        self.glyphs
+            .iter()
+            .map(|glyph| Rect::from_position_size(glyph.position, glyph.size))
+            .reduce(|bounds, rect| bounds.union(&rect))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hexgem::renderer::HexgemRenderer::{ImageData, NullRendererApi, RenderCommand};
+
+    // Size 10 with a 12 pixel line height, so one em is 10 pixels and lines are 1.2 em apart.
+    // 'A' and 'V' advance 5 pixels and 'AV' kerns by -1.
+    fn font() -> Font {
+        RenderCommand::init(NullRendererApi);
+        let descriptor = "info face=\"Test\" size=10\n\
+             common lineHeight=12 base=9 pages=1\n\
+             page id=0 file=\"test.png\"\n\
+             char id=65 x=0 y=0 width=4 height=6 xoffset=0 yoffset=3 xadvance=5\n\
+             char id=86 x=4 y=0 width=4 height=6 xoffset=1 yoffset=3 xadvance=5\n\
+             char id=32 x=0 y=0 width=0 height=0 xoffset=0 yoffset=0 xadvance=3\n\
+             kerning first=65 second=86 amount=-1\n";
+        let page = ImageData::filled(16, 16, [255, 255, 255, 255]).unwrap();
+        Font::from_bmfont(descriptor, &page).unwrap()
+    }
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-5,
+            "{} is not {}",
+            actual,
+            expected
+        );
+    }
+
+    fn glyph_x(layout: &TextLayout) -> Vec<f32> {
+        layout
+            .get_glyphs()
+            .iter()
+            .map(|glyph| glyph.position.x)
+            .collect()
+    }
+
+    #[test]
+    fn kerning_pulls_pairs_together() {
+        let font = font();
+        let kerned = font.layout("AV", &TextOptions::create());
+        assert_near(kerned.get_line_widths()[0], 0.9);
+        // V sits at its pen position plus its own 0.1 em offset.
+        assert_near(glyph_x(&kerned)[1], 0.4 + 0.1);
+        let plain = font.layout("AV", &TextOptions::create().kerning(false));
+        assert_near(plain.get_line_widths()[0], 1.0);
+        assert_near(glyph_x(&plain)[1], 0.5 + 0.1);
+    }
+
+    #[test]
+    fn alignment_shifts_every_line_by_its_width() {
+        let font = font();
+        let text = "A\nAV";
+        let left = font.layout(text, &TextOptions::create());
+        let center = font.layout(text, &TextOptions::create().align(TextAlign::Center));
+        let right = font.layout(text, &TextOptions::create().align(TextAlign::Right));
+        for (layout, shift) in [
+            (&left, [0.0, 0.0]),
+            (&center, [-0.25, -0.45]),
+            (&right, [-0.5, -0.9]),
+        ] {
+            let x = glyph_x(layout);
+            assert_near(x[0], shift[0]);
+            assert_near(x[1], shift[1]);
+        }
+        // The second line starts one line height below the first.
+        assert_near(left.get_glyphs()[1].position.y, -1.2);
+        assert_eq!(left.get_line_count(), 2);
+    }
+
+    #[test]
+    fn letter_spacing_only_goes_between_characters() {
+        let font = font();
+        let layout = font.layout("AA", &TextOptions::create().letter_spacing(0.2));
+        assert_near(layout.get_line_widths()[0], 1.2);
+        assert_near(glyph_x(&layout)[1], 0.7);
+    }
+
+    #[test]
+    fn measure_covers_advances_and_lines() {
+        let font = font();
+        let size = font.measure("AV A");
+        assert_near(size.x, 0.9 + 0.3 + 0.5);
+        assert_near(size.y, 1.2);
+        let size = font.measure("A\nAVA\n");
+        assert_near(size.x, 1.4);
+        assert_near(size.y, 1.2 * 3.0);
+        // Spaces advance without adding a glyph quad.
+        assert_eq!(
+            font.layout("A A", &TextOptions::create())
+                .get_glyphs()
+                .len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,384 @@
+use std::{path::Path, rc::Rc};
+
+use glam::{UVec2, Vec2};
+
+use crate::Hexgem::{
+    core::ToAny,
+    error::{HexgemError, HexgemResult},
+    math::HexgemMath::Rect,
+};
+
+use super::{
+    image_data::{ImageData, TextureFilter, TextureWrap},
+    render_command::RenderCommand,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextureOptions {
+    pub min_filter: TextureFilter,
+    pub mag_filter: TextureFilter,
+    pub wrap_s: TextureWrap,
+    pub wrap_t: TextureWrap,
+    pub mipmaps: bool,
+}
+
+impl Default for TextureOptions {
+    fn default() -> Self {
+        Self {
+            min_filter: TextureFilter::Linear,
+            mag_filter: TextureFilter::Linear,
+            wrap_s: TextureWrap::Repeat,
+            wrap_t: TextureWrap::Repeat,
+            mipmaps: false,
+        }
+    }
+}
+
+impl TextureOptions {
+    pub fn pixel_art() -> Self {
+        Self::default()
+            .filter(TextureFilter::Nearest)
+            .wrap(TextureWrap::ClampToEdge)
+    }
+
+    pub fn filter(mut self, filter: TextureFilter) -> Self {
+        self.min_filter = filter;
+        self.mag_filter = filter;
+        self
+    }
+
+    pub fn min_filter(mut self, filter: TextureFilter) -> Self {
+        self.min_filter = filter;
+        self
+    }
+
+    pub fn mag_filter(mut self, filter: TextureFilter) -> Self {
+        self.mag_filter = filter;
+        self
+    }
+
+    pub fn wrap(mut self, wrap: TextureWrap) -> Self {
+        self.wrap_s = wrap;
+        self.wrap_t = wrap;
+        self
+    }
+
+    pub fn mipmaps(mut self, mipmaps: bool) -> Self {
+        self.mipmaps = mipmaps;
+        self
+    }
+}
+
+pub trait TextureHandle: ToAny {
+    fn bind(&self, slot: u32);
+    fn set_data(&self, image: &ImageData);
+}
+
+pub struct Texture2D {
+    width: u32,
+    height: u32,
+    options: TextureOptions,
+    handle: Box<dyn TextureHandle>,
+}
+
+impl Texture2D {
+    pub fn from_image(image: &ImageData, options: TextureOptions) -> HexgemResult<Self> {
+        let handle = RenderCommand::create_texture(image, &options)?;
+        Ok(Self {
+            width: image.get_width(),
+            height: image.get_height(),
+            options,
+            handle,
+        })
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P, options: TextureOptions) -> HexgemResult<Self> {
+        Self::from_image(&ImageData::load(path)?, options)
+    }
+
+    pub fn from_memory(bytes: &[u8], options: TextureOptions) -> HexgemResult<Self> {
+        Self::from_image(&ImageData::decode(bytes)?, options)
+    }
+
+    pub fn from_rgba(
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+        options: TextureOptions,
+    ) -> HexgemResult<Self> {
+        Self::from_image(
+            &ImageData::from_rgba(width, height, pixels.to_vec())?,
+            options,
+        )
+    }
+
+    pub fn white() -> HexgemResult<Self> {
+        Self::from_image(
+            &ImageData::filled(1, 1, [255; 4])?,
+            TextureOptions::pixel_art(),
+        )
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get_size(&self) -> UVec2 {
+        UVec2::new(self.width, self.height)
+    }
+
+    pub fn get_options(&self) -> &TextureOptions {
+        &self.options
+    }
+
+    pub fn get_handle(&self) -> &dyn TextureHandle {
+        self.handle.as_ref()
+    }
+
+    pub fn bind(&self, slot: u32) {
+        self.handle.bind(slot);
+    }
+
+    pub fn set_data(&self, image: &ImageData) -> HexgemResult<()> {
+        if image.get_width() != self.width || image.get_height() != self.height {
+            return Err(HexgemError::Texture(format!(
+                "Cannot upload {}x{} image into {}x{} texture",
+                image.get_width(),
+                image.get_height(),
+                self.width,
+                self.height
+            )));
+        }
+        self.handle.set_data(image);
+        Ok(())
+    }
+}
+
+// Texture coordinates are ordered bottom left, bottom right, top right, top left.
+#[derive(Clone)]
+pub struct SubTexture2D {
+    texture: Rc<Texture2D>,
+    tex_coords: [Vec2; 4],
+}
+
+impl SubTexture2D {
+    pub fn create(texture: Rc<Texture2D>, uv: Rect) -> Self {
+        Self {
+            texture,
+            tex_coords: rect_tex_coords(uv),
+        }
+    }
+
+    pub fn whole(texture: Rc<Texture2D>) -> Self {
+        Self::create(texture, Rect::from_min_max(Vec2::ZERO, Vec2::ONE))
+    }
+
+    // Region in pixels measured from the top left corner of the image.
+    pub fn from_pixels(texture: Rc<Texture2D>, position: UVec2, size: UVec2) -> Self {
+        let uv = pixel_rect_to_uv(texture.get_size(), position, size);
+        Self::create(texture, uv)
+    }
+
+    pub fn get_texture(&self) -> &Rc<Texture2D> {
+        &self.texture
+    }
+
+    pub fn get_tex_coords(&self) -> &[Vec2; 4] {
+        &self.tex_coords
+    }
+}
+
+pub fn rect_tex_coords(uv: Rect) -> [Vec2; 4] {
+    [
+        uv.min,
+        Vec2::new(uv.max.x, uv.min.y),
+        uv.max,
+        Vec2::new(uv.min.x, uv.max.y),
+    ]
+}
+
+pub fn pixel_rect_to_uv(texture_size: UVec2, position: UVec2, size: UVec2) -> Rect {
+    let texture_size = texture_size.as_vec2();
+    let min = Vec2::new(
+        position.x as f32,
+        texture_size.y - (position.y + size.y) as f32,
+    );
+    Rect::from_position_size(min / texture_size, size.as_vec2() / texture_size)
+}
+
+// Uniform grid over a sprite sheet. Cells are addressed by column and row counted
+// from the top left corner, the way sprite sheet tools lay them out.
+#[derive(Clone)]
+pub struct TextureAtlas {
+    texture_size: UVec2,
+    cell_size: UVec2,
+    margin: UVec2,
+    spacing: UVec2,
+    texture: Option<Rc<Texture2D>>,
+}
+
+impl TextureAtlas {
+    pub fn create(texture: Rc<Texture2D>, cell_size: UVec2) -> HexgemResult<Self> {
+        let mut atlas = Self::from_size(texture.get_size(), cell_size)?;
+        atlas.texture = Some(texture);
+        Ok(atlas)
+    }
+
+    pub fn from_size(texture_size: UVec2, cell_size: UVec2) -> HexgemResult<Self> {
+        if cell_size.x == 0 || cell_size.y == 0 {
+            return Err(HexgemError::Texture(format!(
+                "Invalid atlas cell size {}x{}",
+                cell_size.x, cell_size.y
+            )));
+        }
+        Ok(Self {
+            texture_size,
+            cell_size,
+            margin: UVec2::ZERO,
+            spacing: UVec2::ZERO,
+            texture: None,
+        })
+    }
+
+    pub fn with_margin(mut self, margin: UVec2) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    pub fn with_spacing(mut self, spacing: UVec2) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn get_texture(&self) -> Option<&Rc<Texture2D>> {
+        self.texture.as_ref()
+    }
+
+    pub fn columns(&self) -> u32 {
+        self.fit(
+            self.texture_size.x,
+            self.margin.x,
+            self.cell_size.x,
+            self.spacing.x,
+        )
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.fit(
+            self.texture_size.y,
+            self.margin.y,
+            self.cell_size.y,
+            self.spacing.y,
+        )
+    }
+
+    pub fn len(&self) -> u32 {
+        self.columns().saturating_mul(self.rows())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn cell_pixels(&self, column: u32, row: u32) -> (UVec2, UVec2) {
+        let position = self.margin + UVec2::new(column, row) * (self.cell_size + self.spacing);
+        (position, self.cell_size)
+    }
+
+    // Spans several cells, useful for sprites larger than one grid cell.
+    pub fn region_uv(&self, column: u32, row: u32, cells: UVec2) -> Option<Rect> {
+        let cells = cells.max(UVec2::ONE);
+        let fits = |start: u32, count: u32, available: u32| {
+            start.checked_add(count).is_some_and(|end| end <= available)
+        };
+        if !fits(column, cells.x, self.columns()) || !fits(row, cells.y, self.rows()) {
+            return None;
+        }
+        let (position, _) = self.cell_pixels(column, row);
+        let size = self.cell_size * cells + self.spacing * (cells - UVec2::ONE);
+        Some(pixel_rect_to_uv(self.texture_size, position, size))
+    }
+
+    pub fn uv(&self, column: u32, row: u32) -> Option<Rect> {
+        self.region_uv(column, row, UVec2::ONE)
+    }
+
+    pub fn uv_index(&self, index: u32) -> Option<Rect> {
+        let columns = self.columns();
+        if columns == 0 {
+            return None;
+        }
+        self.uv(index % columns, index / columns)
+    }
+
+    pub fn sub_texture(&self, column: u32, row: u32) -> Option<SubTexture2D> {
+        let texture = self.texture.clone()?;
+        Some(SubTexture2D::create(texture, self.uv(column, row)?))
+    }
+
+    pub fn sub_texture_index(&self, index: u32) -> Option<SubTexture2D> {
+        let texture = self.texture.clone()?;
+        Some(SubTexture2D::create(texture, self.uv_index(index)?))
+    }
+
+    // Widened to u64 so huge margins or spacings cannot overflow, cell is never zero.
+    fn fit(&self, total: u32, margin: u32, cell: u32, spacing: u32) -> u32 {
+        let (total, margin, cell, spacing) =
+            (total as u64, margin as u64, cell as u64, spacing as u64);
+        if total < margin * 2 + cell {
+            return 0;
+        }
+        ((total - margin * 2 + spacing) / (cell + spacing)) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atlas_grid() {
+        let atlas = TextureAtlas::from_size(UVec2::new(64, 32), UVec2::new(16, 16)).unwrap();
+        assert_eq!((atlas.columns(), atlas.rows(), atlas.len()), (4, 2, 8));
+        assert_eq!(
+            atlas.uv(1, 0).unwrap(),
+            Rect::from_min_max(Vec2::new(0.25, 0.5), Vec2::new(0.5, 1.0))
+        );
+        assert!(atlas.uv(4, 0).is_none());
+        assert!(atlas.region_uv(3, 0, UVec2::new(2, 1)).is_none());
+        assert!(atlas.region_uv(u32::MAX, 0, UVec2::new(2, 1)).is_none());
+    }
+
+    #[test]
+    fn atlas_margin_and_spacing() {
+        let atlas = TextureAtlas::from_size(UVec2::new(37, 18), UVec2::new(16, 16))
+            .unwrap()
+            .with_margin(UVec2::ONE)
+            .with_spacing(UVec2::new(3, 0));
+        assert_eq!((atlas.columns(), atlas.rows()), (2, 1));
+        assert_eq!(atlas.cell_pixels(1, 0).0, UVec2::new(20, 1));
+    }
+
+    #[test]
+    fn atlas_rejects_empty_cells() {
+        assert!(TextureAtlas::from_size(UVec2::new(64, 64), UVec2::new(0, 16)).is_err());
+        assert!(TextureAtlas::from_size(UVec2::new(64, 64), UVec2::ZERO).is_err());
+    }
+
+    #[test]
+    fn atlas_extreme_margin_and_spacing() {
+        let atlas = TextureAtlas::from_size(UVec2::new(64, 64), UVec2::ONE)
+            .unwrap()
+            .with_margin(UVec2::splat(u32::MAX));
+        assert!(atlas.is_empty());
+        assert!(atlas.uv_index(0).is_none());
+        let atlas = TextureAtlas::from_size(UVec2::new(64, 64), UVec2::splat(16))
+            .unwrap()
+            .with_spacing(UVec2::splat(u32::MAX));
+        assert_eq!((atlas.columns(), atlas.rows()), (1, 1));
+    }
+}
@@ -0,0 +1,13 @@
+use crate::Hexgem::{core::ToAny, error::HexgemResult};
+
+use super::buffer::{IndexBuffer, VertexBuffer};
+
+pub trait VertexArray: ToAny {
+    fn bind(&self);
+    fn unbind(&self);
+    fn add_vertex_buffer(&mut self, vertex_buffer: Box<dyn VertexBuffer>) -> HexgemResult<()>;
+    fn set_index_buffer(&mut self, index_buffer: Box<dyn IndexBuffer>);
+    fn get_vertex_buffers(&self) -> &[Box<dyn VertexBuffer>];
+    fn get_vertex_buffers_mut(&mut self) -> &mut [Box<dyn VertexBuffer>];
+    fn get_index_buffer(&self) -> Option<&dyn IndexBuffer>;
+}
@@ -0,0 +1,244 @@
+use std::rc::Rc;
+
+use glam::{Mat4, Quat, Vec3, Vec4};
+use uuid::Uuid;
+
+use crate::Hexgem::{
+    ecs::HexgemEcs::Entity, renderer::HexgemRenderer::SubTexture2D, timestep::Timestep,
+};
+
+use super::{scene_camera::SceneCamera, scene_graph::Scene};
+
+// Identity that stays the same across saves and loads, unlike the entity handle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Id {
+    pub uuid: Uuid,
+}
+
+impl Id {
+    pub fn create() -> Self {
+        Self {
+            uuid: Uuid::new_v4(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Tag {
+    pub name: String,
+}
+
+impl Tag {
+    pub fn create(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+        }
+    }
+}
+
+// Local translation, rotation and scale relative to the parent. The hierarchy is edited
+// through Scene::set_parent, which keeps both sides of the link in sync, and the world
+// matrix is refreshed by Scene::propagate_transforms every update.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+    parent: Option<Entity>,
+    children: Vec<Entity>,
+    world_matrix: Mat4,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            scale: Vec3::ONE,
+            parent: None,
+            children: vec![],
+            world_matrix: Mat4::IDENTITY,
+        }
+    }
+}
+
+impl Transform {
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self {
+            translation,
+            world_matrix: Mat4::from_translation(translation),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_rotation(mut self, rotation: Quat) -> Self {
+        self.rotation = rotation;
+        self.world_matrix = self.get_local_matrix();
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec3) -> Self {
+        self.scale = scale;
+        self.world_matrix = self.get_local_matrix();
+        self
+    }
+
+    pub fn get_local_matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+
+    pub fn get_world_matrix(&self) -> Mat4 {
+        self.world_matrix
+    }
+
+    pub fn get_parent(&self) -> Option<Entity> {
+        self.parent
+    }
+
+    pub fn get_children(&self) -> &[Entity] {
+        &self.children
+    }
+
+    pub(crate) fn set_parent(&mut self, parent: Option<Entity>) {
+        self.parent = parent;
+    }
+
+    pub(crate) fn add_child(&mut self, child: Entity) {
+        if !self.children.contains(&child) {
+            self.children.push(child);
+        }
+    }
+
+    pub(crate) fn remove_child(&mut self, child: Entity) {
+        self.children.retain(|entity| *entity != child);
+    }
+
+    pub(crate) fn set_world_matrix(&mut self, world_matrix: Mat4) {
+        self.world_matrix = world_matrix;
+    }
+}
+
+#[derive(Clone)]
+pub struct SpriteRenderer {
+    pub color: Vec4,
+    pub texture: Option<SubTexture2D>,
+    // File the texture was loaded from, what gets saved with the scene.
+    pub texture_path: Option<String>,
+    pub tiling: f32,
+}
+
+impl Default for SpriteRenderer {
+    fn default() -> Self {
+        Self {
+            color: Vec4::ONE,
+            texture: None,
+            texture_path: None,
+            tiling: 1.0,
+        }
+    }
+}
+
+impl SpriteRenderer {
+    pub fn create(color: Vec4) -> Self {
+        Self {
+            color,
+            ..Default::default()
+        }
+    }
+
+    pub fn texture(mut self, texture: SubTexture2D) -> Self {
+        self.texture = Some(texture);
+        self
+    }
+
+    pub fn texture_path(mut self, texture_path: &str) -> Self {
+        self.texture_path = Some(texture_path.to_string());
+        self
+    }
+
+    pub fn tiling(mut self, tiling: f32) -> Self {
+        self.tiling = tiling;
+        self
+    }
+}
+
+// The primary camera is the one the runtime renders through. Cameras with a fixed aspect
+// ratio ignore viewport resizes.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Camera {
+    pub camera: SceneCamera,
+    pub primary: bool,
+    pub fixed_aspect_ratio: bool,
+}
+
+impl Camera {
+    pub fn create(camera: SceneCamera) -> Self {
+        Self {
+            camera,
+            primary: false,
+            fixed_aspect_ratio: false,
+        }
+    }
+
+    pub fn primary(mut self, primary: bool) -> Self {
+        self.primary = primary;
+        self
+    }
+
+    pub fn fixed_aspect_ratio(mut self, fixed_aspect_ratio: bool) -> Self {
+        self.fixed_aspect_ratio = fixed_aspect_ratio;
+        self
+    }
+}
+
+// Gameplay code attached to an entity. Scripts are only instantiated while the runtime
+// updates, the scene is free to use from them since the script is taken out of it meanwhile.
+pub trait Script {
+    fn on_create(&mut self, scene: &mut Scene, entity: Entity) {}
+    fn on_update(&mut self, scene: &mut Scene, entity: Entity, timestep: Timestep);
+    fn on_destroy(&mut self, scene: &mut Scene, entity: Entity) {}
+}
+
+// Holds the factory next to the instance, so copies of the scene start from fresh scripts.
+pub struct NativeScript {
+    factory: Rc<dyn Fn() -> Box<dyn Script>>,
+    instance: Option<Box<dyn Script>>,
+}
+
+impl Clone for NativeScript {
+    fn clone(&self) -> Self {
+        Self {
+            factory: self.factory.clone(),
+            instance: None,
+        }
+    }
+}
+
+impl NativeScript {
+    pub fn bind<S: Script + Default + 'static>() -> Self {
+        Self::from_factory(|| Box::new(S::default()))
+    }
+
+    pub fn from_factory<F: Fn() -> Box<dyn Script> + 'static>(factory: F) -> Self {
+        Self {
+            factory: Rc::new(factory),
+            instance: None,
+        }
+    }
+
+    pub fn is_instantiated(&self) -> bool {
+        self.instance.is_some()
+    }
+
+    pub(crate) fn take_instance(&mut self) -> Option<Box<dyn Script>> {
+        self.instance.take()
+    }
+
+    pub(crate) fn instantiate(&self) -> Box<dyn Script> {
+        (self.factory)()
+    }
+
+    pub(crate) fn set_instance(&mut self, instance: Box<dyn Script>) {
+        self.instance = Some(instance);
+    }
+}
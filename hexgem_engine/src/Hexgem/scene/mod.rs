@@ -0,0 +1,13 @@
+mod components;
+mod scene_camera;
+mod scene_graph;
+mod scene_serializer;
+
+pub mod HexgemScene {
+    pub use super::components::{Camera, Id, NativeScript, Script, SpriteRenderer, Tag, Transform};
+    pub use super::scene_camera::{ProjectionType, SceneCamera};
+    pub use super::scene_graph::{Scene, SceneState};
+    pub use super::scene_serializer::{SceneMigration, SceneSerializer, SCENE_FORMAT_VERSION};
+    pub use toml::Table as SceneDocument;
+    pub use uuid::Uuid;
+}
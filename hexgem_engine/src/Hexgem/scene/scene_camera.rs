@@ -0,0 +1,141 @@
+use glam::Mat4;
+
+use crate::Hexgem::math::HexgemMath::{orthographic, perspective};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ProjectionType {
+    Perspective,
+    #[default]
+    Orthographic,
+}
+
+// Projection only, the view comes from the transform of the entity holding the camera.
+// The orthographic size is the visible height in world units.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SceneCamera {
+    projection_type: ProjectionType,
+    perspective_fov: f32,
+    perspective_near: f32,
+    perspective_far: f32,
+    orthographic_size: f32,
+    orthographic_near: f32,
+    orthographic_far: f32,
+    aspect_ratio: f32,
+    projection: Mat4,
+}
+
+impl Default for SceneCamera {
+    fn default() -> Self {
+        let mut camera = Self {
+            projection_type: ProjectionType::Orthographic,
+            perspective_fov: 45f32.to_radians(),
+            perspective_near: 0.01,
+            perspective_far: 1000.0,
+            orthographic_size: 10.0,
+            orthographic_near: -1.0,
+            orthographic_far: 1.0,
+            aspect_ratio: 1.0,
+            projection: Mat4::IDENTITY,
+        };
+        camera.recalculate_projection();
+        camera
+    }
+}
+
+impl SceneCamera {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn set_perspective(&mut self, fov_y: f32, near: f32, far: f32) {
+        self.projection_type = ProjectionType::Perspective;
+        self.perspective_fov = fov_y;
+        self.perspective_near = near;
+        self.perspective_far = far;
+        self.recalculate_projection();
+    }
+
+    pub fn set_orthographic(&mut self, size: f32, near: f32, far: f32) {
+        self.projection_type = ProjectionType::Orthographic;
+        self.orthographic_size = size;
+        self.orthographic_near = near;
+        self.orthographic_far = far;
+        self.recalculate_projection();
+    }
+
+    pub fn set_viewport_size(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.set_aspect_ratio(width as f32 / height as f32);
+    }
+
+    pub fn get_projection_type(&self) -> ProjectionType {
+        self.projection_type
+    }
+
+    pub fn set_projection_type(&mut self, projection_type: ProjectionType) {
+        self.projection_type = projection_type;
+        self.recalculate_projection();
+    }
+
+    pub fn get_perspective_fov(&self) -> f32 {
+        self.perspective_fov
+    }
+
+    pub fn get_perspective_near(&self) -> f32 {
+        self.perspective_near
+    }
+
+    pub fn get_perspective_far(&self) -> f32 {
+        self.perspective_far
+    }
+
+    pub fn get_orthographic_size(&self) -> f32 {
+        self.orthographic_size
+    }
+
+    pub fn get_orthographic_near(&self) -> f32 {
+        self.orthographic_near
+    }
+
+    pub fn get_orthographic_far(&self) -> f32 {
+        self.orthographic_far
+    }
+
+    pub fn get_aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
+
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
+        self.aspect_ratio = aspect_ratio;
+        self.recalculate_projection();
+    }
+
+    pub fn get_projection(&self) -> Mat4 {
+        self.projection
+    }
+
+    fn recalculate_projection(&mut self) {
+        self.projection = match self.projection_type {
+            ProjectionType::Perspective => perspective(
+                self.perspective_fov,
+                self.aspect_ratio,
+                self.perspective_near,
+                self.perspective_far,
+            ),
+            ProjectionType::Orthographic => {
+                let half_height = self.orthographic_size / 2.0;
+                let half_width = half_height * self.aspect_ratio;
+                orthographic(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    self.orthographic_near,
+                    self.orthographic_far,
+                )
+            }
+        };
+    }
+}
@@ -0,0 +1,600 @@
+use std::any::TypeId;
+
+use glam::{Mat4, UVec2};
+use uuid::Uuid;
+
+use crate::{
+    Hexgem::{
+        ecs::HexgemEcs::{Entity, Scheduler, System, SystemStage, World},
+        error::{HexgemError, HexgemResult},
+        renderer::HexgemRenderer::{Camera as ViewCamera, Renderer2D},
+        timestep::Timestep,
+    },
+    HexgemEvent::{Event, EventDispatcher, EventType, WindowResizeEvent},
+};
+
+use super::components::{Camera, Id, NativeScript, SpriteRenderer, Tag, Transform};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SceneState {
+    #[default]
+    Edit,
+    Play,
+}
+
+type ComponentCopier = fn(&World, &mut World);
+
+// A world of entities with the core components, plus the systems updating it. In play mode
+// the runtime works on a copy of the world while the edited one waits to be restored.
+pub struct Scene {
+    world: World,
+    scheduler: Scheduler,
+    state: SceneState,
+    edit_world: Option<World>,
+    viewport_size: UVec2,
+    component_copiers: Vec<(TypeId, ComponentCopier)>,
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        let mut scene = Self {
+            world: World::create(),
+            scheduler: Scheduler::create(),
+            state: SceneState::Edit,
+            edit_world: None,
+            viewport_size: UVec2::ZERO,
+            component_copiers: vec![],
+        };
+        scene.register_component::<Id>();
+        scene.register_component::<Tag>();
+        scene.register_component::<Transform>();
+        scene.register_component::<SpriteRenderer>();
+        scene.register_component::<Camera>();
+        scene.register_component::<NativeScript>();
+        scene
+    }
+}
+
+impl Scene {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn get_world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn get_world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    pub fn get_scheduler(&self) -> &Scheduler {
+        &self.scheduler
+    }
+
+    pub fn get_scheduler_mut(&mut self) -> &mut Scheduler {
+        &mut self.scheduler
+    }
+
+    pub fn add_system<S: System + 'static>(&mut self, name: &'static str, system: S) {
+        self.scheduler.add_system(name, system);
+    }
+
+    pub fn add_system_to_stage<S: System + 'static>(
+        &mut self,
+        stage: SystemStage,
+        name: &'static str,
+        system: S,
+    ) {
+        self.scheduler.add_system_to_stage(stage, name, system);
+    }
+
+    // Components that get copied along with the scene. Only registered components survive
+    // copy() and make it into the world play mode runs on.
+    pub fn register_component<T: Clone + 'static>(&mut self) {
+        let type_id = TypeId::of::<T>();
+        if self.component_copiers.iter().all(|(id, _)| *id != type_id) {
+            self.component_copiers.push((type_id, |source, target| {
+                source.clone_components::<T>(target)
+            }));
+        }
+    }
+
+    pub fn create_entity(&mut self, name: &str) -> Entity {
+        self.create_entity_with_uuid(Uuid::new_v4(), name)
+    }
+
+    pub fn create_entity_with_uuid(&mut self, uuid: Uuid, name: &str) -> Entity {
+        self.world
+            .spawn_with((Id { uuid }, Tag::create(name), Transform::default()))
+    }
+
+    pub fn create_child(&mut self, parent: Entity, name: &str) -> HexgemResult<Entity> {
+        let entity = self.create_entity(name);
+        if let Err(err) = self.set_parent(entity, Some(parent)) {
+            self.world.despawn(entity);
+            return Err(err);
+        }
+        Ok(entity)
+    }
+
+    // Destroys the entity together with its children, false if it was already gone.
+    pub fn destroy_entity(&mut self, entity: Entity) -> bool {
+        if !self.world.is_alive(entity) {
+            return false;
+        }
+        let children = self
+            .world
+            .get::<Transform>(entity)
+            .map(|transform| transform.get_children().to_vec())
+            .unwrap_or_default();
+        for child in children {
+            self.destroy_entity(child);
+        }
+        let _ = self.set_parent(entity, None);
+        let instance = self
+            .world
+            .get_mut::<NativeScript>(entity)
+            .and_then(|mut script| script.take_instance());
+        if let Some(mut instance) = instance {
+            instance.on_destroy(self, entity);
+        }
+        self.world.despawn(entity)
+    }
+
+    pub fn find_entity_by_name(&self, name: &str) -> Option<Entity> {
+        self.world
+            .query::<(Entity, &Tag)>()
+            .iter()
+            .find(|(_, tag)| tag.name == name)
+            .map(|(entity, _)| entity)
+    }
+
+    pub fn find_entity_by_uuid(&self, uuid: Uuid) -> Option<Entity> {
+        self.world
+            .query::<(Entity, &Id)>()
+            .iter()
+            .find(|(_, id)| id.uuid == uuid)
+            .map(|(entity, _)| entity)
+    }
+
+    // Moves the entity under a new parent, or to the root with None. The local transform is
+    // kept, so the entity follows the parent from where it is relative to it.
+    pub fn set_parent(&mut self, child: Entity, parent: Option<Entity>) -> HexgemResult<()> {
+        if !self.world.has::<Transform>(child) {
+            return Err(HexgemError::Scene(format!(
+                "Entity {:?} has no transform to parent",
+                child
+            )));
+        }
+        if let Some(parent) = parent {
+            if !self.world.has::<Transform>(parent) {
+                return Err(HexgemError::Scene(format!(
+                    "Parent {:?} of entity {:?} has no transform",
+                    parent, child
+                )));
+            }
+            let mut ancestor = Some(parent);
+            while let Some(entity) = ancestor {
+                if entity == child {
+                    return Err(HexgemError::Scene(format!(
+                        "Parenting {:?} to {:?} would create a cycle",
+                        child, parent
+                    )));
+                }
+                ancestor = self
+                    .world
+                    .get::<Transform>(entity)
+                    .and_then(|transform| transform.get_parent());
+            }
+        }
+        let previous = self
+            .world
+            .get::<Transform>(child)
+            .and_then(|transform| transform.get_parent());
+        if let Some(mut transform) =
+            previous.and_then(|previous| self.world.get_mut::<Transform>(previous))
+        {
+            transform.remove_child(child);
+        }
+        if let Some(mut transform) =
+            parent.and_then(|parent| self.world.get_mut::<Transform>(parent))
+        {
+            transform.add_child(child);
+        }
+        if let Some(mut transform) = self.world.get_mut::<Transform>(child) {
+            transform.set_parent(parent);
+        }
+        Ok(())
+    }
+
+    // Recomputes world matrices top down from the roots of the hierarchy.
+    pub fn propagate_transforms(&mut self) {
+        crate::profile_scope!("Scene::propagate_transforms");
+        let mut stack: Vec<(Entity, Mat4)> = self
+            .world
+            .query::<(Entity, &Transform)>()
+            .iter()
+            .filter(|(_, transform)| transform.get_parent().is_none())
+            .map(|(entity, _)| (entity, Mat4::IDENTITY))
+            .collect();
+        let mut transforms = self.world.query::<&mut Transform>();
+        while let Some((entity, parent_matrix)) = stack.pop() {
+            let Some(transform) = transforms.get(entity) else {
+                continue;
+            };
+            let world_matrix = parent_matrix * transform.get_local_matrix();
+            transform.set_world_matrix(world_matrix);
+            stack.extend(
+                transform
+                    .get_children()
+                    .iter()
+                    .map(|child| (*child, world_matrix)),
+            );
+        }
+    }
+
+    // Copy of the entities and registered components. Systems, resources and script instances
+    // are not copied.
+    pub fn copy(&self) -> Scene {
+        Scene {
+            world: self.copy_world(),
+            scheduler: Scheduler::create(),
+            state: SceneState::Edit,
+            edit_world: None,
+            viewport_size: self.viewport_size,
+            component_copiers: self.component_copiers.clone(),
+        }
+    }
+
+    pub fn get_state(&self) -> SceneState {
+        self.state
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.state == SceneState::Play
+    }
+
+    // Puts the edited world aside, which stop() brings back, and runs on a copy of it.
+    // Resources move over to the copy.
+    pub fn play(&mut self) {
+        if self.is_playing() {
+            return;
+        }
+        let mut runtime_world = self.copy_world();
+        self.world.move_resources_to(&mut runtime_world);
+        self.edit_world = Some(std::mem::replace(&mut self.world, runtime_world));
+        self.state = SceneState::Play;
+        self.resize_cameras();
+    }
+
+    // Destroys the running scripts and brings back the world as it was when play() was called.
+    // Resources are runtime state and stay as the runtime left them.
+    pub fn stop(&mut self) {
+        if !self.is_playing() {
+            return;
+        }
+        let entities: Vec<Entity> = self
+            .world
+            .query::<(Entity, &NativeScript)>()
+            .iter()
+            .map(|(entity, _)| entity)
+            .collect();
+        for entity in entities {
+            let instance = self
+                .world
+                .get_mut::<NativeScript>(entity)
+                .and_then(|mut script| script.take_instance());
+            if let Some(mut instance) = instance {
+                instance.on_destroy(self, entity);
+            }
+        }
+        if let Some(mut edit_world) = self.edit_world.take() {
+            self.world.move_resources_to(&mut edit_world);
+            self.world = edit_world;
+        }
+        self.state = SceneState::Edit;
+    }
+
+    // Scripts, then systems, then transforms, and the frame is drawn through the primary camera.
+    // Does nothing outside of play mode, so the edited world is never simulated.
+    pub fn on_update_runtime(&mut self, timestep: Timestep) {
+        if !self.is_playing() {
+            return;
+        }
+        crate::profile_scope!("Scene::on_update_runtime");
+        self.update_scripts(timestep);
+        self.scheduler.run(&mut self.world, timestep);
+        self.propagate_transforms();
+        if let Some(view_projection) = self.get_primary_view_projection() {
+            self.render(&view_projection);
+        }
+    }
+
+    // Nothing simulates in the editor, the scene is only drawn through the editor camera.
+    pub fn on_update_editor(&mut self, _timestep: Timestep, camera: &dyn ViewCamera) {
+        crate::profile_scope!("Scene::on_update_editor");
+        self.propagate_transforms();
+        self.render(camera);
+    }
+
+    pub fn get_primary_camera(&self) -> Option<Entity> {
+        self.world
+            .query::<(Entity, &Camera)>()
+            .iter()
+            .find(|(_, camera)| camera.primary)
+            .map(|(entity, _)| entity)
+    }
+
+    pub fn get_viewport_size(&self) -> UVec2 {
+        self.viewport_size
+    }
+
+    pub fn on_viewport_resize(&mut self, width: u32, height: u32) {
+        self.viewport_size = UVec2::new(width, height);
+        self.resize_cameras();
+    }
+
+    pub fn on_event(&mut self, event: &mut Box<dyn Event>) {
+        let event_dispatcher = EventDispatcher::from(event);
+        event_dispatcher.dispatch::<WindowResizeEvent, _>(EventType::WindowResize, |e| {
+            self.on_viewport_resize(e.size.width.max(0) as u32, e.size.height.max(0) as u32);
+            Some(false)
+        });
+    }
+
+    fn copy_world(&self) -> World {
+        let mut world = self.world.clone_entities();
+        for (_, copier) in self.component_copiers.iter() {
+            copier(&self.world, &mut world);
+        }
+        world
+    }
+
+    fn resize_cameras(&mut self) {
+        if self.viewport_size.x == 0 || self.viewport_size.y == 0 {
+            return;
+        }
+        for camera in self.world.query::<&mut Camera>().iter() {
+            if !camera.fixed_aspect_ratio {
+                camera
+                    .camera
+                    .set_viewport_size(self.viewport_size.x, self.viewport_size.y);
+            }
+        }
+    }
+
+    fn update_scripts(&mut self, timestep: Timestep) {
+        let entities: Vec<Entity> = self
+            .world
+            .query::<(Entity, &NativeScript)>()
+            .iter()
+            .map(|(entity, _)| entity)
+            .collect();
+        for entity in entities {
+            // The instance is taken out while it runs, so the script can use the whole scene.
+            let Some((mut instance, created)) =
+                self.world
+                    .get_mut::<NativeScript>(entity)
+                    .map(|mut script| match script.take_instance() {
+                        Some(instance) => (instance, false),
+                        None => (script.instantiate(), true),
+                    })
+            else {
+                continue;
+            };
+            if created {
+                instance.on_create(self, entity);
+            }
+            instance.on_update(self, entity, timestep);
+            // A script that destroyed or replaced its own component is done.
+            let mut instance = Some(instance);
+            if let Some(mut script) = self.world.get_mut::<NativeScript>(entity) {
+                if !script.is_instantiated() {
+                    script.set_instance(instance.take().expect("instance is still here"));
+                }
+            }
+            if let Some(mut instance) = instance {
+                instance.on_destroy(self, entity);
+            }
+        }
+    }
+
+    fn get_primary_view_projection(&self) -> Option<Mat4> {
+        let mut query = self.world.query::<(&Camera, &Transform)>();
+        let view_projection =
+            query
+                .iter()
+                .find(|(camera, _)| camera.primary)
+                .map(|(camera, transform)| {
+                    camera.camera.get_projection() * transform.get_world_matrix().inverse()
+                });
+        view_projection
+    }
+
+    fn render(&self, camera: &dyn ViewCamera) {
+        if !Renderer2D::is_initialized() {
+            return;
+        }
+        Renderer2D::begin_scene(camera);
+        for (transform, sprite) in self.world.query::<(&Transform, &SpriteRenderer)>().iter() {
+            Renderer2D::draw_quad_transform(
+                transform.get_world_matrix(),
+                sprite.color,
+                sprite.texture.as_ref(),
+                sprite.tiling,
+            );
+        }
+        Renderer2D::end_scene();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::*;
+
+    // Not registered with the scene, so copies leave it behind.
+    struct EditorOnly(u32);
+
+    #[test]
+    fn play_runs_on_a_copy_and_stop_restores_the_original() {
+        let mut scene = Scene::create();
+        scene.get_world_mut().insert_resource(1u32);
+        let entity = scene.create_entity("player");
+        scene.get_world_mut().insert(entity, EditorOnly(7)).unwrap();
+
+        scene.play();
+        assert!(scene.is_playing());
+        assert!(!scene.get_world().has::<EditorOnly>(entity));
+        assert_eq!(*scene.get_world().get_resource::<u32>().unwrap(), 1);
+        scene
+            .get_world()
+            .get_mut::<Transform>(entity)
+            .unwrap()
+            .translation
+            .x = 5.0;
+        scene.get_world().get_mut::<Tag>(entity).unwrap().name = "moved".to_string();
+        *scene.get_world().get_resource_mut::<u32>().unwrap() = 2;
+        let spawned = scene.create_entity("spawned");
+
+        scene.stop();
+        assert!(!scene.is_playing());
+        let world = scene.get_world();
+        assert_eq!(world.get::<EditorOnly>(entity).unwrap().0, 7);
+        assert_eq!(world.get::<Transform>(entity).unwrap().translation.x, 0.0);
+        assert_eq!(world.get::<Tag>(entity).unwrap().name, "player");
+        assert!(!world.is_alive(spawned));
+        assert_eq!(*world.get_resource::<u32>().unwrap(), 2);
+    }
+
+    fn translation(scene: &Scene, entity: Entity) -> Vec3 {
+        scene
+            .get_world()
+            .get::<Transform>(entity)
+            .unwrap()
+            .get_world_matrix()
+            .transform_point3(Vec3::ZERO)
+    }
+
+    fn children(scene: &Scene, entity: Entity) -> Vec<Entity> {
+        scene
+            .get_world()
+            .get::<Transform>(entity)
+            .unwrap()
+            .get_children()
+            .to_vec()
+    }
+
+    fn parent(scene: &Scene, entity: Entity) -> Option<Entity> {
+        scene
+            .get_world()
+            .get::<Transform>(entity)
+            .unwrap()
+            .get_parent()
+    }
+
+    #[test]
+    fn world_matrices_propagate_through_the_hierarchy() {
+        let mut scene = Scene::create();
+        let root = scene.create_entity("root");
+        let child = scene.create_child(root, "child").unwrap();
+        let grandchild = scene.create_child(child, "grandchild").unwrap();
+        let world = scene.get_world();
+        world.get_mut::<Transform>(root).unwrap().translation = Vec3::new(1.0, 0.0, 0.0);
+        let mut transform = world.get_mut::<Transform>(child).unwrap();
+        transform.translation = Vec3::new(0.0, 2.0, 0.0);
+        transform.scale = Vec3::splat(2.0);
+        drop(transform);
+        world.get_mut::<Transform>(grandchild).unwrap().translation = Vec3::new(1.0, 0.0, 0.0);
+
+        scene.propagate_transforms();
+        assert!(translation(&scene, root).abs_diff_eq(Vec3::new(1.0, 0.0, 0.0), 1e-6));
+        assert!(translation(&scene, child).abs_diff_eq(Vec3::new(1.0, 2.0, 0.0), 1e-6));
+        assert!(translation(&scene, grandchild).abs_diff_eq(Vec3::new(3.0, 2.0, 0.0), 1e-6));
+
+        // The local transform is kept on reparenting, so the grandchild moves with its new parent.
+        scene.set_parent(grandchild, Some(root)).unwrap();
+        scene.propagate_transforms();
+        assert!(translation(&scene, grandchild).abs_diff_eq(Vec3::new(2.0, 0.0, 0.0), 1e-6));
+    }
+
+    #[test]
+    fn set_parent_rejects_cycles() {
+        let mut scene = Scene::create();
+        let a = scene.create_entity("a");
+        let b = scene.create_child(a, "b").unwrap();
+        let c = scene.create_child(b, "c").unwrap();
+        assert!(scene.set_parent(a, Some(c)).is_err());
+        assert!(scene.set_parent(b, Some(b)).is_err());
+        assert!(scene.create_child(c, "d").is_ok());
+        assert_eq!(parent(&scene, a), None);
+        assert_eq!(children(&scene, a), [b]);
+        assert_eq!(children(&scene, c).len(), 1);
+        assert!(scene.find_entity_by_name("d").is_some());
+    }
+
+    #[test]
+    fn reparenting_moves_the_child_between_parents() {
+        let mut scene = Scene::create();
+        let first = scene.create_entity("first");
+        let second = scene.create_entity("second");
+        let child = scene.create_child(first, "child").unwrap();
+        scene.set_parent(child, Some(second)).unwrap();
+        assert!(children(&scene, first).is_empty());
+        assert_eq!(children(&scene, second), [child]);
+        assert_eq!(parent(&scene, child), Some(second));
+        scene.set_parent(child, None).unwrap();
+        assert!(children(&scene, second).is_empty());
+        assert_eq!(parent(&scene, child), None);
+    }
+
+    #[test]
+    fn destroying_an_entity_destroys_its_children() {
+        let mut scene = Scene::create();
+        let root = scene.create_entity("root");
+        let child = scene.create_child(root, "child").unwrap();
+        let grandchild = scene.create_child(child, "grandchild").unwrap();
+        let sibling = scene.create_child(root, "sibling").unwrap();
+        assert!(scene.destroy_entity(child));
+        assert!(!scene.destroy_entity(child));
+        let world = scene.get_world();
+        assert!(!world.is_alive(child) && !world.is_alive(grandchild));
+        assert!(world.is_alive(root) && world.is_alive(sibling));
+        assert_eq!(children(&scene, root), [sibling]);
+    }
+
+    #[test]
+    fn runtime_updates_only_run_in_play_mode() {
+        let mut scene = Scene::create();
+        scene.get_world_mut().insert_resource(0u32);
+        scene.add_system("count", |world: &mut World, _| {
+            *world.get_resource_mut::<u32>().unwrap() += 1;
+        });
+        scene.on_update_runtime(Timestep::create(0.016));
+        assert_eq!(*scene.get_world().get_resource::<u32>().unwrap(), 0);
+        scene.play();
+        scene.on_update_runtime(Timestep::create(0.016));
+        scene.on_update_runtime(Timestep::create(0.016));
+        assert_eq!(*scene.get_world().get_resource::<u32>().unwrap(), 2);
+    }
+
+    #[test]
+    fn play_and_stop_are_idempotent() {
+        let mut scene = Scene::create();
+        let entity = scene.create_entity("player");
+        scene.stop();
+        assert!(scene.get_world().is_alive(entity));
+        scene.play();
+        scene.get_world().get_mut::<Tag>(entity).unwrap().name = "running".to_string();
+        scene.play();
+        assert_eq!(
+            scene.get_world().get::<Tag>(entity).unwrap().name,
+            "running"
+        );
+        scene.stop();
+        scene.stop();
+        assert_eq!(scene.get_world().get::<Tag>(entity).unwrap().name, "player");
+    }
+}
@@ -0,0 +1,881 @@
+use std::{collections::HashMap, fs, path::Path, rc::Rc};
+
+use glam::{Quat, Vec3, Vec4};
+use log::warn;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use toml::{Table, Value};
+use uuid::Uuid;
+
+use crate::Hexgem::{
+    ecs::HexgemEcs::{Entity, World},
+    error::{HexgemError, HexgemResult},
+    renderer::HexgemRenderer::{SubTexture2D, Texture2D, TextureOptions},
+};
+
+use super::{
+    components::{Camera, Id, SpriteRenderer, Tag, Transform},
+    scene_camera::{ProjectionType, SceneCamera},
+    scene_graph::Scene,
+};
+
+// Version written by a serializer without custom components, bump it together with a migration
+// whenever the layout of the core components changes.
+pub const SCENE_FORMAT_VERSION: u32 = 1;
+
+// Keys of the entity tables used by the serializer itself, custom components cannot use them.
+const RESERVED_KEYS: [&str; 5] = ["uuid", "tag", "transform", "sprite_renderer", "camera"];
+
+// Upgrades a document from one version to the next, before any of it is read.
+pub type SceneMigration = Box<dyn Fn(&mut Table) -> Result<(), String>>;
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TagDocument {
+    name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransformDocument {
+    translation: [f64; 3],
+    // Quaternion as x, y, z, w.
+    rotation: [f64; 4],
+    scale: [f64; 3],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parent: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpriteRendererDocument {
+    color: [f64; 4],
+    tiling: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    texture: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ProjectionDocument {
+    Perspective,
+    Orthographic,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CameraDocument {
+    primary: bool,
+    fixed_aspect_ratio: bool,
+    projection: ProjectionDocument,
+    aspect_ratio: f64,
+    perspective_fov: f64,
+    perspective_near: f64,
+    perspective_far: f64,
+    orthographic_size: f64,
+    orthographic_near: f64,
+    orthographic_far: f64,
+}
+
+// Path below the component key, when the error is about a nested field, and the message.
+type FieldError = (Option<String>, String);
+type SaveComponent = Box<dyn Fn(&World, Entity) -> Option<Result<Value, String>>>;
+type LoadComponent = Box<dyn Fn(&mut World, Entity, Value) -> Result<(), FieldError>>;
+type TextureLoader<'a> = dyn Fn(&str) -> HexgemResult<Rc<Texture2D>> + 'a;
+
+struct ComponentFormat {
+    key: String,
+    save: SaveComponent,
+    load: LoadComponent,
+    register: fn(&mut Scene),
+}
+
+// Writes scenes as TOML, one [[entities]] table per entity with a sub table per component,
+// keyed by the entity's uuid so files diff well and references survive reloads. Native
+// scripts are code and are not saved, custom components are saved once registered.
+pub struct SceneSerializer {
+    version: u32,
+    migrations: HashMap<u32, SceneMigration>,
+    components: Vec<ComponentFormat>,
+}
+
+impl Default for SceneSerializer {
+    fn default() -> Self {
+        Self {
+            version: SCENE_FORMAT_VERSION,
+            migrations: HashMap::new(),
+            components: vec![],
+        }
+    }
+}
+
+impl SceneSerializer {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    // Version written to new files and expected after migrating old ones.
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    // Upgrades documents of version from_version to from_version + 1.
+    pub fn migration<F: Fn(&mut Table) -> Result<(), String> + 'static>(
+        mut self,
+        from_version: u32,
+        migration: F,
+    ) -> Self {
+        self.migrations.insert(from_version, Box::new(migration));
+        self
+    }
+
+    // Saves T under the given key of each entity having it, and registers it with loaded scenes.
+    // Panics when the key belongs to a core component or another registered component, which
+    // would otherwise shadow it.
+    pub fn component<T: Serialize + DeserializeOwned + Clone + 'static>(
+        mut self,
+        key: &str,
+    ) -> Self {
+        assert!(
+            !RESERVED_KEYS.contains(&key),
+            "Scene component key {} is reserved for a core component",
+            key
+        );
+        assert!(
+            self.components.iter().all(|format| format.key != key),
+            "Scene component key {} is already registered",
+            key
+        );
+        self.components.push(ComponentFormat {
+            key: key.to_string(),
+            save: Box::new(|world, entity| {
+                let component = world.get::<T>(entity)?;
+                Some(Value::try_from(&*component).map_err(|err| err.to_string()))
+            }),
+            load: Box::new(|world, entity, value| {
+                let component = parse::<T>(value)?;
+                world
+                    .insert(entity, component)
+                    .map(|_| ())
+                    .map_err(|err| (None, err.to_string()))
+            }),
+            register: |scene| scene.register_component::<T>(),
+        });
+        self
+    }
+
+    pub fn get_version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn serialize(&self, scene: &Scene) -> HexgemResult<String> {
+        let world = scene.get_world();
+        let entities = world.get_entities();
+        // A made up uuid would change on every save and break references to the entity, so
+        // every entity needs its own Id.
+        let mut uuids: HashMap<Entity, Uuid> = HashMap::new();
+        for entity in entities.iter() {
+            let uuid = world.get::<Id>(*entity).map(|id| id.uuid).ok_or_else(|| {
+                HexgemError::SceneFormat {
+                    file: "<output>".to_string(),
+                    entity: Some(format!("{:?}", entity)),
+                    field: Some("uuid".to_string()),
+                    message: "entity has no Id component".to_string(),
+                }
+            })?;
+            uuids.insert(*entity, uuid);
+        }
+        let mut documents = vec![];
+        for entity in entities {
+            let uuid = uuids[&entity];
+            let error = |field: &str, message: String| HexgemError::SceneFormat {
+                file: "<output>".to_string(),
+                entity: Some(uuid.to_string()),
+                field: Some(field.to_string()),
+                message,
+            };
+            let mut table = Table::new();
+            table.insert("uuid".to_string(), Value::String(uuid.to_string()));
+            if let Some(tag) = world.get::<Tag>(entity) {
+                let document = TagDocument {
+                    name: tag.name.clone(),
+                };
+                table.insert(
+                    "tag".to_string(),
+                    to_value(&document).map_err(|err| error("tag", err))?,
+                );
+            }
+            if let Some(transform) = world.get::<Transform>(entity) {
+                let document = TransformDocument {
+                    translation: vec3_to_array(transform.translation),
+                    rotation: quat_to_array(transform.rotation),
+                    scale: vec3_to_array(transform.scale),
+                    parent: transform
+                        .get_parent()
+                        .and_then(|parent| uuids.get(&parent).copied()),
+                };
+                table.insert(
+                    "transform".to_string(),
+                    to_value(&document).map_err(|err| error("transform", err))?,
+                );
+            }
+            if let Some(sprite) = world.get::<SpriteRenderer>(entity) {
+                let document = SpriteRendererDocument {
+                    color: vec4_to_array(sprite.color),
+                    tiling: f32_to_f64(sprite.tiling),
+                    texture: sprite.texture_path.clone(),
+                };
+                table.insert(
+                    "sprite_renderer".to_string(),
+                    to_value(&document).map_err(|err| error("sprite_renderer", err))?,
+                );
+            }
+            if let Some(camera) = world.get::<Camera>(entity) {
+                let scene_camera = &camera.camera;
+                let document = CameraDocument {
+                    primary: camera.primary,
+                    fixed_aspect_ratio: camera.fixed_aspect_ratio,
+                    projection: match scene_camera.get_projection_type() {
+                        ProjectionType::Perspective => ProjectionDocument::Perspective,
+                        ProjectionType::Orthographic => ProjectionDocument::Orthographic,
+                    },
+                    aspect_ratio: f32_to_f64(scene_camera.get_aspect_ratio()),
+                    perspective_fov: f32_to_f64(scene_camera.get_perspective_fov()),
+                    perspective_near: f32_to_f64(scene_camera.get_perspective_near()),
+                    perspective_far: f32_to_f64(scene_camera.get_perspective_far()),
+                    orthographic_size: f32_to_f64(scene_camera.get_orthographic_size()),
+                    orthographic_near: f32_to_f64(scene_camera.get_orthographic_near()),
+                    orthographic_far: f32_to_f64(scene_camera.get_orthographic_far()),
+                };
+                table.insert(
+                    "camera".to_string(),
+                    to_value(&document).map_err(|err| error("camera", err))?,
+                );
+            }
+            for format in self.components.iter() {
+                if let Some(value) = (format.save)(world, entity) {
+                    table.insert(
+                        format.key.clone(),
+                        value.map_err(|err| error(&format.key, err))?,
+                    );
+                }
+            }
+            documents.push(Value::Table(table));
+        }
+        let mut document = Table::new();
+        document.insert("version".to_string(), Value::Integer(self.version as i64));
+        document.insert("entities".to_string(), Value::Array(documents));
+        toml::to_string(&document).map_err(|err| HexgemError::SceneFormat {
+            file: "<output>".to_string(),
+            entity: None,
+            field: None,
+            message: err.to_string(),
+        })
+    }
+
+    pub fn serialize_to_file<P: AsRef<Path>>(&self, scene: &Scene, path: P) -> HexgemResult<()> {
+        let path = path.as_ref();
+        let source = self.serialize(scene)?;
+        fs::write(path, source).map_err(|err| HexgemError::SceneFormat {
+            file: path.display().to_string(),
+            entity: None,
+            field: None,
+            message: err.to_string(),
+        })
+    }
+
+    pub fn deserialize(&self, source: &str) -> HexgemResult<Scene> {
+        self.load(source, "<string>", &load_texture_file)
+    }
+
+    // Sprite texture paths are relative to the scene file, like with SceneLoader.
+    pub fn deserialize_file<P: AsRef<Path>>(&self, path: P) -> HexgemResult<Scene> {
+        let path = path.as_ref();
+        let file = path.display().to_string();
+        let source = fs::read_to_string(path).map_err(|err| HexgemError::SceneFormat {
+            file: file.clone(),
+            entity: None,
+            field: None,
+            message: err.to_string(),
+        })?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+        self.load(&source, &file, &|texture: &str| {
+            load_texture_file(&directory.join(texture).to_string_lossy())
+        })
+    }
+
+    // Sprite textures are loaded through load_texture instead of straight from disk, so scenes
+    // can live somewhere other than the filesystem and sprites can share their textures.
+    pub fn deserialize_with<F>(
+        &self,
+        source: &str,
+        file: &str,
+        load_texture: F,
+    ) -> HexgemResult<Scene>
+    where
+        F: Fn(&str) -> HexgemResult<Rc<Texture2D>>,
+    {
+        self.load(source, file, &load_texture)
+    }
+
+    fn load(&self, source: &str, file: &str, load_texture: &TextureLoader) -> HexgemResult<Scene> {
+        let error =
+            |entity: Option<&str>, field: Option<&str>, message: String| HexgemError::SceneFormat {
+                file: file.to_string(),
+                entity: entity.map(str::to_string),
+                field: field.map(str::to_string),
+                message,
+            };
+        let mut document: Table =
+            toml::from_str(source).map_err(|err| error(None, None, err.to_string()))?;
+        self.migrate(&mut document)
+            .map_err(|(field, message)| error(None, field, message))?;
+        let entities = match document.remove("entities") {
+            None => vec![],
+            Some(Value::Array(entities)) => entities,
+            Some(_) => {
+                return Err(error(
+                    None,
+                    Some("entities"),
+                    "expected an array of tables".to_string(),
+                ))
+            }
+        };
+
+        let mut scene = Scene::create();
+        for format in self.components.iter() {
+            (format.register)(&mut scene);
+        }
+        let mut created: HashMap<Uuid, Entity> = HashMap::new();
+        let mut parents = vec![];
+        for (index, value) in entities.into_iter().enumerate() {
+            let Value::Table(mut table) = value else {
+                return Err(error(
+                    Some(&format!("#{}", index)),
+                    None,
+                    "expected a table".to_string(),
+                ));
+            };
+            let uuid = match table.remove("uuid") {
+                Some(Value::String(uuid)) => Uuid::parse_str(&uuid).map_err(|err| {
+                    error(Some(&format!("#{}", index)), Some("uuid"), err.to_string())
+                })?,
+                Some(_) => {
+                    return Err(error(
+                        Some(&format!("#{}", index)),
+                        Some("uuid"),
+                        "expected a string".to_string(),
+                    ))
+                }
+                None => {
+                    return Err(error(
+                        Some(&format!("#{}", index)),
+                        Some("uuid"),
+                        "missing field".to_string(),
+                    ))
+                }
+            };
+            let label = uuid.to_string();
+            if created.contains_key(&uuid) {
+                return Err(error(
+                    Some(&label),
+                    Some("uuid"),
+                    "used by another entity".to_string(),
+                ));
+            }
+            let world = scene.get_world_mut();
+            let entity = world.spawn();
+            let _ = world.insert(entity, Id { uuid });
+            created.insert(uuid, entity);
+            for (key, value) in table {
+                let field_error = |(path, message): FieldError| {
+                    let field = match path {
+                        Some(path) => format!("{}.{}", key, path),
+                        None => key.clone(),
+                    };
+                    error(Some(&label), Some(&field), message)
+                };
+                match key.as_str() {
+                    "tag" => {
+                        let document = parse::<TagDocument>(value).map_err(field_error)?;
+                        let _ = world.insert(entity, Tag::create(&document.name));
+                    }
+                    "transform" => {
+                        let document = parse::<TransformDocument>(value).map_err(field_error)?;
+                        let transform =
+                            Transform::from_translation(array_to_vec3(document.translation))
+                                .with_rotation(array_to_quat(document.rotation))
+                                .with_scale(array_to_vec3(document.scale));
+                        let _ = world.insert(entity, transform);
+                        if let Some(parent) = document.parent {
+                            parents.push((entity, label.clone(), parent));
+                        }
+                    }
+                    "sprite_renderer" => {
+                        let document =
+                            parse::<SpriteRendererDocument>(value).map_err(field_error)?;
+                        let _ =
+                            world.insert(entity, load_sprite(document, file, &label, load_texture));
+                    }
+                    "camera" => {
+                        let document = parse::<CameraDocument>(value).map_err(field_error)?;
+                        let _ = world.insert(entity, load_camera(document));
+                    }
+                    _ => {
+                        let format = self
+                            .components
+                            .iter()
+                            .find(|format| format.key == key)
+                            .ok_or_else(|| field_error((None, "unknown component".to_string())))?;
+                        (format.load)(world, entity, value).map_err(field_error)?;
+                    }
+                }
+            }
+        }
+        for (entity, label, parent) in parents {
+            let parent_entity = *created.get(&parent).ok_or_else(|| {
+                error(
+                    Some(&label),
+                    Some("transform.parent"),
+                    format!("no entity has the uuid {}", parent),
+                )
+            })?;
+            scene
+                .set_parent(entity, Some(parent_entity))
+                .map_err(|err| error(Some(&label), Some("transform.parent"), err.to_string()))?;
+        }
+        scene.propagate_transforms();
+        Ok(scene)
+    }
+
+    // Runs the migrations in order until the document reaches the serializer's version.
+    fn migrate(&self, document: &mut Table) -> Result<(), (Option<&'static str>, String)> {
+        let mut version = match document.get("version") {
+            Some(Value::Integer(version)) if (0..=u32::MAX as i64).contains(version) => {
+                *version as u32
+            }
+            Some(_) => {
+                return Err((
+                    Some("version"),
+                    "expected a non-negative integer".to_string(),
+                ))
+            }
+            None => return Err((Some("version"), "missing field".to_string())),
+        };
+        if version > self.version {
+            return Err((
+                Some("version"),
+                format!(
+                    "version {} is newer than the supported version {}",
+                    version, self.version
+                ),
+            ));
+        }
+        while version < self.version {
+            let migration = self.migrations.get(&version).ok_or_else(|| {
+                (
+                    Some("version"),
+                    format!("no migration from version {}", version),
+                )
+            })?;
+            migration(document).map_err(|message| {
+                (
+                    None,
+                    format!("migrating from version {}: {}", version, message),
+                )
+            })?;
+            version += 1;
+        }
+        document.insert("version".to_string(), Value::Integer(version as i64));
+        Ok(())
+    }
+}
+
+fn parse<T: DeserializeOwned>(value: Value) -> Result<T, FieldError> {
+    serde_path_to_error::deserialize(value).map_err(|err| {
+        let path = err.path().to_string();
+        let path = (path != ".").then_some(path);
+        (path, err.into_inner().message().to_string())
+    })
+}
+
+fn to_value<T: Serialize>(document: &T) -> Result<Value, String> {
+    Value::try_from(document).map_err(|err| err.to_string())
+}
+
+// A missing texture leaves the sprite untextured rather than failing the whole scene.
+fn load_sprite(
+    document: SpriteRendererDocument,
+    file: &str,
+    label: &str,
+    load_texture: &TextureLoader,
+) -> SpriteRenderer {
+    let mut sprite =
+        SpriteRenderer::create(array_to_vec4(document.color)).tiling(document.tiling as f32);
+    if let Some(path) = document.texture {
+        match load_texture(&path) {
+            Ok(texture) => sprite = sprite.texture(SubTexture2D::whole(texture)),
+            Err(err) => warn!("Scene {}, entity {}: {}", file, label, err),
+        }
+        sprite = sprite.texture_path(&path);
+    }
+    sprite
+}
+
+fn load_texture_file(path: &str) -> HexgemResult<Rc<Texture2D>> {
+    Texture2D::from_file(path, TextureOptions::default()).map(Rc::new)
+}
+
+fn load_camera(document: CameraDocument) -> Camera {
+    let mut scene_camera = SceneCamera::create();
+    let perspective = (
+        document.perspective_fov as f32,
+        document.perspective_near as f32,
+        document.perspective_far as f32,
+    );
+    let orthographic = (
+        document.orthographic_size as f32,
+        document.orthographic_near as f32,
+        document.orthographic_far as f32,
+    );
+    // The last projection set is the active one.
+    match document.projection {
+        ProjectionDocument::Perspective => {
+            scene_camera.set_orthographic(orthographic.0, orthographic.1, orthographic.2);
+            scene_camera.set_perspective(perspective.0, perspective.1, perspective.2);
+        }
+        ProjectionDocument::Orthographic => {
+            scene_camera.set_perspective(perspective.0, perspective.1, perspective.2);
+            scene_camera.set_orthographic(orthographic.0, orthographic.1, orthographic.2);
+        }
+    }
+    scene_camera.set_aspect_ratio(document.aspect_ratio as f32);
+    Camera::create(scene_camera)
+        .primary(document.primary)
+        .fixed_aspect_ratio(document.fixed_aspect_ratio)
+}
+
+// Goes through the shortest decimal form, so 0.1 is written as 0.1 rather than as the f64
+// closest to the f32, and still reads back to the same f32.
+fn f32_to_f64(value: f32) -> f64 {
+    value.to_string().parse().unwrap_or(value as f64)
+}
+
+fn vec3_to_array(value: Vec3) -> [f64; 3] {
+    value.to_array().map(f32_to_f64)
+}
+
+fn vec4_to_array(value: Vec4) -> [f64; 4] {
+    value.to_array().map(f32_to_f64)
+}
+
+fn quat_to_array(value: Quat) -> [f64; 4] {
+    value.to_array().map(f32_to_f64)
+}
+
+fn array_to_vec3(value: [f64; 3]) -> Vec3 {
+    Vec3::from_array(value.map(|value| value as f32))
+}
+
+fn array_to_vec4(value: [f64; 4]) -> Vec4 {
+    Vec4::from_array(value.map(|value| value as f32))
+}
+
+// Hand edited rotations may drift off unit length.
+fn array_to_quat(value: [f64; 4]) -> Quat {
+    let rotation = Quat::from_array(value.map(|value| value as f32));
+    if rotation.is_normalized() {
+        rotation
+    } else if rotation.length_squared() > f32::EPSILON {
+        rotation.normalize()
+    } else {
+        Quat::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hexgem::renderer::HexgemRenderer::{ImageData, NullRendererApi, RenderCommand};
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Health {
+        current: i32,
+        max: i32,
+    }
+
+    fn serializer() -> SceneSerializer {
+        SceneSerializer::create().component::<Health>("health")
+    }
+
+    fn no_textures(path: &str) -> HexgemResult<Rc<Texture2D>> {
+        Err(HexgemError::Texture(format!("{} is not available", path)))
+    }
+
+    fn load(serializer: &SceneSerializer, source: &str) -> HexgemResult<Scene> {
+        serializer.deserialize_with(source, "level.toml", no_textures)
+    }
+
+    fn uuid_of(scene: &Scene, entity: Entity) -> Uuid {
+        scene.get_world().get::<Id>(entity).unwrap().uuid
+    }
+
+    fn sample_scene() -> Scene {
+        let mut scene = Scene::create();
+        let root = scene.create_entity("root");
+        let child = scene.create_child(root, "child").unwrap();
+        scene.create_child(child, "grandchild").unwrap();
+        let camera = scene.create_entity("camera");
+        let world = scene.get_world_mut();
+        {
+            let mut transform = world.get_mut::<Transform>(root).unwrap();
+            transform.translation = Vec3::new(1.5, -2.0, 0.1);
+            transform.rotation = Quat::from_rotation_z(0.3);
+            transform.scale = Vec3::new(2.0, 2.0, 1.0);
+        }
+        world.get_mut::<Transform>(child).unwrap().translation = Vec3::new(0.0, 3.0, 0.0);
+        world
+            .insert(
+                child,
+                SpriteRenderer::create(Vec4::new(0.2, 0.4, 0.6, 0.8))
+                    .tiling(2.5)
+                    .texture_path("textures/crate.png"),
+            )
+            .unwrap();
+        world
+            .insert(
+                child,
+                Health {
+                    current: 7,
+                    max: 10,
+                },
+            )
+            .unwrap();
+        let mut scene_camera = SceneCamera::create();
+        scene_camera.set_perspective(0.9, 0.05, 500.0);
+        scene_camera.set_orthographic(12.0, -5.0, 5.0);
+        scene_camera.set_aspect_ratio(1.5);
+        world
+            .insert(
+                camera,
+                Camera::create(scene_camera)
+                    .primary(true)
+                    .fixed_aspect_ratio(true),
+            )
+            .unwrap();
+        scene
+    }
+
+    fn assert_same_entity(original: &Scene, loaded: &Scene, entity: Entity) {
+        let uuid = uuid_of(original, entity);
+        let other = loaded.find_entity_by_uuid(uuid).unwrap();
+        let (world, other_world) = (original.get_world(), loaded.get_world());
+        assert_eq!(
+            world.get::<Tag>(entity).unwrap().name,
+            other_world.get::<Tag>(other).unwrap().name
+        );
+        let (transform, other_transform) = (
+            world.get::<Transform>(entity).unwrap(),
+            other_world.get::<Transform>(other).unwrap(),
+        );
+        assert_eq!(transform.translation, other_transform.translation);
+        assert_eq!(transform.rotation, other_transform.rotation);
+        assert_eq!(transform.scale, other_transform.scale);
+        assert_eq!(
+            transform
+                .get_parent()
+                .map(|parent| uuid_of(original, parent)),
+            other_transform
+                .get_parent()
+                .map(|parent| uuid_of(loaded, parent))
+        );
+        assert_eq!(
+            transform.get_world_matrix(),
+            other_transform.get_world_matrix()
+        );
+        assert_eq!(
+            world.get::<Health>(entity).as_deref(),
+            other_world.get::<Health>(other).as_deref()
+        );
+        let sprites = (
+            world.get::<SpriteRenderer>(entity),
+            other_world.get::<SpriteRenderer>(other),
+        );
+        if let (Some(sprite), Some(other_sprite)) = (&sprites.0, &sprites.1) {
+            assert_eq!(sprite.color, other_sprite.color);
+            assert_eq!(sprite.tiling, other_sprite.tiling);
+            assert_eq!(sprite.texture_path, other_sprite.texture_path);
+        }
+        assert_eq!(sprites.0.is_some(), sprites.1.is_some());
+        let cameras = (
+            world.get::<Camera>(entity),
+            other_world.get::<Camera>(other),
+        );
+        if let (Some(camera), Some(other_camera)) = (&cameras.0, &cameras.1) {
+            assert_eq!(camera.primary, other_camera.primary);
+            assert_eq!(camera.fixed_aspect_ratio, other_camera.fixed_aspect_ratio);
+            assert_eq!(
+                camera.camera.get_projection(),
+                other_camera.camera.get_projection()
+            );
+            assert_eq!(
+                camera.camera.get_perspective_fov(),
+                other_camera.camera.get_perspective_fov()
+            );
+            assert_eq!(
+                camera.camera.get_perspective_far(),
+                other_camera.camera.get_perspective_far()
+            );
+        }
+        assert_eq!(cameras.0.is_some(), cameras.1.is_some());
+    }
+
+    fn scene_format_error(result: HexgemResult<Scene>) -> (String, Option<String>, Option<String>) {
+        match result {
+            Err(HexgemError::SceneFormat {
+                file,
+                entity,
+                field,
+                ..
+            }) => (file, entity, field),
+            Err(err) => panic!("Expected a scene format error, got {}", err),
+            Ok(_) => panic!("Expected the scene to be rejected"),
+        }
+    }
+
+    #[test]
+    fn file_textures_resolve_next_to_the_scene() {
+        RenderCommand::init(NullRendererApi);
+        let dir = std::env::temp_dir().join(format!("hexgem_scene_{}", std::process::id()));
+        fs::create_dir_all(dir.join("textures")).unwrap();
+        ImageData::filled(2, 2, [255; 4])
+            .unwrap()
+            .save(dir.join("textures/crate.png"))
+            .unwrap();
+        let path = dir.join("level.toml");
+        serializer()
+            .serialize_to_file(&sample_scene(), &path)
+            .unwrap();
+
+        let scene = serializer().deserialize_file(&path).unwrap();
+        let child = scene.find_entity_by_name("child").unwrap();
+        let sprite = scene.get_world().get::<SpriteRenderer>(child).unwrap();
+        assert!(sprite.texture.is_some());
+        assert_eq!(sprite.texture_path.as_deref(), Some("textures/crate.png"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn round_trip_keeps_every_component() {
+        let serializer = serializer();
+        let mut scene = sample_scene();
+        scene.propagate_transforms();
+        let source = serializer.serialize(&scene).unwrap();
+        let loaded = load(&serializer, &source).unwrap();
+
+        assert_eq!(
+            loaded.get_world().get_entity_count(),
+            scene.get_world().get_entity_count()
+        );
+        for entity in scene.get_world().get_entities() {
+            assert_same_entity(&scene, &loaded, entity);
+        }
+        // Saving the loaded scene again gives the same file.
+        assert_eq!(serializer.serialize(&loaded).unwrap(), source);
+    }
+
+    #[test]
+    fn entities_without_an_id_are_rejected() {
+        let mut scene = Scene::create();
+        scene.get_world_mut().spawn_with((Tag::create("nameless"),));
+        match SceneSerializer::create().serialize(&scene) {
+            Err(HexgemError::SceneFormat { field, .. }) => {
+                assert_eq!(field.as_deref(), Some("uuid"))
+            }
+            _ => panic!("Expected the entity without an Id to be rejected"),
+        }
+    }
+
+    #[test]
+    fn version_zero_documents_are_migrated() {
+        // Version 0 kept the name directly on the entity.
+        let source = r#"
+            version = 0
+
+            [[entities]]
+            uuid = "6f1c7a52-3d0e-4c55-9a51-1f5e2b7c9d10"
+            name = "legacy"
+        "#;
+        let serializer = SceneSerializer::create().migration(0, |document| {
+            let Some(Value::Array(entities)) = document.get_mut("entities") else {
+                return Err("entities is not an array".to_string());
+            };
+            for entity in entities.iter_mut().filter_map(Value::as_table_mut) {
+                if let Some(name) = entity.remove("name") {
+                    let mut tag = Table::new();
+                    tag.insert("name".to_string(), name);
+                    entity.insert("tag".to_string(), Value::Table(tag));
+                }
+            }
+            Ok(())
+        });
+        let scene = load(&serializer, source).unwrap();
+        assert!(scene.find_entity_by_name("legacy").is_some());
+
+        let (_, entity, field) = scene_format_error(load(&SceneSerializer::create(), source));
+        assert_eq!(entity, None);
+        assert_eq!(field.as_deref(), Some("version"));
+    }
+
+    #[test]
+    fn malformed_input_reports_file_entity_and_field() {
+        let uuid = "0b5d2c1e-8f47-4a3b-b6d9-2e7f1a9c4d33";
+        let document = |component: &str| {
+            format!(
+                "version = 1\n\n[[entities]]\nuuid = \"{}\"\n\n{}\n",
+                uuid, component
+            )
+        };
+        let serializer = serializer();
+
+        let (file, entity, field) = scene_format_error(load(
+            &serializer,
+            &document(
+                "[entities.transform]\ntranslation = [0.0, 0.0, 0.0]\n\
+                 rotation = [0.0, 0.0, 0.0, 1.0]\nscale = [1.0, \"big\", 1.0]",
+            ),
+        ));
+        assert_eq!(file, "level.toml");
+        assert_eq!(entity.as_deref(), Some(uuid));
+        assert_eq!(field.as_deref(), Some("transform.scale[1]"));
+
+        let (_, entity, field) = scene_format_error(load(
+            &serializer,
+            &document("[entities.health]\ncurrent = 3"),
+        ));
+        assert_eq!(entity.as_deref(), Some(uuid));
+        assert_eq!(field.as_deref(), Some("health"));
+
+        let (_, _, field) = scene_format_error(load(
+            &serializer,
+            &document("[entities.inventory]\nslots = 4"),
+        ));
+        assert_eq!(field.as_deref(), Some("inventory"));
+
+        let (file, entity, field) = scene_format_error(load(&serializer, "version = "));
+        assert_eq!(file, "level.toml");
+        assert_eq!((entity, field), (None, None));
+    }
+
+    #[test]
+    #[should_panic(expected = "reserved")]
+    fn custom_components_cannot_shadow_core_keys() {
+        let _ = SceneSerializer::create().component::<Health>("transform");
+    }
+
+    #[test]
+    #[should_panic(expected = "already registered")]
+    fn custom_component_keys_are_unique() {
+        let _ = serializer().component::<Health>("health");
+    }
+}
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Default)]
+pub struct Timestep(f32);
+
+impl Timestep {
+    pub fn create(seconds: f32) -> Self {
+        Self(seconds)
+    }
+
+    pub fn get_seconds(&self) -> f32 {
+        self.0
+    }
+
+    pub fn get_milliseconds(&self) -> f32 {
+        self.0 * 1000.0
+    }
+}
+
+impl From<Duration> for Timestep {
+    fn from(value: Duration) -> Self {
+        Self(value.as_secs_f32())
+    }
+}
+
+impl From<Timestep> for f32 {
+    fn from(value: Timestep) -> Self {
+        value.0
+    }
+}
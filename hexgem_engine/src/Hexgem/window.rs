@@ -1,12 +1,18 @@
 use glfw::{flush_messages, Glfw, WindowEvent};
 use log::info;
 
-use crate::HexgemEvent::{
-    Event, KeyboardEvent, MouseButtonEvent, MouseMoveEvent, MouseScrollEvent, NoneEvent,
-    WindowCloseEvent, WindowFocusEvent, WindowMoveEvent, WindowResizeEvent,
+use crate::{
+    HexgemEvent::{
+        Event, KeyboardEvent, MouseButtonEvent, MouseMoveEvent, MouseScrollEvent, NoneEvent,
+        WindowCloseEvent, WindowFocusEvent, WindowMoveEvent, WindowResizeEvent,
+    },
+    HexgemRenderer::{ImageData, RenderCommand},
 };
 
-use super::core::{Position, Size};
+use super::{
+    core::{Position, Size},
+    error::HexgemResult,
+};
 
 pub struct WindowProps {
     pub title: &'static str,
@@ -24,8 +30,29 @@ impl Default for WindowProps {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowBackend {
+    Glfw,
+    Sdl,
+    // Renders with the software renderer, closes after frame_limit frames when set.
+    Headless { frame_limit: Option<u64> },
+}
+
+impl Default for WindowBackend {
+    fn default() -> Self {
+        #[cfg(not(target_os = "macos"))]
+        {
+            WindowBackend::Glfw
+        }
+        #[cfg(target_os = "macos")]
+        {
+            WindowBackend::Sdl
+        }
+    }
+}
+
 pub trait Window {
-    fn create(props: WindowProps) -> Box<dyn Window>
+    fn create(props: WindowProps) -> HexgemResult<Box<dyn Window>>
     where
         Self: Sized;
     fn is_vsync(&self) -> bool;
@@ -38,6 +65,18 @@ pub trait Window {
     // fn get_window(&mut self) -> &mut glfw::PWindow;
     fn on_update(&mut self, callback: &mut dyn FnMut(Box<dyn Event>, Box<&mut dyn Window>));
     fn set_vsync(&mut self, enabled: bool);
+    fn is_cursor_locked(&self) -> bool;
+    // Hides the cursor and keeps it inside the window, mouse moves keep reporting deltas.
+    fn set_cursor_locked(&mut self, locked: bool);
+    // Reads the back buffer, call it after rendering and before the buffers are swapped.
+    fn capture(&self) -> HexgemResult<ImageData> {
+        RenderCommand::read_pixels(
+            0,
+            0,
+            self.get_width().max(0) as u32,
+            self.get_height().max(0) as u32,
+        )
+    }
     // fn event_callback(&self) -> &dyn FnMut(Box<dyn Event>);
     // fn set_event_callback(&mut self, callback: Box<dyn FnMut(Box<dyn Event>)>);
 }
@@ -0,0 +1,97 @@
+use std::{env, process::ExitCode};
+
+use hexgem_engine::{
+    HexgemError,
+    HexgemPack::{Pack, PackBuilder, PackCompression},
+    HexgemResult,
+};
+
+const USAGE: &str = "Usage:
+    hexgem-pack build <directory> <output> [--store]
+    hexgem-pack list <pack>
+    hexgem-pack verify <pack>
+    hexgem-pack extract <pack> <directory>
+
+build packs every file below the directory, --store skips compression.";
+
+fn build(directory: &str, output: &str, store: bool) -> HexgemResult<()> {
+    let compression = if store {
+        PackCompression::None
+    } else {
+        PackCompression::Deflate
+    };
+    let mut builder = PackBuilder::create().compression(compression);
+    let count = builder.add_directory(directory)?;
+    builder.write_to_file(output)?;
+    println!("Packed {} files from {} into {}", count, directory, output);
+    Ok(())
+}
+
+fn list(path: &str) -> HexgemResult<()> {
+    let pack = Pack::open(path)?;
+    println!(
+        "{:<10} {:<8} {:>12} {:>12} {:>10}  name",
+        "kind", "storage", "size", "stored", "crc32"
+    );
+    for entry in pack.get_entries() {
+        println!(
+            "{:<10} {:<8} {:>12} {:>12} {:>10x}  {}",
+            format!("{:?}", entry.get_kind()),
+            format!("{:?}", entry.get_compression()),
+            entry.get_size(),
+            entry.get_stored_size(),
+            entry.get_checksum(),
+            entry.get_name()
+        );
+    }
+    println!("{} entries", pack.get_entries().len());
+    Ok(())
+}
+
+fn verify(path: &str) -> HexgemResult<()> {
+    let pack = Pack::open(path)?;
+    pack.verify()?;
+    println!(
+        "{} entries of {} are intact",
+        pack.get_entries().len(),
+        path
+    );
+    Ok(())
+}
+
+fn extract(path: &str, directory: &str) -> HexgemResult<()> {
+    let pack = Pack::open(path)?;
+    let written = pack.extract(directory)?;
+    println!("Extracted {} files into {}", written.len(), directory);
+    Ok(())
+}
+
+fn run(args: &[String]) -> HexgemResult<()> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["build", directory, output] => build(directory, output, false),
+        ["build", directory, output, "--store"] => build(directory, output, true),
+        ["list", path] => list(path),
+        ["verify", path] => verify(path),
+        ["extract", path, directory] => extract(path, directory),
+        _ => Err(HexgemError::Pack(format!(
+            "Unexpected arguments\n\n{}",
+            USAGE
+        ))),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() || args[0] == "--help" || args[0] == "-h" {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,12 +1,15 @@
 use hexgem_engine::{
-    info, Application, EguiLayer, HexgemApp,
+    error, info, Application, EguiLayer, HexgemApp,
     HexgemEvent::{Event, EventType},
-    HexgemLogger, Layer, Window,
+    HexgemLogger,
+    HexgemMath::{Size, Vec2, Vec3, Vec4},
+    HexgemRenderer::{OrthographicCameraController, Quad, Renderer2D},
+    HexgemResult, Layer, Timestep, Window,
 };
 
 struct Sandbox {}
 impl HexgemApp for Sandbox {
-    fn application() -> Application {
+    fn application() -> HexgemResult<Application> {
         Application::create()
     }
 }
@@ -24,10 +27,62 @@ impl Layer for ExampleLayer {
         if event.get_event_type() != EventType::None {}
     }
 }
+struct Sandbox2D {
+    camera_controller: OrthographicCameraController,
+    rotation: f32,
+}
+
+impl Sandbox2D {
+    fn create(aspect_ratio: f32) -> Self {
+        Self {
+            camera_controller: OrthographicCameraController::create(aspect_ratio, true),
+            rotation: 0.0,
+        }
+    }
+}
+
+impl Layer for Sandbox2D {
+    fn get_name(&self) -> &'static str {
+        "Sandbox2D"
+    }
+
+    fn on_update(&mut self, window: &mut Box<dyn Window>, timestep: Timestep) {
+        self.camera_controller.on_update(timestep);
+        self.rotation += timestep.get_seconds();
+
+        Renderer2D::begin_scene(self.camera_controller.get_camera());
+        Renderer2D::draw_quad(
+            &Quad::create(Vec3::new(-1.0, 0.0, 0.0), Vec2::new(0.8, 0.8))
+                .color(Vec4::new(0.8, 0.2, 0.3, 1.0)),
+        );
+        Renderer2D::draw_quad(
+            &Quad::create(Vec3::new(0.5, -0.5, 0.0), Vec2::new(0.5, 0.75))
+                .color(Vec4::new(0.2, 0.3, 0.8, 1.0))
+                .rotation(self.rotation),
+        );
+        Renderer2D::draw_circle(
+            Vec3::new(1.2, 0.4, 0.0),
+            0.3,
+            Vec4::new(0.9, 0.8, 0.2, 1.0),
+            0.2,
+        );
+        Renderer2D::draw_rect(
+            Vec3::ZERO,
+            Vec2::new(3.0, 1.8),
+            Vec4::new(1.0, 1.0, 1.0, 0.5),
+        );
+        Renderer2D::end_scene();
+    }
+
+    fn on_event(&mut self, event: &mut Box<dyn Event>, window: &mut dyn Window) {
+        self.camera_controller.on_event(event);
+    }
+}
+
 fn main() {
     HexgemLogger::init().expect("Error occured on init logger");
     let sandbox = Sandbox {};
-    sandbox.run(|app| {
+    let result = sandbox.run(|app| {
         let layer = ExampleLayer { name: "TEST" };
         // let glfw = app.get_window().as_ref().map(|w| w.get_glfw());
 
@@ -37,5 +92,16 @@ fn main() {
         //     app.window = Some(w);
         // });
         app.push_layer(layer);
+        let aspect_ratio = app.get_window().as_ref().map_or(16.0 / 9.0, |window| {
+            Size {
+                width: window.get_width(),
+                height: window.get_height(),
+            }
+            .aspect_ratio()
+        });
+        app.push_layer(Sandbox2D::create(aspect_ratio));
     });
+    if let Err(err) = result {
+        error!("{}", err);
+    }
 }